@@ -1,9 +1,9 @@
-//! An example testing the continuity and correct mapping of the `CubeSphereGrid` projection.
+//! An example testing the continuity and correct mapping of the `RectangleSphereGrid` projection.
 
-use std::{error::Error, f64::consts::PI};
+use std::error::Error;
 
 use pixels::{SurfaceTexture, Pixels};
-use surface_grid::{sphere::{RectangleSphereGrid, RectangleSpherePoint, SpherePoint}, SurfaceGrid, GridPoint};
+use surface_grid::{render::{Colormap, Projection, Renderer}, sphere::RectangleSphereGrid, StaticSurfaceGrid, GridPoint};
 use winit::{event_loop::EventLoop, window::WindowBuilder, dpi::{LogicalSize, PhysicalSize}, event::{Event, WindowEvent}};
 
 // The initial window size.
@@ -30,7 +30,15 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let mut pixels = Pixels::new(window_size.width, window_size.height, surface_texture)?;
 
-    let buffer: RectangleSphereGrid<_, 400, 200> = RectangleSphereGrid::from_fn(|point| (point.position(1.0)));
+    // Each cell is coloured by a smooth pattern of its position in 3D space so that a seam joining
+    // the wrong cells shows up as a break in the pattern.
+    let buffer: RectangleSphereGrid<f64, 400, 200> = RectangleSphereGrid::from_fn(|point| {
+        let (x, y, z) = point.position(1.0);
+
+        (x * 6.0).sin() + (y * 6.0).sin() + (z * 6.0).sin()
+    });
+
+    let renderer = Renderer::new(Projection::Equirectangular, Colormap::Viridis);
 
     event_loop.run(move |event, target| {
         match event {
@@ -54,26 +62,7 @@ fn main() -> Result<(), Box<dyn Error>> {
                     }
                     WindowEvent::RedrawRequested => {
                         // Display the result using pixels.
-                        let frame = pixels.frame_mut();
-                
-                        for y in 0..size.height {
-                            for x in 0..size.width {
-                                let i = (y as usize * size.width as usize + x as usize) * 4;
-
-                                // Convert the X Y screen coordinates to an equirectangular
-                                // projection of the latitude and longitude.
-                                let latitude = (y as f64 / size.height as f64) * PI - PI / 2.0;
-                                let longitude = (x as f64 / size.width as f64) * PI * 2.0;
-
-                                // Gets the value stored at the latitude and longitude calculated.
-                                let (x, y, z) = buffer[RectangleSpherePoint::from_geographic(latitude, longitude)];
-
-                                frame[i + 0] = ((x + 1.0) / 2.0 * 255.0) as u8;
-                                frame[i + 1] = ((y + 1.0) / 2.0 * 255.0) as u8;
-                                frame[i + 2] = ((z + 1.0) / 2.0 * 255.0) as u8;
-                                frame[i + 3] = 255;
-                            }
-                        }
+                        renderer.render_into_par(&buffer, pixels.frame_mut(), size.width as usize, size.height as usize, |value| *value);
 
                         // Render the pixels to the screen.
                         pixels.render().expect("Failed to render");
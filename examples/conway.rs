@@ -1,10 +1,10 @@
 //! An example implementing conways game of life on the surface of a sphere.
 
-use std::{error::Error, f64::consts::PI, mem::swap, time::{Instant, Duration}};
+use std::{error::Error, mem::swap, time::{Instant, Duration}};
 
 use pixels::{SurfaceTexture, Pixels};
 use rand::{thread_rng, Rng};
-use surface_grid::{sphere::{CubeSphereGrid, CubeSpherePoint, SpherePoint}, SurfaceGrid};
+use surface_grid::{render::{Colormap, Projection, Renderer, ValueRange}, sphere::CubeSphereGrid, SurfaceGrid, StaticSurfaceGrid};
 use winit::{event_loop::{EventLoop, ControlFlow}, window::WindowBuilder, dpi::{LogicalSize, PhysicalSize}, event::{Event, WindowEvent, StartCause}};
 
 // The initial window size.
@@ -41,6 +41,10 @@ fn main() -> Result<(), Box<dyn Error>> {
     let mut buffer1: CubeSphereGrid<bool, 256> = CubeSphereGrid::from_fn(|_| rng.gen());
     let mut buffer2: CubeSphereGrid<bool, 256> = CubeSphereGrid::default();
 
+    // Live cells are drawn in white and dead cells in black.
+    let renderer = Renderer::new(Projection::Equirectangular, Colormap::Grayscale)
+        .with_range(ValueRange::Fixed(0.0, 1.0));
+
     event_loop.run(move |event, target| {
         match event {
             Event::NewEvents(StartCause::Init) => {
@@ -71,10 +75,11 @@ fn main() -> Result<(), Box<dyn Error>> {
                     }
                     WindowEvent::RedrawRequested => {
                         // Calculate conways game of life in parallel.
-                        buffer2.set_from_neighbours_diagonals_par(&buffer1, |s1, s2, s3, s4, current, s6, s7, s8, s9| {
-                            let count = [s1, s2, s3, s4, s6, s7, s8, s9]
-                                .into_iter()
-                                .filter(|s| **s)
+                        // Cells at the corners of the cube only have 7 neighbours.
+                        buffer2.set_from_neighbours_and_diagonals_par(&buffer1, |current, direct, diagonals| {
+                            let count = direct.iter()
+                                .chain(diagonals)
+                                .filter(|s| ***s)
                                 .count();
 
                             if count < 2 {
@@ -94,33 +99,13 @@ fn main() -> Result<(), Box<dyn Error>> {
                         swap(&mut buffer2, &mut buffer1);
 
                         // Display the result using pixels.
-                        let frame = pixels.frame_mut();
-                
-                        for y in 0..size.height {
-                            for x in 0..size.width {
-                                let i = (y as usize * size.width as usize + x as usize) * 4;
-
-                                // Convert the X Y screen coordinates to an equirectangular
-                                // projection of the latitude and longitude.
-                                let latitude = (y as f64 / size.height as f64) * PI - PI / 2.0;
-                                let longitude = (x as f64 / size.width as f64) * PI * 2.0;
-
-                                // Gets the value stored at the latitude and longitude calculated.
-                                let value = buffer1[CubeSpherePoint::from_geographic(latitude, longitude)];
-
-                                // Set the pixel colour.
-                                if value {
-                                    frame[i] = 255;
-                                    frame[i + 1] = 255;
-                                    frame[i + 2] = 255;
-                                } else {
-                                    frame[i] = 0;
-                                    frame[i + 1] = 0;
-                                    frame[i + 2] = 0;
-                                }
-                                frame[i + 3] = 255;
+                        renderer.render_into_par(&buffer1, pixels.frame_mut(), size.width as usize, size.height as usize, |value| {
+                            if *value {
+                                1.0
+                            } else {
+                                0.0
                             }
-                        }
+                        });
 
                         // Render the pixels to the screen.
                         pixels.render().expect("Failed to render");
@@ -0,0 +1,156 @@
+//! A module containing the policies used by grids with hard edges.
+//!
+//! Every direction on a `GridPoint` must return another point, so a grid that does not loop
+//! around in a direction uses a `Boundary` to decide where a step off the edge ends up.
+
+use std::{fmt::Debug, hash::Hash};
+
+/// A policy deciding what happens when a point steps off the edge of a bounded axis.
+pub trait Boundary: Debug + Clone + Copy + PartialEq + Eq + Hash + Default + Send + Sync {
+    /// Gets the coordinate before `position` on an axis.
+    /// Returns `None` if the step leaves the grid.
+    ///
+    /// - `position` - The current coordinate. This must be less than `size`.
+    /// - `size` - The number of cells along the axis.
+    fn previous(position: u32, size: u32) -> Option<u32>;
+
+    /// Gets the coordinate after `position` on an axis.
+    /// Returns `None` if the step leaves the grid.
+    ///
+    /// - `position` - The current coordinate. This must be less than `size`.
+    /// - `size` - The number of cells along the axis.
+    fn next(position: u32, size: u32) -> Option<u32>;
+}
+
+/// Stepping off the edge wraps around to the opposite edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Periodic;
+
+impl Boundary for Periodic {
+    fn previous(position: u32, size: u32) -> Option<u32> {
+        Some(if position == 0 {
+            size - 1
+        } else {
+            position - 1
+        })
+    }
+
+    fn next(position: u32, size: u32) -> Option<u32> {
+        Some((position + 1) % size)
+    }
+}
+
+/// Stepping off the edge stays on the edge cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Clamp;
+
+impl Boundary for Clamp {
+    fn previous(position: u32, _size: u32) -> Option<u32> {
+        Some(position.saturating_sub(1))
+    }
+
+    fn next(position: u32, size: u32) -> Option<u32> {
+        Some((position + 1).min(size - 1))
+    }
+}
+
+/// Stepping off the edge mirrors back into the grid without repeating the edge cell, so the
+/// neighbour above the top row is the second row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Reflect;
+
+impl Boundary for Reflect {
+    fn previous(position: u32, size: u32) -> Option<u32> {
+        if position == 0 {
+            Some(1.min(size - 1))
+        } else {
+            Some(position - 1)
+        }
+    }
+
+    fn next(position: u32, size: u32) -> Option<u32> {
+        if position + 1 >= size {
+            Some(position.saturating_sub(1))
+        } else {
+            Some(position + 1)
+        }
+    }
+}
+
+/// Stepping off the edge leaves the grid.
+/// The grid holds a single constant value which is returned for every point outside it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Constant;
+
+impl Boundary for Constant {
+    fn previous(position: u32, _size: u32) -> Option<u32> {
+        position.checked_sub(1)
+    }
+
+    fn next(position: u32, size: u32) -> Option<u32> {
+        if position + 1 >= size {
+            None
+        } else {
+            Some(position + 1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Boundary, Clamp, Constant, Periodic, Reflect};
+
+    #[test]
+    fn test_periodic_middle() {
+        assert_eq!(Some(3), Periodic::previous(4, 10));
+        assert_eq!(Some(5), Periodic::next(4, 10));
+    }
+
+    #[test]
+    fn test_periodic_edges() {
+        assert_eq!(Some(9), Periodic::previous(0, 10));
+        assert_eq!(Some(0), Periodic::next(9, 10));
+    }
+
+    #[test]
+    fn test_clamp_middle() {
+        assert_eq!(Some(3), Clamp::previous(4, 10));
+        assert_eq!(Some(5), Clamp::next(4, 10));
+    }
+
+    #[test]
+    fn test_clamp_edges() {
+        assert_eq!(Some(0), Clamp::previous(0, 10));
+        assert_eq!(Some(9), Clamp::next(9, 10));
+    }
+
+    #[test]
+    fn test_reflect_middle() {
+        assert_eq!(Some(3), Reflect::previous(4, 10));
+        assert_eq!(Some(5), Reflect::next(4, 10));
+    }
+
+    #[test]
+    fn test_reflect_edges() {
+        assert_eq!(Some(1), Reflect::previous(0, 10));
+        assert_eq!(Some(8), Reflect::next(9, 10));
+    }
+
+    #[test]
+    fn test_reflect_single_cell() {
+        assert_eq!(Some(0), Reflect::previous(0, 1));
+        assert_eq!(Some(0), Reflect::next(0, 1));
+    }
+
+    #[test]
+    fn test_constant_middle() {
+        assert_eq!(Some(3), Constant::previous(4, 10));
+        assert_eq!(Some(5), Constant::next(4, 10));
+    }
+
+    #[test]
+    fn test_constant_edges() {
+        assert_eq!(None, Constant::previous(0, 10));
+        assert_eq!(None, Constant::next(9, 10));
+    }
+}
@@ -0,0 +1,888 @@
+//! A module containing grids wrapped around cylinders.
+
+use std::{f64::consts::PI, marker::PhantomData, ops::{Index, IndexMut}, vec};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use static_array::HeapArray2D;
+
+use crate::{boundary::{Boundary, Clamp}, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+/// A grid wrapped around the side of an open cylinder.
+///
+/// The grid wraps horizontally around the cylinder while the top and bottom edges are handled by
+/// the boundary policy `B`.
+/// When the policy is `Constant`, stepping off the top or bottom edge gives a point outside the
+/// grid which always holds the value set with `set_outside`.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+/// - `B` - The boundary policy for the top and bottom edges.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid. This is the number of cells around the cylinder.
+/// - `H` - The height of the grid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CylinderGrid<T, const W: usize, const H: usize, B: Boundary = Clamp> {
+    /// The data held in this grid.
+    data: HeapArray2D<T, W, H>,
+    /// The value of every point outside the grid.
+    outside: T,
+    boundary: PhantomData<B>,
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> CylinderGrid<T, W, H, B> {
+    /// Creates a new grid from a function of each point with the specified value outside the grid.
+    ///
+    /// - `outside` - The value of every point outside the grid.
+    /// - `f` - The function to create each value in the grid.
+    pub fn from_fn_with_outside<F: FnMut(&CylinderPoint<W, H, B>) -> T>(outside: T, mut f: F) -> Self {
+        Self {
+            data: HeapArray2D::from_fn(|y, x| f(&CylinderPoint::new(x as u32, y as u32))),
+            outside,
+            boundary: PhantomData,
+        }
+    }
+
+    /// Creates a new grid from a function of each point with the specified value outside the grid
+    /// computing each value in parallel.
+    ///
+    /// - `outside` - The value of every point outside the grid.
+    /// - `f` - The function to create each value in the grid.
+    pub fn from_fn_par_with_outside<F: Fn(&CylinderPoint<W, H, B>) -> T + Send + Sync>(outside: T, f: F) -> Self where T: Send + Sync {
+        Self {
+            data: HeapArray2D::from_fn_par(|y, x| f(&CylinderPoint::new(x as u32, y as u32))),
+            outside,
+            boundary: PhantomData,
+        }
+    }
+
+    /// Gets the value of every point outside the grid.
+    pub fn outside(&self) -> &T {
+        &self.outside
+    }
+
+    /// Sets the value of every point outside the grid.
+    ///
+    /// - `value` - The new value outside the grid.
+    pub fn set_outside(&mut self, value: T) {
+        self.outside = value;
+    }
+}
+
+impl <T: Clone, const W: usize, const H: usize, B: Boundary> SurfaceGrid<T> for CylinderGrid<T, W, H, B> {
+    type Point = CylinderPoint<W, H, B>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn_with_outside(self.outside.clone(), f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par_with_outside(self.outside.clone(), f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| CylinderPoint::new(x as u32, y as u32))
+            .for_each(|point| self[point] = f(&point))
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        self.data.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+            for (x, value) in subarray.iter_mut().enumerate() {
+                *value = f(&CylinderPoint::new(x as u32, y as u32));
+            }
+        })
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| (CylinderPoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| (CylinderPoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| CylinderPoint::new(x as u32, y as u32))
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| CylinderPoint::new(x as u32, y as u32))
+    }
+}
+
+impl <T: Clone + Default, const W: usize, const H: usize, B: Boundary> StaticSurfaceGrid<T> for CylinderGrid<T, W, H, B> {
+    /// Creates a new grid from a function of each point.
+    /// The value outside the grid is the default value of `T`.
+    ///
+    /// - `f` - The function to create each value in the grid.
+    fn from_fn<F: FnMut(&Self::Point) -> T>(f: F) -> Self {
+        Self::from_fn_with_outside(T::default(), f)
+    }
+
+    /// Creates a new grid from a function of each point computing each value in parallel.
+    /// The value outside the grid is the default value of `T`.
+    ///
+    /// - `f` - The function to create each value in the grid.
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par_with_outside(T::default(), f)
+    }
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> Index<CylinderPoint<W, H, B>> for CylinderGrid<T, W, H, B> {
+    type Output = T;
+
+    fn index(&self, index: CylinderPoint<W, H, B>) -> &Self::Output {
+        if index.outside {
+            &self.outside
+        } else {
+            &self.data[index.y as usize][index.x as usize]
+        }
+    }
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> IndexMut<CylinderPoint<W, H, B>> for CylinderGrid<T, W, H, B> {
+    fn index_mut(&mut self, index: CylinderPoint<W, H, B>) -> &mut Self::Output {
+        if index.outside {
+            &mut self.outside
+        } else {
+            &mut self.data[index.y as usize][index.x as usize]
+        }
+    }
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> IntoIterator for CylinderGrid<T, W, H, B> {
+    type Item = (CylinderPoint<W, H, B>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let data: Vec<_> = self.data.into_iter()
+            .enumerate()
+            .flat_map(|(y, subarray)| subarray.into_iter()
+                      .enumerate()
+                      .map(move |(x, value)| (CylinderPoint::new(x as u32, y as u32), value))
+                      )
+            .collect();
+
+        data.into_iter()
+    }
+}
+
+/// A point on a `CylinderGrid`.
+///
+/// Stepping off the top or bottom edge is resolved by the boundary policy `B`.
+/// Points outside the grid stay outside the grid in every direction.
+///
+/// # Type Parameters
+/// - `B` - The boundary policy for the top and bottom edges.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid.
+/// - `H` - The height of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CylinderPoint<const W: usize, const H: usize, B: Boundary = Clamp> {
+    /// The X position in the grid.
+    x: u32,
+    /// The Y position in the grid.
+    y: u32,
+    /// Whether the point is outside the grid.
+    outside: bool,
+    boundary: PhantomData<B>,
+}
+
+impl <const W: usize, const H: usize, B: Boundary> CylinderPoint<W, H, B> {
+    fn new(x: u32, y: u32) -> Self {
+        Self {
+            x: x.rem_euclid(W as u32),
+            y: y.min(H as u32 - 1),
+            outside: false,
+            boundary: PhantomData,
+        }
+    }
+
+    fn new_outside() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            outside: true,
+            boundary: PhantomData,
+        }
+    }
+
+    /// Checks whether the point is outside the grid.
+    pub fn is_outside(&self) -> bool {
+        self.outside
+    }
+
+    /// Gets the position of the centre of the cell in 3D space on a cylinder with the specified
+    /// dimensions.
+    ///
+    /// The central axis of the cylinder is the Y axis and the cylinder is centred on the origin.
+    /// Points outside the grid are placed at the origin.
+    ///
+    /// - `radius` - The radius of the cylinder.
+    /// - `height` - The height of the cylinder.
+    pub fn cylinder_position(&self, radius: f64, height: f64) -> (f64, f64, f64) {
+        if self.outside {
+            return (0.0, 0.0, 0.0);
+        }
+
+        let angle = (self.x as f64 + 0.5) / W as f64 * PI * 2.0;
+
+        let x = radius * angle.sin();
+        let y = height / 2.0 - (self.y as f64 + 0.5) / H as f64 * height;
+        let z = radius * angle.cos();
+
+        (x, y, z)
+    }
+
+    fn step(&self, y: Option<u32>) -> Self {
+        match y {
+            Some(y) => Self {
+                y,
+                ..*self
+            },
+            None => Self::new_outside(),
+        }
+    }
+}
+
+impl <const W: usize, const H: usize, B: Boundary> GridPoint for CylinderPoint<W, H, B> {
+    fn up(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        self.step(B::previous(self.y, H as u32))
+    }
+
+    fn down(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        self.step(B::next(self.y, H as u32))
+    }
+
+    fn left(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        Self {
+            x: (self.x as i64 - 1).rem_euclid(W as i64) as u32,
+            ..*self
+        }
+    }
+
+    fn right(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        Self {
+            x: (self.x + 1).rem_euclid(W as u32),
+            ..*self
+        }
+    }
+
+    /// Gets the position of the point in 3D space.
+    ///
+    /// The point is placed on a cylinder with a radius of `scale` and a height chosen so that each
+    /// cell is square. Use `cylinder_position` to choose the dimensions separately.
+    ///
+    /// - `scale` - The scale of the 3D object.
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        self.cylinder_position(scale, scale * PI * 2.0 * H as f64 / W as f64)
+    }
+}
+
+impl <const W: usize, const H: usize, B: Boundary> IndexedPoint for CylinderPoint<W, H, B> {
+    const COUNT: usize = W * H;
+
+    fn to_index(&self) -> usize {
+        assert!(!self.outside, "The point is outside the grid");
+
+        self.y as usize * W + self.x as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        Self::new((index % W) as u32, (index / W) as u32)
+    }
+}
+
+/// A grid wrapped around a closed cylinder with square end caps.
+///
+/// The side of the cylinder is a grid `4 * S` cells wide which wraps horizontally.
+/// Each end of the cylinder is capped with an `S` by `S` grid whose edges are stitched to the top
+/// and bottom rows of the side, a quarter of the side per edge.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+///
+/// # Constant Parameters
+/// - `S` - The size of each side of the end caps.
+/// - `H` - The height of the side of the cylinder.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct CappedCylinderGrid<T, const S: usize, const H: usize> {
+    top: HeapArray2D<T, S, S>,
+    side: [HeapArray2D<T, S, H>; 4],
+    bottom: HeapArray2D<T, S, S>,
+}
+
+impl <T, const S: usize, const H: usize> SurfaceGrid<T> for CappedCylinderGrid<T, S, H> {
+    type Point = CappedCylinderPoint<S, H>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        for point in CappedCylinderPoint::all() {
+            self[point] = f(&point);
+        }
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        self.top.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+            for (x, value) in subarray.iter_mut().enumerate() {
+                *value = f(&CappedCylinderPoint::new(CylinderSection::Top, x as u32, y as u32));
+            }
+        });
+
+        for (quarter, side) in self.side.iter_mut().enumerate() {
+            side.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+                for (x, value) in subarray.iter_mut().enumerate() {
+                    *value = f(&CappedCylinderPoint::new(CylinderSection::Side, (quarter * S + x) as u32, y as u32));
+                }
+            });
+        }
+
+        self.bottom.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+            for (x, value) in subarray.iter_mut().enumerate() {
+                *value = f(&CappedCylinderPoint::new(CylinderSection::Bottom, x as u32, y as u32));
+            }
+        });
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        self.points()
+            .map(|point| (point, &self[point]))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        self.par_points()
+            .map(|point| (point, &self[point]))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        CappedCylinderPoint::all()
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        CappedCylinderPoint::all().par_bridge()
+    }
+}
+
+impl <T, const S: usize, const H: usize> StaticSurfaceGrid<T> for CappedCylinderGrid<T, S, H> {
+    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
+        Self {
+            top: HeapArray2D::from_fn(|y, x| f(&CappedCylinderPoint::new(CylinderSection::Top, x as u32, y as u32))),
+            side: std::array::from_fn(|quarter| HeapArray2D::from_fn(|y, x| f(&CappedCylinderPoint::new(CylinderSection::Side, (quarter * S + x) as u32, y as u32)))),
+            bottom: HeapArray2D::from_fn(|y, x| f(&CappedCylinderPoint::new(CylinderSection::Bottom, x as u32, y as u32))),
+        }
+    }
+
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self {
+            top: HeapArray2D::from_fn_par(|y, x| f(&CappedCylinderPoint::new(CylinderSection::Top, x as u32, y as u32))),
+            side: std::array::from_fn(|quarter| HeapArray2D::from_fn_par(|y, x| f(&CappedCylinderPoint::new(CylinderSection::Side, (quarter * S + x) as u32, y as u32)))),
+            bottom: HeapArray2D::from_fn_par(|y, x| f(&CappedCylinderPoint::new(CylinderSection::Bottom, x as u32, y as u32))),
+        }
+    }
+}
+
+impl <T, const S: usize, const H: usize> Index<CappedCylinderPoint<S, H>> for CappedCylinderGrid<T, S, H> {
+    type Output = T;
+
+    fn index(&self, index: CappedCylinderPoint<S, H>) -> &Self::Output {
+        let x = index.x as usize;
+        let y = index.y as usize;
+
+        match index.section {
+            CylinderSection::Top => &self.top[y][x],
+            CylinderSection::Side => &self.side[x / S][y][x % S],
+            CylinderSection::Bottom => &self.bottom[y][x],
+        }
+    }
+}
+
+impl <T, const S: usize, const H: usize> IndexMut<CappedCylinderPoint<S, H>> for CappedCylinderGrid<T, S, H> {
+    fn index_mut(&mut self, index: CappedCylinderPoint<S, H>) -> &mut Self::Output {
+        let x = index.x as usize;
+        let y = index.y as usize;
+
+        match index.section {
+            CylinderSection::Top => &mut self.top[y][x],
+            CylinderSection::Side => &mut self.side[x / S][y][x % S],
+            CylinderSection::Bottom => &mut self.bottom[y][x],
+        }
+    }
+}
+
+impl <T, const S: usize, const H: usize> IntoIterator for CappedCylinderGrid<T, S, H> {
+    type Item = (CappedCylinderPoint<S, H>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut data: Vec<_> = self.top.into_iter()
+            .enumerate()
+            .flat_map(|(y, subarray)| subarray.into_iter()
+                      .enumerate()
+                      .map(move |(x, value)| (CappedCylinderPoint::new(CylinderSection::Top, x as u32, y as u32), value))
+                      )
+            .collect();
+
+        let mut side: Vec<_> = self.side.into_iter()
+            .enumerate()
+            .flat_map(|(quarter, side)| side.into_iter()
+                      .enumerate()
+                      .flat_map(move |(y, subarray)| subarray.into_iter()
+                                .enumerate()
+                                .map(move |(x, value)| (CappedCylinderPoint::new(CylinderSection::Side, (quarter * S + x) as u32, y as u32), value))
+                                ))
+            .collect();
+        side.sort_by_key(|(point, _)| (point.y, point.x));
+
+        data.extend(side);
+        data.extend(self.bottom.into_iter()
+                    .enumerate()
+                    .flat_map(|(y, subarray)| subarray.into_iter()
+                              .enumerate()
+                              .map(move |(x, value)| (CappedCylinderPoint::new(CylinderSection::Bottom, x as u32, y as u32), value))
+                              ));
+
+        data.into_iter()
+    }
+}
+
+/// A section of a capped cylinder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum CylinderSection {
+    Top,
+    Side,
+    Bottom,
+}
+
+/// A point on a `CappedCylinderGrid`.
+///
+/// # Constant Parameters
+/// - `S` - The size of each side of the end caps.
+/// - `H` - The height of the side of the cylinder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CappedCylinderPoint<const S: usize, const H: usize> {
+    /// The section of the cylinder that the point is on.
+    section: CylinderSection,
+    /// The X position in the section.
+    x: u32,
+    /// The Y position in the section.
+    y: u32,
+}
+
+impl <const S: usize, const H: usize> CappedCylinderPoint<S, H> {
+    fn new(section: CylinderSection, x: u32, y: u32) -> Self {
+        let (width, height) = match section {
+            CylinderSection::Side => (4 * S as u32, H as u32),
+            CylinderSection::Top | CylinderSection::Bottom => (S as u32, S as u32),
+        };
+
+        Self {
+            section,
+            x: x.min(width - 1),
+            y: y.min(height - 1),
+        }
+    }
+
+    /// Iterates over every point on the cylinder.
+    /// The top cap comes first, followed by the side and then the bottom cap, each in row order.
+    fn all() -> impl Iterator<Item = Self> {
+        let caps = (0..S).cartesian_product(0..S);
+        let side = (0..H).cartesian_product(0..4 * S);
+
+        caps.clone().map(|(y, x)| Self::new(CylinderSection::Top, x as u32, y as u32))
+            .chain(side.map(|(y, x)| Self::new(CylinderSection::Side, x as u32, y as u32)))
+            .chain(caps.map(|(y, x)| Self::new(CylinderSection::Bottom, x as u32, y as u32)))
+    }
+
+    /// Gets the point on a cap which is stitched to the specified column of the side.
+    ///
+    /// - `section` - The cap to get the point on.
+    /// - `x` - The column of the side.
+    fn cap_from_side(section: CylinderSection, x: u32) -> Self {
+        let size = S as u32;
+        let offset = x % size;
+
+        match x / size {
+            0 => Self::new(section, offset, size - 1),
+            1 => Self::new(section, size - 1, size - 1 - offset),
+            2 => Self::new(section, size - 1 - offset, 0),
+            _ => Self::new(section, 0, offset),
+        }
+    }
+
+    /// Gets the row of the side which is stitched to the cap this point is on.
+    fn side_row(&self) -> u32 {
+        match self.section {
+            CylinderSection::Bottom => H as u32 - 1,
+            _ => 0,
+        }
+    }
+
+    /// Gets the position of the centre of the cell in 3D space on a capped cylinder with the
+    /// specified dimensions.
+    ///
+    /// The central axis of the cylinder is the Y axis and the cylinder is centred on the origin.
+    /// The square caps are stretched over the circular ends of the cylinder.
+    ///
+    /// - `radius` - The radius of the cylinder.
+    /// - `height` - The height of the side of the cylinder.
+    pub fn cylinder_position(&self, radius: f64, height: f64) -> (f64, f64, f64) {
+        let size = S as f64;
+
+        match self.section {
+            CylinderSection::Side => {
+                let s = (2.0 * (self.x % S as u32) as f64 + 1.0) / size - 1.0;
+
+                let (x, z) = match self.x / S as u32 {
+                    0 => (s, 1.0),
+                    1 => (1.0, -s),
+                    2 => (-s, -1.0),
+                    _ => (-1.0, s),
+                };
+                let length = (x * x + z * z).sqrt();
+
+                let y = height / 2.0 - (self.y as f64 + 0.5) / H as f64 * height;
+
+                (radius * x / length, y, radius * z / length)
+            },
+            CylinderSection::Top | CylinderSection::Bottom => {
+                let u = (2.0 * self.x as f64 + 1.0) / size - 1.0;
+                let v = (2.0 * self.y as f64 + 1.0) / size - 1.0;
+
+                let length = (u * u + v * v).sqrt();
+                let stretch = if length == 0.0 {
+                    0.0
+                } else {
+                    u.abs().max(v.abs()) / length
+                };
+
+                let y = if self.section == CylinderSection::Top {
+                    height / 2.0
+                } else {
+                    -height / 2.0
+                };
+
+                (radius * u * stretch, y, radius * v * stretch)
+            },
+        }
+    }
+}
+
+impl <const S: usize, const H: usize> GridPoint for CappedCylinderPoint<S, H> {
+    fn up(&self) -> Self {
+        match self.section {
+            CylinderSection::Side => if self.y == 0 {
+                Self::cap_from_side(CylinderSection::Top, self.x)
+            } else {
+                Self::new(CylinderSection::Side, self.x, self.y - 1)
+            },
+            _ => if self.y == 0 {
+                Self::new(CylinderSection::Side, 3 * S as u32 - 1 - self.x, self.side_row())
+            } else {
+                Self::new(self.section, self.x, self.y - 1)
+            },
+        }
+    }
+
+    fn down(&self) -> Self {
+        match self.section {
+            CylinderSection::Side => if self.y == H as u32 - 1 {
+                Self::cap_from_side(CylinderSection::Bottom, self.x)
+            } else {
+                Self::new(CylinderSection::Side, self.x, self.y + 1)
+            },
+            _ => if self.y == S as u32 - 1 {
+                Self::new(CylinderSection::Side, self.x, self.side_row())
+            } else {
+                Self::new(self.section, self.x, self.y + 1)
+            },
+        }
+    }
+
+    fn left(&self) -> Self {
+        match self.section {
+            CylinderSection::Side => Self::new(CylinderSection::Side, (self.x as i64 - 1).rem_euclid(4 * S as i64) as u32, self.y),
+            _ => if self.x == 0 {
+                Self::new(CylinderSection::Side, 3 * S as u32 + self.y, self.side_row())
+            } else {
+                Self::new(self.section, self.x - 1, self.y)
+            },
+        }
+    }
+
+    fn right(&self) -> Self {
+        match self.section {
+            CylinderSection::Side => Self::new(CylinderSection::Side, (self.x + 1).rem_euclid(4 * S as u32), self.y),
+            _ => if self.x == S as u32 - 1 {
+                Self::new(CylinderSection::Side, 2 * S as u32 - 1 - self.y, self.side_row())
+            } else {
+                Self::new(self.section, self.x + 1, self.y)
+            },
+        }
+    }
+
+    /// Gets the position of the point in 3D space.
+    ///
+    /// The point is placed on a cylinder with a radius of `scale` and a height chosen so that each
+    /// cell on the side is square. Use `cylinder_position` to choose the dimensions separately.
+    ///
+    /// - `scale` - The scale of the 3D object.
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        self.cylinder_position(scale, scale * PI * 2.0 * H as f64 / (4 * S) as f64)
+    }
+}
+
+impl <const S: usize, const H: usize> IndexedPoint for CappedCylinderPoint<S, H> {
+    const COUNT: usize = 2 * S * S + 4 * S * H;
+
+    fn to_index(&self) -> usize {
+        let x = self.x as usize;
+        let y = self.y as usize;
+
+        match self.section {
+            CylinderSection::Top => y * S + x,
+            CylinderSection::Side => S * S + y * 4 * S + x,
+            CylinderSection::Bottom => S * S + 4 * S * H + y * S + x,
+        }
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        let side = 4 * S * H;
+
+        if index < S * S {
+            Self::new(CylinderSection::Top, (index % S) as u32, (index / S) as u32)
+        } else if index < S * S + side {
+            let index = index - S * S;
+
+            Self::new(CylinderSection::Side, (index % (4 * S)) as u32, (index / (4 * S)) as u32)
+        } else {
+            let index = index - S * S - side;
+
+            Self::new(CylinderSection::Bottom, (index % S) as u32, (index / S) as u32)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{boundary::{Clamp, Constant, Reflect}, test::assert_indexed_points, GridPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    use super::{CappedCylinderGrid, CappedCylinderPoint, CylinderGrid, CylinderPoint, CylinderSection};
+
+    #[test]
+    fn test_cylinder_point_left_wraps() {
+        let point: CylinderPoint<10, 5> = CylinderPoint::new(0, 2);
+
+        assert_eq!(CylinderPoint::new(9, 2), point.left());
+    }
+
+    #[test]
+    fn test_cylinder_point_right_wraps() {
+        let point: CylinderPoint<10, 5> = CylinderPoint::new(9, 2);
+
+        assert_eq!(CylinderPoint::new(0, 2), point.right());
+    }
+
+    #[test]
+    fn test_cylinder_point_up_middle() {
+        let point: CylinderPoint<10, 5> = CylinderPoint::new(3, 2);
+
+        assert_eq!(CylinderPoint::new(3, 1), point.up());
+    }
+
+    #[test]
+    fn test_cylinder_point_up_clamp() {
+        let point: CylinderPoint<10, 5, Clamp> = CylinderPoint::new(3, 0);
+
+        assert_eq!(point, point.up());
+    }
+
+    #[test]
+    fn test_cylinder_point_down_clamp() {
+        let point: CylinderPoint<10, 5, Clamp> = CylinderPoint::new(3, 4);
+
+        assert_eq!(point, point.down());
+    }
+
+    #[test]
+    fn test_cylinder_point_up_reflect() {
+        let point: CylinderPoint<10, 5, Reflect> = CylinderPoint::new(3, 0);
+
+        assert_eq!(CylinderPoint::new(3, 1), point.up());
+    }
+
+    #[test]
+    fn test_cylinder_point_down_reflect() {
+        let point: CylinderPoint<10, 5, Reflect> = CylinderPoint::new(3, 4);
+
+        assert_eq!(CylinderPoint::new(3, 3), point.down());
+    }
+
+    #[test]
+    fn test_cylinder_point_up_constant() {
+        let point: CylinderPoint<10, 5, Constant> = CylinderPoint::new(3, 0);
+
+        assert!(point.up().is_outside());
+        assert!(point.up().left().down().is_outside());
+    }
+
+    #[test]
+    fn test_cylinder_from_neighbours_constant() {
+        let mut grid: CylinderGrid<u32, 10, 5, Constant> = CylinderGrid::from_fn(|_| 1);
+        grid.set_outside(100);
+
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert_eq!(5, grid2[CylinderPoint::new(3, 2)]);
+        assert_eq!(104, grid2[CylinderPoint::new(3, 0)]);
+        assert_eq!(100, *grid2.outside());
+    }
+
+    #[test]
+    fn test_cylinder_from_neighbours_reflect() {
+        let grid: CylinderGrid<u32, 10, 5, Reflect> = CylinderGrid::from_fn(|point| point.y);
+
+        let grid2 = grid.map_neighbours(|_, up, _, _, _| *up);
+
+        assert_eq!(1, grid2[CylinderPoint::new(3, 0)]);
+        assert_eq!(2, grid2[CylinderPoint::new(3, 3)]);
+    }
+
+    #[test]
+    fn test_cylinder_position() {
+        let point: CylinderPoint<4, 2> = CylinderPoint::new(0, 0);
+
+        let (x, y, z) = point.cylinder_position(2.0, 4.0);
+
+        assert_relative_eq!(2.0 * 0.5_f64.sqrt(), x);
+        assert_relative_eq!(1.0, y);
+        assert_relative_eq!(2.0 * 0.5_f64.sqrt(), z);
+    }
+
+    #[test]
+    fn test_capped_point_up_top_edge() {
+        let point: CappedCylinderPoint<4, 3> = CappedCylinderPoint::new(CylinderSection::Side, 1, 0);
+
+        assert_eq!(CappedCylinderPoint::new(CylinderSection::Top, 1, 3), point.up());
+    }
+
+    #[test]
+    fn test_capped_point_down_bottom_edge() {
+        let point: CappedCylinderPoint<4, 3> = CappedCylinderPoint::new(CylinderSection::Side, 9, 2);
+
+        assert_eq!(CappedCylinderPoint::new(CylinderSection::Bottom, 2, 0), point.down());
+    }
+
+    #[test]
+    fn test_capped_point_cap_right() {
+        let point: CappedCylinderPoint<4, 3> = CappedCylinderPoint::new(CylinderSection::Top, 3, 1);
+
+        assert_eq!(CappedCylinderPoint::new(CylinderSection::Side, 6, 0), point.right());
+    }
+
+    #[test]
+    fn test_capped_point_side_wraps() {
+        let point: CappedCylinderPoint<4, 3> = CappedCylinderPoint::new(CylinderSection::Side, 15, 1);
+
+        assert_eq!(CappedCylinderPoint::new(CylinderSection::Side, 0, 1), point.right());
+    }
+
+    #[test]
+    fn test_capped_point_neighbours_symmetric() {
+        for point in CappedCylinderPoint::<4, 3>::all() {
+            for neighbour in [point.up(), point.down(), point.left(), point.right()] {
+                assert!([neighbour.up(), neighbour.down(), neighbour.left(), neighbour.right()].contains(&point),
+                    "{:?} is not a neighbour of {:?}", point, neighbour);
+            }
+        }
+    }
+
+    #[test]
+    fn test_capped_point_neighbours_adjacent() {
+        for point in CappedCylinderPoint::<4, 3>::all() {
+            let (x, y, z) = point.position(1.0);
+
+            for neighbour in [point.up(), point.down(), point.left(), point.right()] {
+                let (nx, ny, nz) = neighbour.position(1.0);
+                let distance = ((x - nx).powi(2) + (y - ny).powi(2) + (z - nz).powi(2)).sqrt();
+
+                assert!(distance < 1.0, "{:?} is too far from {:?}", point, neighbour);
+            }
+        }
+    }
+
+    #[test]
+    fn test_capped_from_fn() {
+        let grid: CappedCylinderGrid<u32, 4, 3> = CappedCylinderGrid::from_fn(|point| point.x);
+
+        assert_eq!(13, grid[CappedCylinderPoint::new(CylinderSection::Side, 13, 2)]);
+        assert_eq!(2, grid[CappedCylinderPoint::new(CylinderSection::Bottom, 2, 1)]);
+    }
+
+    #[test]
+    fn test_capped_from_fn_par() {
+        let grid: CappedCylinderGrid<u32, 4, 3> = CappedCylinderGrid::from_fn(|point| point.x + point.y);
+        let grid2: CappedCylinderGrid<u32, 4, 3> = CappedCylinderGrid::from_fn_par(|point| point.x + point.y);
+
+        assert_eq!(grid, grid2);
+    }
+
+    #[test]
+    fn test_capped_into_iter_matches_points() {
+        let grid: CappedCylinderGrid<u32, 4, 3> = CappedCylinderGrid::from_fn(|point| point.x);
+
+        let points: Vec<_> = grid.points().collect();
+        let values: Vec<_> = grid.into_iter().map(|(point, _)| point).collect();
+
+        assert_eq!(points, values);
+    }
+
+    #[test]
+    fn test_cylinder_indexed_points() {
+        assert_indexed_points(&CylinderGrid::<(), 7, 5>::default());
+    }
+
+    #[test]
+    fn test_capped_cylinder_indexed_points() {
+        assert_indexed_points(&CappedCylinderGrid::<(), 3, 4>::default());
+    }
+}
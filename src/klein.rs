@@ -0,0 +1,367 @@
+//! A module containing grids wrapped around Klein bottles.
+
+use std::{f64::consts::PI, hash::{Hash, Hasher}, ops::{Index, IndexMut}, vec};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use static_array::HeapArray2D;
+
+use crate::{GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+/// A grid wrapped around a Klein bottle.
+///
+/// The grid wraps vertically like a torus, but wraps horizontally with a reflection, so stepping
+/// off the left or right edge comes back in on the opposite edge upside down.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid. This is the number of cells along the bottle.
+/// - `H` - The height of the grid. This is the number of cells around the tube of the bottle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct KleinGrid<T, const W: usize, const H: usize> {
+    /// The data held in this grid.
+    data: HeapArray2D<T, W, H>,
+}
+
+impl <T, const W: usize, const H: usize> SurfaceGrid<T> for KleinGrid<T, W, H> {
+    type Point = KleinPoint<W, H>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| KleinPoint::new(x as u32, y as u32))
+            .for_each(|point| self[point] = f(&point))
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        self.data.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+            for (x, value) in subarray.iter_mut().enumerate() {
+                *value = f(&KleinPoint::new(x as u32, y as u32));
+            }
+        })
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| (KleinPoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| (KleinPoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| KleinPoint::new(x as u32, y as u32))
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| KleinPoint::new(x as u32, y as u32))
+    }
+}
+
+impl <T, const W: usize, const H: usize> StaticSurfaceGrid<T> for KleinGrid<T, W, H> {
+    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
+        Self {
+            data: HeapArray2D::from_fn(|y, x| f(&KleinPoint::new(x as u32, y as u32)))
+        }
+    }
+
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self {
+            data: HeapArray2D::from_fn_par(|y, x| f(&KleinPoint::new(x as u32, y as u32)))
+        }
+    }
+}
+
+impl <T, const W: usize, const H: usize> Index<KleinPoint<W, H>> for KleinGrid<T, W, H> {
+    type Output = T;
+
+    fn index(&self, index: KleinPoint<W, H>) -> &Self::Output {
+        &self.data[index.y as usize][index.x as usize]
+    }
+}
+
+impl <T, const W: usize, const H: usize> IndexMut<KleinPoint<W, H>> for KleinGrid<T, W, H> {
+    fn index_mut(&mut self, index: KleinPoint<W, H>) -> &mut Self::Output {
+        &mut self.data[index.y as usize][index.x as usize]
+    }
+}
+
+impl <T, const W: usize, const H: usize> IntoIterator for KleinGrid<T, W, H> {
+    type Item = (KleinPoint<W, H>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let data: Vec<_> = self.data.into_iter()
+            .enumerate()
+            .flat_map(|(y, subarray)| subarray.into_iter()
+                      .enumerate()
+                      .map(move |(x, value)| (KleinPoint::new(x as u32, y as u32), value))
+                      )
+            .collect();
+
+        data.into_iter()
+    }
+}
+
+/// A point on a `KleinGrid`.
+///
+/// Crossing the reflected seam flips the orientation of the point so that `up` and `down` are
+/// swapped relative to the grid, keeping walks across the seam continuous.
+/// Points compare equal when they refer to the same cell regardless of their orientation.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid.
+/// - `H` - The height of the grid.
+#[derive(Debug, Clone, Copy)]
+pub struct KleinPoint<const W: usize, const H: usize> {
+    /// The X position in the grid.
+    x: u32,
+    /// The Y position in the grid.
+    y: u32,
+    /// Whether the point is upside down relative to the grid.
+    flipped: bool,
+}
+
+impl <const W: usize, const H: usize> PartialEq for KleinPoint<W, H> {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x && self.y == other.y
+    }
+}
+
+impl <const W: usize, const H: usize> Eq for KleinPoint<W, H> {}
+
+impl <const W: usize, const H: usize> Hash for KleinPoint<W, H> {
+    fn hash<Hs: Hasher>(&self, state: &mut Hs) {
+        self.x.hash(state);
+        self.y.hash(state);
+    }
+}
+
+impl <const W: usize, const H: usize> KleinPoint<W, H> {
+    fn new(x: u32, y: u32) -> Self {
+        Self {
+            x: x.rem_euclid(W as u32),
+            y: y.rem_euclid(H as u32),
+            flipped: false,
+        }
+    }
+
+    /// Checks whether the point is upside down relative to the grid after crossing the seam an
+    /// odd number of times.
+    pub fn is_flipped(&self) -> bool {
+        self.flipped
+    }
+
+    /// Gets the position of the point in 3D space on the figure-eight immersion of a Klein bottle
+    /// with the specified radii.
+    ///
+    /// The bottle circles the Y axis and passes through itself along a circle around the axis.
+    ///
+    /// - `major_radius` - The distance from the Y axis to the centre of the tube.
+    /// - `minor_radius` - The radius of each loop of the figure-eight cross section.
+    pub fn klein_position(&self, major_radius: f64, minor_radius: f64) -> (f64, f64, f64) {
+        let around_axis = (self.x as f64 + 0.5) / W as f64 * PI * 2.0;
+        let around_tube = (self.y as f64 + 0.5) / H as f64 * PI * 2.0;
+
+        let (half_sin, half_cos) = (around_axis / 2.0).sin_cos();
+
+        let radius = major_radius
+            + minor_radius * (half_cos * around_tube.sin() - half_sin * (2.0 * around_tube).sin());
+
+        let x = radius * around_axis.sin();
+        let y = minor_radius * (half_sin * around_tube.sin() + half_cos * (2.0 * around_tube).sin());
+        let z = radius * around_axis.cos();
+
+        (x, y, z)
+    }
+
+    fn across_seam(&self, x: u32) -> Self {
+        Self {
+            x,
+            y: H as u32 - 1 - self.y,
+            flipped: !self.flipped,
+        }
+    }
+
+    fn previous_y(&self) -> u32 {
+        (self.y as i64 - 1).rem_euclid(H as i64) as u32
+    }
+
+    fn next_y(&self) -> u32 {
+        (self.y + 1).rem_euclid(H as u32)
+    }
+}
+
+impl <const W: usize, const H: usize> GridPoint for KleinPoint<W, H> {
+    fn up(&self) -> Self {
+        Self {
+            y: if self.flipped { self.next_y() } else { self.previous_y() },
+            ..*self
+        }
+    }
+
+    fn down(&self) -> Self {
+        Self {
+            y: if self.flipped { self.previous_y() } else { self.next_y() },
+            ..*self
+        }
+    }
+
+    fn left(&self) -> Self {
+        if self.x == 0 {
+            self.across_seam(W as u32 - 1)
+        } else {
+            Self {
+                x: self.x - 1,
+                ..*self
+            }
+        }
+    }
+
+    fn right(&self) -> Self {
+        if self.x == W as u32 - 1 {
+            self.across_seam(0)
+        } else {
+            Self {
+                x: self.x + 1,
+                ..*self
+            }
+        }
+    }
+
+    /// Gets the position of the point in 3D space.
+    ///
+    /// The point is placed on a figure-eight Klein bottle with a major radius of `scale` and a
+    /// minor radius of a third of `scale`. Use `klein_position` to choose the radii separately.
+    ///
+    /// - `scale` - The scale of the 3D object.
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        self.klein_position(scale, scale / 3.0)
+    }
+}
+
+impl <const W: usize, const H: usize> IndexedPoint for KleinPoint<W, H> {
+    const COUNT: usize = W * H;
+
+    fn to_index(&self) -> usize {
+        self.y as usize * W + self.x as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        Self::new((index % W) as u32, (index / W) as u32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{test::assert_indexed_points, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    use super::{KleinGrid, KleinPoint};
+
+    #[test]
+    fn test_klein_point_up_wraps() {
+        let point: KleinPoint<10, 5> = KleinPoint::new(3, 0);
+
+        assert_eq!(KleinPoint::new(3, 4), point.up());
+    }
+
+    #[test]
+    fn test_klein_point_down_wraps() {
+        let point: KleinPoint<10, 5> = KleinPoint::new(3, 4);
+
+        assert_eq!(KleinPoint::new(3, 0), point.down());
+    }
+
+    #[test]
+    fn test_klein_point_right_seam() {
+        let point: KleinPoint<10, 5> = KleinPoint::new(9, 1);
+
+        assert_eq!(KleinPoint::new(0, 3), point.right());
+        assert!(point.right().is_flipped());
+    }
+
+    #[test]
+    fn test_klein_point_left_seam() {
+        let point: KleinPoint<10, 5> = KleinPoint::new(0, 4);
+
+        assert_eq!(KleinPoint::new(9, 0), point.left());
+        assert!(point.left().is_flipped());
+    }
+
+    #[test]
+    fn test_klein_point_up_flipped() {
+        let point: KleinPoint<10, 5> = KleinPoint::new(9, 0).right();
+
+        assert_eq!(KleinPoint::new(0, 0), point.up());
+        assert_eq!(KleinPoint::new(0, 3), point.down());
+    }
+
+    #[test]
+    fn test_klein_point_loop() {
+        let start: KleinPoint<4, 5> = KleinPoint::new(1, 1);
+
+        let mut point = start;
+        for _ in 0..8 {
+            point = point.right();
+        }
+
+        assert_eq!(start, point);
+        assert!(!point.is_flipped());
+    }
+
+    #[test]
+    fn test_klein_point_diagonal_commutes() {
+        let point: KleinPoint<10, 5> = KleinPoint::new(9, 0);
+
+        assert_eq!(point.up().right(), point.right().up());
+        assert_eq!(point.down().right(), point.right().down());
+    }
+
+    #[test]
+    fn test_klein_from_neighbours_seam() {
+        let grid: KleinGrid<u32, 10, 5> = KleinGrid::from_fn(|point| point.y);
+
+        let grid2 = grid.map_neighbours(|_, _, _, left, _| *left);
+
+        assert_eq!(4, grid2[KleinPoint::new(0, 0)]);
+    }
+
+    #[test]
+    fn test_klein_position_seam_continuous() {
+        for y in 0..10 {
+            let point: KleinPoint<40, 10> = KleinPoint::new(39, y);
+
+            let (x1, y1, z1) = point.position(1.0);
+            let (x2, y2, z2) = point.right().position(1.0);
+
+            let distance = ((x1 - x2).powi(2) + (y1 - y2).powi(2) + (z1 - z2).powi(2)).sqrt();
+
+            assert!(distance < 0.3);
+        }
+    }
+
+    #[test]
+    fn test_klein_indexed_points() {
+        assert_eq!(35, KleinPoint::<7, 5>::COUNT);
+
+        assert_indexed_points(&KleinGrid::<(), 7, 5>::default());
+    }
+}
@@ -1,35 +1,93 @@
 //! A crate providing data structures for square-tiled grids wrapped around the surface of certain objects.
 //! This create was intended to be used for the creation of cellular automata on non-flat grids.
 //! The crate provides a trait `SurfaceGrid` with an associated type `Point` which can be used to traverse the grid squares.
+//! Grids with dimensions known at compile time also implement `StaticSurfaceGrid` which allows them to be constructed
+//! without an existing grid.
 //! Additionally, for grids that wrap a sphere the `Point` type implements the `SpherePoint` trait providing conversions
 //! between geographic and surface grid coordinates.
+//! Sphere points can also be placed on an `Ellipsoid` such as WGS84 to get earth centred coordinates, distances and cell areas.
+//! Points on rectangle and cube sphere grids give the corners, area and solid angle of their cells.
+//! Grids of sphere points can find the points within a distance of a point or inside a box of latitudes and longitudes without
+//! visiting every point.
+//! Grids whose cells are not all squares have points implementing `PolygonPoint` which gives every neighbour of a cell.
+//! Points on every grid with dimensions known at compile time implement `IndexedPoint` which numbers them in iteration order.
+//! Any point can be wrapped in `Oriented` to give it a heading that turns correctly across the seams of a grid.
+//! Larger neighbourhoods of any radius such as `Moore` and `VonNeumann` can be found with `GridPoint::neighbourhood`.
+//! Grids with cell geometry can be turned into a triangle `Mesh` and written as OBJ or PLY files.
+//! Sphere grids can be drawn into RGBA images with a `Renderer` and saved as PPM files without a window, or as PNG files with
+//! the `images` feature.
+//! The `serde` feature allows rectangle and cube sphere grids and their points to be serialized, checking their dimensions
+//! when they are deserialized.
+//! Rectangle and cube sphere grids of plain values can be checkpointed quickly with the compact binary `Snapshot` format.
+//! Their values are held in a `Storage`, and the `mmap` feature allows snapshot files to be mapped into memory as grids
+//! that are larger than the available memory or shared between processes.
+//! Cube sphere grids can be converted to and from cubemaps as six face images, a stacked OpenGL image or a horizontal
+//! cross.
+//! Any sphere grid can be filled from an equirectangular `Raster` such as an elevation map by nearest, bilinear or
+//! area-averaged sampling, and the `images` feature reads rasters from PNG and PPM files.
 //! 
 //! ## Available Surfaces
 //! ### Spheres
 //! - `RectangleSphereGrid` - Uses an equirectangular projection to wrap a rectangle around the sphere.
 //! - `CubeSphereGrid` - Projects a cube over the sphere with each face being a square grid.
+//! - `EquiangularCubeSphereGrid` - A `CubeSphereGrid` with cells spaced by equal angle so that they are close to uniform in size.
+//! - `DynRectangleSphereGrid` - A `RectangleSphereGrid` with dimensions chosen at runtime.
+//! - `DynCubeSphereGrid` - A `CubeSphereGrid` with a size chosen at runtime.
+//! - `IcosaSphereGrid` - Subdivides an icosahedron into a geodesic grid of hexagons and 12 pentagons.
+//! - `HealpixSphereGrid` - The HEALPix equal area grid in nested or ring ordering.
+//! - `OctaSphereGrid` - Unfolds an octahedron into equal area diamond cells on rings of latitude like an octahedral reduced Gaussian grid.
+//!
+//! ### Tori
+//! - `TorusGrid` - Wraps a rectangle around a torus so that every edge connects to the opposite edge.
+//!
+//! ### Cylinders
+//! - `CylinderGrid` - Wraps a rectangle around the side of a cylinder with a `Boundary` policy for the top and bottom edges.
+//! - `CappedCylinderGrid` - Wraps a rectangle around the side of a cylinder and closes each end with a square grid.
+//!
+//! ### Planes
+//! - `PlaneGrid` - A flat rectangle with a `Boundary` policy for its edges: periodic, clamped, reflecting or a constant outside value.
+//!
+//! ### Non-orientable Surfaces
+//! - `MobiusGrid` - Wraps a rectangle around a Möbius strip with a `Boundary` policy for the edge of the strip.
+//! - `KleinGrid` - Wraps a rectangle around a Klein bottle.
 
-use std::ops::{IndexMut, Index};
+use std::{hash::Hash, ops::{IndexMut, Index}};
 
 use rayon::iter::ParallelIterator;
 
+use crate::{neighbourhood::Neighbourhood, sphere::SpherePoint};
+
+pub mod boundary;
+pub mod cylinder;
+pub mod klein;
+pub mod mesh;
+pub mod mobius;
+pub mod neighbourhood;
+pub mod oriented;
+pub mod plane;
+pub mod raster;
+pub mod render;
+pub mod snapshot;
 pub mod sphere;
+pub mod storage;
+pub mod torus;
 
 /// A grid wrapped around a surface.
 pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T> + IntoIterator<Item = (Self::Point, T)> {
     /// The type of a point on this grid.
     type Point: GridPoint + Send;
 
-    /// Creates a new surface grid by calling the specified function for each point in the grid.
+    /// Creates a new surface grid with the same dimensions as this grid by calling the specified
+    /// function for each point in the grid.
     ///
     /// - `f` - The function to apply.
-    fn from_fn<F: FnMut(&Self::Point) -> T>(f: F) -> Self;
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self;
 
-    /// Creates a new surface grid by calling the specified function in parallel for each point in
-    /// the grid.
+    /// Creates a new surface grid with the same dimensions as this grid by calling the specified
+    /// function in parallel for each point in the grid.
     ///
     /// - `f` - The function to apply.
-    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync;
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync;
 
     /// Applies a function to each cell and its direct neighbours.
     ///
@@ -37,7 +95,7 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
     ///
     /// `f` - The function to apply.
     fn map_neighbours<F: FnMut(&T, &T, &T, &T, &T) -> T>(&self, mut f: F) -> Self where Self: Sized {
-        Self::from_fn(|current| {
+        self.same_size_from_fn(|current| {
             f(&self[current.clone()], &self[current.up()], &self[current.down()], &self[current.left()], &self[current.right()])
         })
     }
@@ -47,11 +105,15 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
     /// The provided function is called with the arguments: up_left, up, up_right,
     /// left, current, right, down_left, down, down_right.
     ///
+    /// The diagonals are found by stepping up or down and then left or right, so they are
+    /// distorted where the directions of a grid turn or where only three cells meet at a corner.
+    /// Use `map_neighbours_and_diagonals` to get the real diagonals of every cell.
+    ///
     /// `f` - The function to apply.
     fn map_neighbours_diagonals<
                 F: FnMut(&T, &T, &T, &T, &T, &T, &T, &T, &T) -> T
             >(&self, mut f: F) -> Self where Self: Sized {
-        Self::from_fn(|current| {
+        self.same_size_from_fn(|current| {
             f(
                 &self[current.up().left()], &self[current.up()], &self[current.up().right()],
                 &self[current.left()], &self[current.clone()], &self[current.right()],
@@ -68,7 +130,7 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
     fn map_neighbours_par<
                 F: Fn(&T, &T, &T, &T, &T) -> T + Send + Sync
             >(&self, f: F) -> Self where Self: Sized + Sync, T: Send + Sync {
-        Self::from_fn_par(|current| {
+        self.same_size_from_fn_par(|current| {
             f(&self[current.clone()], &self[current.up()], &self[current.down()], &self[current.left()], &self[current.right()])
         })
     }
@@ -78,11 +140,14 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
     /// The provided function is called with the arguments: up_left, up, up_right,
     /// left, current, right, down_left, down, down_right.
     ///
+    /// The diagonals are distorted in the same way as `map_neighbours_diagonals`.
+    /// Use `map_neighbours_and_diagonals_par` to get the real diagonals of every cell.
+    ///
     /// `f` - The function to apply.
     fn map_neighbours_diagonals_par<
                 F: Fn(&T, &T, &T, &T, &T, &T, &T, &T, &T) -> T + Send + Sync
             >(&self, f: F) -> Self where Self: Sized + Sync, T: Send + Sync {
-        Self::from_fn_par(|current| {
+        self.same_size_from_fn_par(|current| {
             f(
                 &self[current.up().left()], &self[current.up()], &self[current.up().right()],
                 &self[current.left()], &self[current.clone()], &self[current.right()],
@@ -97,7 +162,7 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
     ///
     /// `f` - The function to apply.
     fn map_neighbours_with_position<F: FnMut(&T, &Self::Point, &T, &T, &T, &T) -> T>(&self, mut f: F) -> Self where Self: Sized {
-        Self::from_fn(|current| {
+        self.same_size_from_fn(|current| {
             f(&self[current.clone()], current, &self[current.up()], &self[current.down()], &self[current.left()], &self[current.right()])
         })
     }
@@ -114,7 +179,7 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
     fn map_neighbours_diagonals_with_position<
                 F: FnMut(&Self::Point, &T, &T, &T, &T, &T, &T, &T, &T, &T) -> T
             >(&self, mut f: F) -> Self where Self: Sized {
-        Self::from_fn(|current| {
+        self.same_size_from_fn(|current| {
             f(current,
                 &self[current.up().left()], &self[current.up()], &self[current.up().right()],
                 &self[current.left()], &self[current.clone()], &self[current.right()],
@@ -132,7 +197,7 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
     fn map_neighbours_par_with_position<
                 F: Fn(&T, &Self::Point, &T, &T, &T, &T) -> T + Send + Sync
             >(&self, f: F) -> Self where Self: Sized + Sync, T: Send + Sync {
-        Self::from_fn_par(|current| {
+        self.same_size_from_fn_par(|current| {
             f(&self[current.clone()], current, &self[current.up()], &self[current.down()], &self[current.left()], &self[current.right()])
         })
     }
@@ -149,7 +214,7 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
     fn map_neighbours_diagonals_par_with_position<
                 F: Fn(&Self::Point, &T, &T, &T, &T, &T, &T, &T, &T, &T) -> T + Send + Sync
             >(&self, f: F) -> Self where Self: Sized + Sync, T: Send + Sync {
-        Self::from_fn_par(|current| {
+        self.same_size_from_fn_par(|current| {
             f(current,
                 &self[current.up().left()], &self[current.up()], &self[current.up().right()],
                 &self[current.left()], &self[current.clone()], &self[current.right()],
@@ -321,6 +386,227 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
         })
     }
 
+    /// Applies a function to each cell, its direct neighbours and the cells touching its corners.
+    ///
+    /// The provided function is called with the arguments: current, direct, diagonals.
+    /// The direct neighbours are up, down, left and right.
+    /// The diagonals are in the same order as `GridPoint::diagonals` and there are only 3 of
+    /// them where three cells meet at a corner, such as at the corners of a cube.
+    ///
+    /// `source` - The source grid from which to read data.
+    /// `f` - The function to apply.
+    fn set_from_neighbours_and_diagonals<
+                U,
+                G: SurfaceGrid<U, Point = Self::Point>,
+                F: FnMut(&U, &[&U; 4], &[&U]) -> T
+            >(&mut self, source: &G, mut f: F) {
+        self.set_from_fn(|current| {
+            let diagonals: Vec<_> = current.diagonals()
+                .into_iter()
+                .map(|point| &source[point])
+                .collect();
+
+            f(
+                &source[current.clone()],
+                &[&source[current.up()], &source[current.down()], &source[current.left()], &source[current.right()]],
+                &diagonals
+            )
+        })
+    }
+
+    /// Applies a function to each cell, its direct neighbours and the cells touching its corners
+    /// in parallel.
+    ///
+    /// The provided function is called with the arguments: current, direct, diagonals.
+    /// The direct neighbours are up, down, left and right.
+    /// The diagonals are in the same order as `GridPoint::diagonals` and there are only 3 of
+    /// them where three cells meet at a corner, such as at the corners of a cube.
+    ///
+    /// `source` - The source grid from which to read data.
+    /// `f` - The function to apply.
+    fn set_from_neighbours_and_diagonals_par<
+                U,
+                G: SurfaceGrid<U, Point = Self::Point> + Sync,
+                F: Fn(&U, &[&U; 4], &[&U]) -> T + Send + Sync
+            >(&mut self, source: &G, f: F) where T: Send + Sync {
+        self.set_from_fn_par(|current| {
+            let diagonals: Vec<_> = current.diagonals()
+                .into_iter()
+                .map(|point| &source[point])
+                .collect();
+
+            f(
+                &source[current.clone()],
+                &[&source[current.up()], &source[current.down()], &source[current.left()], &source[current.right()]],
+                &diagonals
+            )
+        })
+    }
+
+    /// Applies a function to each cell and every point returned by its `neighbours`.
+    ///
+    /// The provided function is called with the arguments: current, neighbours.
+    /// The neighbours are in the same order as `PolygonPoint::neighbours`.
+    ///
+    /// `f` - The function to apply.
+    fn map_polygon_neighbours<F: FnMut(&T, &[&T]) -> T>(&self, mut f: F) -> Self where Self: Sized, Self::Point: PolygonPoint {
+        self.same_size_from_fn(|current| {
+            let neighbours: Vec<_> = current.neighbours()
+                .into_iter()
+                .map(|point| &self[point])
+                .collect();
+
+            f(&self[current.clone()], &neighbours)
+        })
+    }
+
+    /// Applies a function in parallel to each cell and every point returned by its `neighbours`.
+    ///
+    /// The provided function is called with the arguments: current, neighbours.
+    /// The neighbours are in the same order as `PolygonPoint::neighbours`.
+    ///
+    /// `f` - The function to apply.
+    fn map_polygon_neighbours_par<
+                F: Fn(&T, &[&T]) -> T + Send + Sync
+            >(&self, f: F) -> Self where Self: Sized + Sync, Self::Point: PolygonPoint, T: Send + Sync {
+        self.same_size_from_fn_par(|current| {
+            let neighbours: Vec<_> = current.neighbours()
+                .into_iter()
+                .map(|point| &self[point])
+                .collect();
+
+            f(&self[current.clone()], &neighbours)
+        })
+    }
+
+    /// Applies a function to each cell, its direct neighbours and the cells touching its corners.
+    ///
+    /// The provided function is called with the arguments: current, direct, diagonals.
+    /// The direct neighbours are up, down, left and right.
+    /// The diagonals are in the same order as `GridPoint::diagonals` and there are only 3 of
+    /// them where three cells meet at a corner, such as at the corners of a cube.
+    ///
+    /// `f` - The function to apply.
+    fn map_neighbours_and_diagonals<F: FnMut(&T, &[&T; 4], &[&T]) -> T>(&self, mut f: F) -> Self where Self: Sized {
+        self.same_size_from_fn(|current| {
+            let diagonals: Vec<_> = current.diagonals()
+                .into_iter()
+                .map(|point| &self[point])
+                .collect();
+
+            f(
+                &self[current.clone()],
+                &[&self[current.up()], &self[current.down()], &self[current.left()], &self[current.right()]],
+                &diagonals
+            )
+        })
+    }
+
+    /// Applies a function in parallel to each cell, its direct neighbours and the cells touching
+    /// its corners.
+    ///
+    /// The provided function is called with the arguments: current, direct, diagonals.
+    /// The direct neighbours are up, down, left and right.
+    /// The diagonals are in the same order as `GridPoint::diagonals` and there are only 3 of
+    /// them where three cells meet at a corner, such as at the corners of a cube.
+    ///
+    /// `f` - The function to apply.
+    fn map_neighbours_and_diagonals_par<
+                F: Fn(&T, &[&T; 4], &[&T]) -> T + Send + Sync
+            >(&self, f: F) -> Self where Self: Sized + Sync, T: Send + Sync {
+        self.same_size_from_fn_par(|current| {
+            let diagonals: Vec<_> = current.diagonals()
+                .into_iter()
+                .map(|point| &self[point])
+                .collect();
+
+            f(
+                &self[current.clone()],
+                &[&self[current.up()], &self[current.down()], &self[current.left()], &self[current.right()]],
+                &diagonals
+            )
+        })
+    }
+
+    /// Applies a function to each cell and every point in its neighbourhood.
+    ///
+    /// The provided function is called with the arguments: current, neighbours.
+    /// The neighbours are in the same order as `GridPoint::neighbourhood`.
+    ///
+    /// `shape` - The shape of the neighbourhood.
+    /// `f` - The function to apply.
+    fn map_neighbourhood<
+                N: Neighbourhood,
+                F: FnMut(&T, &[&T]) -> T
+            >(&self, shape: N, mut f: F) -> Self where Self: Sized, Self::Point: Hash {
+        self.same_size_from_fn(|current| {
+            let neighbours: Vec<_> = current.neighbourhood(shape)
+                .into_iter()
+                .map(|point| &self[point])
+                .collect();
+
+            f(&self[current.clone()], &neighbours)
+        })
+    }
+
+    /// Applies a function in parallel to each cell and every point in its neighbourhood.
+    ///
+    /// The provided function is called with the arguments: current, neighbours.
+    /// The neighbours are in the same order as `GridPoint::neighbourhood`.
+    ///
+    /// `shape` - The shape of the neighbourhood.
+    /// `f` - The function to apply.
+    fn map_neighbourhood_par<
+                N: Neighbourhood + Send + Sync,
+                F: Fn(&T, &[&T]) -> T + Send + Sync
+            >(&self, shape: N, f: F) -> Self where Self: Sized + Sync, Self::Point: Hash, T: Send + Sync {
+        self.same_size_from_fn_par(|current| {
+            let neighbours: Vec<_> = current.neighbourhood(shape)
+                .into_iter()
+                .map(|point| &self[point])
+                .collect();
+
+            f(&self[current.clone()], &neighbours)
+        })
+    }
+
+    /// Gets every point on a spherical grid within a distance of a point along the surface of the
+    /// sphere.
+    ///
+    /// Only the points near the centre are visited, so the time taken depends on the number of
+    /// points found rather than the size of the grid.
+    ///
+    /// - `centre` - The point at the centre of the region.
+    /// - `distance` - The maximum distance from the centre.
+    /// - `radius` - The radius of the sphere.
+    fn points_within_distance(
+                &self, centre: &Self::Point, distance: f64, radius: f64
+            ) -> impl Iterator<Item = Self::Point> where Self::Point: SpherePoint + Hash {
+        sphere::points_within_distance(centre, distance, radius).into_iter()
+    }
+
+    /// Gets every point on a spherical grid inside a range of latitudes and longitudes.
+    ///
+    /// If `west` is greater than `east` the box crosses the antimeridian, and a box at least 2π
+    /// wide covers every longitude.
+    /// Points at the poles are inside any box that reaches them.
+    /// Only the points near the box are visited, so the time taken depends on the number of
+    /// points found rather than the size of the grid.
+    ///
+    /// - `south` - The lowest latitude in radians.
+    /// - `north` - The highest latitude in radians.
+    /// - `west` - The longitude of the western edge in radians.
+    /// - `east` - The longitude of the eastern edge in radians.
+    fn points_in_box(
+                &self, south: f64, north: f64, west: f64, east: f64
+            ) -> impl Iterator<Item = Self::Point> where Self::Point: SpherePoint + Hash {
+        self.points()
+            .next()
+            .map(|point| sphere::points_in_box(&point, south, north, west, east))
+            .unwrap_or_default()
+            .into_iter()
+    }
+
     /// Iterates over the points in this grid and their values.
     fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a;
 
@@ -334,6 +620,20 @@ pub trait SurfaceGrid<T> : IndexMut<Self::Point> + Index<Self::Point, Output = T
     fn par_points(&self) -> impl ParallelIterator<Item = Self::Point>;
 }
 
+/// A surface grid with dimensions that are known at compile time.
+pub trait StaticSurfaceGrid<T> : SurfaceGrid<T> {
+    /// Creates a new surface grid by calling the specified function for each point in the grid.
+    ///
+    /// - `f` - The function to apply.
+    fn from_fn<F: FnMut(&Self::Point) -> T>(f: F) -> Self;
+
+    /// Creates a new surface grid by calling the specified function in parallel for each point in
+    /// the grid.
+    ///
+    /// - `f` - The function to apply.
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync;
+}
+
 /// A point on a surface grid.
 /// 
 /// A type implementing this trait should ensure that the following conditions are met:
@@ -359,5 +659,88 @@ pub trait GridPoint : Eq + PartialEq + Clone {
     ///
     /// - `scale` - The scale of the 3D object.
     fn position(&self, scale: f64) -> (f64, f64, f64);
+
+    /// Gets the points that touch the corners of this point.
+    ///
+    /// The points are in the order up_right, down_right, down_left, up_left, skipping any corner
+    /// where only three cells meet, so a cell at the corner of a cube has 3 diagonals.
+    /// The diagonals follow the directions of the grid across seams.
+    fn diagonals(&self) -> Vec<Self> where Self: Sized {
+        neighbourhood::diagonals(self)
+    }
+
+    /// Gets every point in a neighbourhood of this point, not including this point.
+    /// The points are ordered by their distance from this point and each point appears once.
+    ///
+    /// - `shape` - The shape of the neighbourhood such as `Moore(1)` or `VonNeumann(2)`.
+    fn neighbourhood<N: Neighbourhood>(&self, shape: N) -> Vec<Self> where Self: Sized + Hash {
+        shape.points(self)
+    }
+}
+
+/// A point on a grid whose cells are not all squares, so each cell can have any number of
+/// direct neighbours.
+///
+/// The `GridPoint` directions of such a point pick the neighbours closest to each direction, so
+/// they may not undo each other.
+pub trait PolygonPoint : GridPoint {
+    /// Gets every point that shares an edge with this point.
+    /// The points are in anticlockwise order when viewed from outside the surface.
+    fn neighbours(&self) -> Vec<Self>;
+}
+
+/// A point on a grid whose cells are quadrilaterals with known corners.
+pub trait CellPoint : GridPoint {
+    /// Gets the positions of the corners of the cell at this point in 3D space.
+    /// The corners are in clockwise order when viewed from outside the surface.
+    ///
+    /// - `scale` - The scale of the 3D object.
+    fn corners(&self, scale: f64) -> [(f64, f64, f64); 4];
 }
 
+/// A point on a grid with dimensions known at compile time that can be converted to and from a
+/// linear index.
+///
+/// The indices count up from 0 in the same order as `SurfaceGrid::points`, so they can be used to
+/// keep data about each point in a `Vec` or a bitset.
+/// It is implemented by the points of every grid with dimensions known at compile time.
+pub trait IndexedPoint : GridPoint {
+    /// The number of points on the grid.
+    const COUNT: usize;
+
+    /// Gets the index of this point.
+    ///
+    /// # Panics
+    /// Panics if the point is outside a grid with a boundary, as it is not one of the grid's points.
+    fn to_index(&self) -> usize;
+
+    /// Gets the point with the specified index.
+    ///
+    /// # Panics
+    /// Panics if the index is not less than `COUNT`.
+    ///
+    /// - `index` - The index of the point.
+    fn from_index(index: usize) -> Self;
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fmt::Debug, panic};
+
+    use crate::{IndexedPoint, SurfaceGrid};
+
+    /// Checks that the indices of the points on a grid count up in iteration order and that
+    /// `from_index` rejects the first index past the end.
+    ///
+    /// - `grid` - The grid to check.
+    pub(crate) fn assert_indexed_points<T, G: SurfaceGrid<T>>(grid: &G) where G::Point: IndexedPoint + Debug {
+        assert_eq!(G::Point::COUNT, grid.points().count());
+
+        for (i, point) in grid.points().enumerate() {
+            assert_eq!(i, point.to_index());
+            assert_eq!(point, G::Point::from_index(i));
+        }
+
+        assert!(panic::catch_unwind(|| G::Point::from_index(G::Point::COUNT)).is_err());
+    }
+}
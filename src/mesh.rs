@@ -0,0 +1,558 @@
+//! A module for turning grids into triangle meshes that can be viewed in 3D software.
+//!
+//! Every cell of a grid whose points implement `CellPoint` becomes two triangles.
+//! Corners shared by several cells become a single vertex so that values can be smoothly
+//! interpolated across the surface, except where the texture coordinates wrap around at
+//! longitude 0.
+
+use std::{collections::HashMap, f64::consts::PI, io::{self, BufWriter, Write}};
+
+use crate::{CellPoint, SurfaceGrid};
+
+/// Where the values of a mesh are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueLocation {
+    /// Each vertex has the average value of the cells that share it.
+    Vertex,
+    /// Each triangle has the value of the cell it belongs to.
+    Face,
+}
+
+/// An indexed triangle mesh made from the cells of a grid.
+///
+/// The triangles are in anticlockwise order when viewed from outside the surface.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mesh {
+    /// The position of each vertex.
+    positions: Vec<(f64, f64, f64)>,
+    /// The unit normal of each vertex.
+    normals: Vec<(f64, f64, f64)>,
+    /// The texture coordinate of each vertex.
+    uvs: Vec<(f64, f64)>,
+    /// The indices of the vertices of each triangle.
+    triangles: Vec<[u32; 3]>,
+    /// The values for each vertex or triangle and where they are stored.
+    values: Option<(ValueLocation, Vec<f64>)>,
+}
+
+impl Mesh {
+    /// Creates a mesh from the cells of a grid.
+    ///
+    /// - `grid` - The grid to create the mesh from.
+    /// - `scale` - The scale of the 3D object.
+    pub fn from_grid<T, G: SurfaceGrid<T>>(grid: &G, scale: f64) -> Self where G::Point: CellPoint {
+        let (mut mesh, _, _) = Self::build(grid, scale);
+
+        mesh.values = None;
+
+        mesh
+    }
+
+    /// Creates a mesh from the cells of a grid with a value for each vertex or triangle.
+    ///
+    /// - `grid` - The grid to create the mesh from.
+    /// - `scale` - The scale of the 3D object.
+    /// - `location` - Whether to store the values for each vertex or each triangle.
+    /// - `f` - Gets the value of a cell.
+    pub fn from_grid_with_values<
+                T,
+                G: SurfaceGrid<T>,
+                F: FnMut(&G::Point, &T) -> f64
+            >(grid: &G, scale: f64, location: ValueLocation, mut f: F) -> Self where G::Point: CellPoint {
+        let (mut mesh, cells, shared) = Self::build(grid, scale);
+
+        let cell_values: Vec<_> = grid.iter()
+            .map(|(point, value)| f(&point, value))
+            .collect();
+
+        let values = match location {
+            ValueLocation::Vertex => {
+                let mut sums = vec![0.0; mesh.positions.len()];
+                let mut counts = vec![0u32; mesh.positions.len()];
+
+                // Copies of a vertex at the same position share the cells of every copy.
+                for (corners, value) in cells.iter().zip(&cell_values) {
+                    for &corner in corners {
+                        sums[shared[corner as usize] as usize] += value;
+                        counts[shared[corner as usize] as usize] += 1;
+                    }
+                }
+
+                shared.iter()
+                    .map(|&vertex| sums[vertex as usize] / counts[vertex as usize] as f64)
+                    .collect()
+            },
+            ValueLocation::Face => cells.iter()
+                .zip(&cell_values)
+                .flat_map(|(corners, value)| Self::cell_triangles(corners).map(|_| *value))
+                .collect(),
+        };
+
+        mesh.values = Some((location, values));
+
+        mesh
+    }
+
+    /// Creates the vertices and triangles of a mesh and gets the indices of the corners of each
+    /// cell in the order of `SurfaceGrid::points`, along with the index of the first vertex at the
+    /// same position as each vertex.
+    ///
+    /// - `grid` - The grid to create the mesh from.
+    /// - `scale` - The scale of the 3D object.
+    fn build<T, G: SurfaceGrid<T>>(grid: &G, scale: f64) -> (Self, Vec<[u32; 4]>, Vec<u32>) where G::Point: CellPoint {
+        let mut mesh = Self {
+            positions: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            triangles: Vec::new(),
+            values: None,
+        };
+
+        // Corners are matched by rounding them so that the same corner computed from different
+        // faces becomes one vertex.
+        let key = |(x, y, z): (f64, f64, f64)| {
+            let round = |value: f64| (value / scale * 1e9).round() as i64;
+
+            (round(x), round(y), round(z))
+        };
+
+        let mut vertices = HashMap::new();
+        let mut positions = HashMap::new();
+        let mut shared = Vec::new();
+
+        let cells: Vec<_> = grid.points()
+            .map(|point| {
+                let corners = point.corners(scale);
+                let wrapped = Self::wrapped_corners(&corners);
+
+                std::array::from_fn(|i| {
+                    *vertices.entry((key(corners[i]), wrapped[i])).or_insert_with(|| {
+                        mesh.add_vertex(corners[i], wrapped[i]);
+
+                        let vertex = mesh.positions.len() as u32 - 1;
+                        shared.push(*positions.entry(key(corners[i])).or_insert(vertex));
+
+                        vertex
+                    })
+                })
+            })
+            .collect();
+
+        for corners in &cells {
+            mesh.triangles.extend(Self::cell_triangles(corners));
+        }
+
+        (mesh, cells, shared)
+    }
+
+    /// Gets which corners of a cell that crosses longitude 0 are on the eastern side and need
+    /// their U coordinate increased by 1 to keep the cell from spanning the whole texture.
+    ///
+    /// - `corners` - The positions of the corners of the cell.
+    fn wrapped_corners(corners: &[(f64, f64, f64); 4]) -> [bool; 4] {
+        // Corners at the poles have no longitude so they are left alone.
+        let us = corners.map(|corner| {
+            let (x, y, z) = Self::normal(corner);
+
+            (y.abs() < 1.0 - 1e-9).then(|| Self::uv((x, y, z)).0)
+        });
+
+        let (low, high) = us.iter()
+            .flatten()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), &u| (low.min(u), high.max(u)));
+
+        us.map(|u| high - low > 0.5 && u.is_some_and(|u| u < 0.5))
+    }
+
+    /// Gets the triangles covering a cell in anticlockwise order, skipping any triangle that has
+    /// collapsed because two of the corners of the cell are the same vertex.
+    ///
+    /// - `corners` - The indices of the corners of the cell in clockwise order.
+    fn cell_triangles(corners: &[u32; 4]) -> impl Iterator<Item = [u32; 3]> {
+        [[corners[0], corners[3], corners[2]], [corners[0], corners[2], corners[1]]]
+            .into_iter()
+            .filter(|[a, b, c]| a != b && b != c && c != a)
+    }
+
+    /// Adds a vertex at a position on the surface.
+    ///
+    /// - `position` - The position of the vertex.
+    /// - `wrapped` - Whether the U coordinate is increased by 1.
+    fn add_vertex(&mut self, position: (f64, f64, f64), wrapped: bool) {
+        let normal = Self::normal(position);
+        let (u, v) = Self::uv(normal);
+
+        self.positions.push(position);
+        self.normals.push(normal);
+        self.uvs.push((if wrapped { u + 1.0 } else { u }, v));
+    }
+
+    /// Gets the unit normal at a position on the surface.
+    ///
+    /// - `position` - The position on the surface.
+    fn normal((x, y, z): (f64, f64, f64)) -> (f64, f64, f64) {
+        let length = (x * x + y * y + z * z).sqrt();
+
+        if length > 0.0 {
+            (x / length, y / length, z / length)
+        } else {
+            (0.0, 1.0, 0.0)
+        }
+    }
+
+    /// Gets the texture coordinates of a unit normal.
+    ///
+    /// The texture coordinates use an equirectangular projection with U increasing eastwards
+    /// from longitude 0 and V increasing northwards from the south pole.
+    ///
+    /// - `normal` - The unit normal.
+    fn uv((x, y, z): (f64, f64, f64)) -> (f64, f64) {
+        let longitude = x.atan2(z).rem_euclid(2.0 * PI);
+        let latitude = y.clamp(-1.0, 1.0).asin();
+
+        // Rounding can put a corner at longitude 0 just below a full turn.
+        let u = longitude / (2.0 * PI);
+        let u = if u > 1.0 - 1e-9 { 0.0 } else { u };
+
+        (u, latitude / PI + 0.5)
+    }
+
+    /// Gets the position of each vertex.
+    pub fn positions(&self) -> &[(f64, f64, f64)] {
+        &self.positions
+    }
+
+    /// Gets the unit normal of each vertex.
+    pub fn normals(&self) -> &[(f64, f64, f64)] {
+        &self.normals
+    }
+
+    /// Gets the texture coordinates of each vertex.
+    ///
+    /// The coordinates use an equirectangular projection.
+    /// Cells crossing longitude 0 use copies of the vertices on their eastern side with U
+    /// increased by 1, so the texture should repeat horizontally.
+    pub fn uvs(&self) -> &[(f64, f64)] {
+        &self.uvs
+    }
+
+    /// Gets the indices of the vertices of each triangle.
+    pub fn triangles(&self) -> &[[u32; 3]] {
+        &self.triangles
+    }
+
+    /// Gets where the values of this mesh are stored.
+    /// Returns `None` if the mesh has no values.
+    pub fn value_location(&self) -> Option<ValueLocation> {
+        self.values.as_ref().map(|(location, _)| *location)
+    }
+
+    /// Gets the value of each vertex or triangle.
+    /// This is empty if the mesh has no values.
+    pub fn values(&self) -> &[f64] {
+        self.values.as_ref().map_or(&[], |(_, values)| values)
+    }
+
+    /// Writes this mesh in the Wavefront OBJ format.
+    ///
+    /// The format has no place for values so they are not written.
+    ///
+    /// - `writer` - The writer to write to.
+    pub fn write_obj<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+
+        writeln!(writer, "# {} vertices, {} triangles", self.positions.len(), self.triangles.len())?;
+
+        for (x, y, z) in &self.positions {
+            writeln!(writer, "v {} {} {}", x, y, z)?;
+        }
+
+        for (u, v) in &self.uvs {
+            writeln!(writer, "vt {} {}", u, v)?;
+        }
+
+        for (x, y, z) in &self.normals {
+            writeln!(writer, "vn {} {} {}", x, y, z)?;
+        }
+
+        // Indices in the OBJ format start at 1.
+        for [a, b, c] in &self.triangles {
+            writeln!(writer, "f {0}/{0}/{0} {1}/{1}/{1} {2}/{2}/{2}", a + 1, b + 1, c + 1)?;
+        }
+
+        writer.flush()
+    }
+
+    /// Writes this mesh in the binary little endian PLY format.
+    ///
+    /// Each vertex has the properties `x`, `y`, `z`, `nx`, `ny`, `nz`, `s` and `t`.
+    /// Each face has the property `vertex_indices`.
+    /// Values are written as a `value` property of each vertex or face.
+    ///
+    /// - `writer` - The writer to write to.
+    pub fn write_ply<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+
+        let location = self.value_location();
+
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format binary_little_endian 1.0")?;
+        writeln!(writer, "element vertex {}", self.positions.len())?;
+
+        for property in ["x", "y", "z", "nx", "ny", "nz", "s", "t"] {
+            writeln!(writer, "property float {}", property)?;
+        }
+
+        if location == Some(ValueLocation::Vertex) {
+            writeln!(writer, "property float value")?;
+        }
+
+        writeln!(writer, "element face {}", self.triangles.len())?;
+        writeln!(writer, "property list uchar uint vertex_indices")?;
+
+        if location == Some(ValueLocation::Face) {
+            writeln!(writer, "property float value")?;
+        }
+
+        writeln!(writer, "end_header")?;
+
+        let write_float = |writer: &mut BufWriter<W>, value: f64| writer.write_all(&(value as f32).to_le_bytes());
+
+        for i in 0..self.positions.len() {
+            let (x, y, z) = self.positions[i];
+            let (nx, ny, nz) = self.normals[i];
+            let (u, v) = self.uvs[i];
+
+            for value in [x, y, z, nx, ny, nz, u, v] {
+                write_float(&mut writer, value)?;
+            }
+
+            if location == Some(ValueLocation::Vertex) {
+                write_float(&mut writer, self.values()[i])?;
+            }
+        }
+
+        for (i, triangle) in self.triangles.iter().enumerate() {
+            writer.write_all(&[3])?;
+
+            for index in triangle {
+                writer.write_all(&index.to_le_bytes())?;
+            }
+
+            if location == Some(ValueLocation::Face) {
+                write_float(&mut writer, self.values()[i])?;
+            }
+        }
+
+        writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{sphere::{CubeSphereGrid, DynCubeSphereGrid, RectangleSphereGrid}, StaticSurfaceGrid};
+
+    use super::{Mesh, ValueLocation};
+
+    /// Checks that every triangle is anticlockwise when viewed from outside.
+    fn assert_anticlockwise(mesh: &Mesh) {
+        for &[a, b, c] in mesh.triangles() {
+            let (ax, ay, az) = mesh.positions()[a as usize];
+            let (bx, by, bz) = mesh.positions()[b as usize];
+            let (cx, cy, cz) = mesh.positions()[c as usize];
+
+            let (ux, uy, uz) = (bx - ax, by - ay, bz - az);
+            let (vx, vy, vz) = (cx - ax, cy - ay, cz - az);
+
+            let normal = (uy * vz - uz * vy, uz * vx - ux * vz, ux * vy - uy * vx);
+
+            assert!(normal.0 * ax + normal.1 * ay + normal.2 * az > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_mesh_cube_counts() {
+        let grid: CubeSphereGrid<(), 3> = CubeSphereGrid::default();
+
+        let mesh = Mesh::from_grid(&grid, 1.0);
+
+        // The corners east of longitude 0 on the cells crossing it have a second copy.
+        assert_eq!(6 * 9 + 2 + 8, mesh.positions().len());
+        assert_eq!(6 * 9 * 2, mesh.triangles().len());
+        assert_eq!(mesh.positions().len(), mesh.normals().len());
+        assert_eq!(mesh.positions().len(), mesh.uvs().len());
+        assert_eq!(None, mesh.value_location());
+        assert!(mesh.values().is_empty());
+    }
+
+    #[test]
+    fn test_mesh_rect_counts() {
+        let grid: RectangleSphereGrid<(), 4, 3> = RectangleSphereGrid::default();
+
+        let mesh = Mesh::from_grid(&grid, 1.0);
+
+        // The top and bottom rows meet at a single vertex at each pole, and the corners at
+        // longitude 0 have a second copy for the cells to the west.
+        assert_eq!(2 * 4 + 2 + 2, mesh.positions().len());
+        assert_eq!(2 * 4 * 3 - 2 * 4, mesh.triangles().len());
+    }
+
+    #[test]
+    fn test_mesh_anticlockwise() {
+        let grid: CubeSphereGrid<(), 4> = CubeSphereGrid::default();
+
+        assert_anticlockwise(&Mesh::from_grid(&grid, 2.0));
+
+        let grid: RectangleSphereGrid<(), 8, 4> = RectangleSphereGrid::default();
+
+        assert_anticlockwise(&Mesh::from_grid(&grid, 2.0));
+    }
+
+    #[test]
+    fn test_mesh_normals() {
+        let grid = DynCubeSphereGrid::<()>::new(2);
+
+        let mesh = Mesh::from_grid(&grid, 3.0);
+
+        for (&(x, y, z), &(nx, ny, nz)) in mesh.positions().iter().zip(mesh.normals()) {
+            assert_relative_eq!(3.0, (x * x + y * y + z * z).sqrt(), epsilon = 1e-9);
+            assert_relative_eq!(x / 3.0, nx, epsilon = 1e-9);
+            assert_relative_eq!(y / 3.0, ny, epsilon = 1e-9);
+            assert_relative_eq!(z / 3.0, nz, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_mesh_uvs() {
+        let grid: RectangleSphereGrid<(), 4, 2> = RectangleSphereGrid::default();
+
+        let mesh = Mesh::from_grid(&grid, 1.0);
+
+        for (&(_, y, _), &(u, v)) in mesh.positions().iter().zip(mesh.uvs()) {
+            assert!((0.0..=1.0).contains(&u));
+            assert_relative_eq!(y.asin() / std::f64::consts::PI + 0.5, v, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_mesh_uvs_seam() {
+        let rect: RectangleSphereGrid<(), 8, 4> = RectangleSphereGrid::default();
+        let cube: CubeSphereGrid<(), 4> = CubeSphereGrid::default();
+
+        for mesh in [Mesh::from_grid(&rect, 1.0), Mesh::from_grid(&cube, 1.0)] {
+            for triangle in mesh.triangles() {
+                // The texture coordinates have no longitude at the poles.
+                if triangle.iter().any(|&i| mesh.normals()[i as usize].1.abs() > 1.0 - 1e-9) {
+                    continue;
+                }
+
+                let us = triangle.map(|i| mesh.uvs()[i as usize].0);
+
+                assert!(us.iter().cloned().fold(f64::NEG_INFINITY, f64::max) - us.iter().cloned().fold(f64::INFINITY, f64::min) < 0.5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mesh_vertex_values_seam() {
+        let grid: RectangleSphereGrid<f64, 4, 3> = RectangleSphereGrid::from_fn(|point| point.x() as f64);
+
+        let mesh = Mesh::from_grid_with_values(&grid, 1.0, ValueLocation::Vertex, |_, value| *value);
+
+        // Both copies of a corner at longitude 0 have the average of the columns on either side.
+        for ((&(x, _, z), value), &(u, _)) in mesh.positions().iter().zip(mesh.values()).zip(mesh.uvs()) {
+            if x.abs() < 1e-9 && z > 1e-9 {
+                assert_relative_eq!(1.5, *value, epsilon = 1e-9);
+                assert!(u == 0.0 || u == 1.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mesh_face_values() {
+        let grid: CubeSphereGrid<usize, 2> = CubeSphereGrid::from_fn(|point| point.x());
+
+        let mesh = Mesh::from_grid_with_values(&grid, 1.0, ValueLocation::Face, |_, value| *value as f64);
+
+        assert_eq!(Some(ValueLocation::Face), mesh.value_location());
+        assert_eq!(mesh.triangles().len(), mesh.values().len());
+        assert_eq!(mesh.values()[0], mesh.values()[1]);
+    }
+
+    #[test]
+    fn test_mesh_vertex_values() {
+        let grid: CubeSphereGrid<f64, 3> = CubeSphereGrid::from_fn(|_| 2.5);
+
+        let mesh = Mesh::from_grid_with_values(&grid, 1.0, ValueLocation::Vertex, |_, value| *value);
+
+        assert_eq!(mesh.positions().len(), mesh.values().len());
+
+        for value in mesh.values() {
+            assert_relative_eq!(2.5, *value);
+        }
+    }
+
+    #[test]
+    fn test_write_obj() {
+        let grid: CubeSphereGrid<(), 2> = CubeSphereGrid::default();
+
+        let mesh = Mesh::from_grid(&grid, 1.0);
+
+        let mut output = Vec::new();
+        mesh.write_obj(&mut output).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(29, output.lines().filter(|line| line.starts_with("v ")).count());
+        assert_eq!(29, output.lines().filter(|line| line.starts_with("vt ")).count());
+        assert_eq!(29, output.lines().filter(|line| line.starts_with("vn ")).count());
+        assert_eq!(48, output.lines().filter(|line| line.starts_with("f ")).count());
+        assert!(!output.lines().any(|line| line.starts_with("f ") && line.contains(" 0/")));
+    }
+
+    #[test]
+    fn test_write_ply_face_values() {
+        let grid: CubeSphereGrid<f64, 2> = CubeSphereGrid::from_fn(|_| 1.0);
+
+        let mesh = Mesh::from_grid_with_values(&grid, 1.0, ValueLocation::Face, |_, value| *value);
+
+        let mut output = Vec::new();
+        mesh.write_ply(&mut output).unwrap();
+
+        let header = b"end_header\n";
+        let end = output.windows(header.len()).position(|window| window == header).unwrap() + header.len();
+
+        let text = String::from_utf8(output[..end].to_vec()).unwrap();
+
+        assert!(text.starts_with("ply\nformat binary_little_endian 1.0\nelement vertex 29\n"));
+        assert!(text.contains("element face 48\nproperty list uchar uint vertex_indices\nproperty float value\n"));
+
+        assert_eq!(29 * 8 * 4 + 48 * (1 + 3 * 4 + 4), output.len() - end);
+
+        // The first triangle follows the vertices.
+        let face = &output[end + 29 * 8 * 4..];
+
+        assert_eq!(3, face[0]);
+        assert_eq!(1.0, f32::from_le_bytes(face[13..17].try_into().unwrap()));
+    }
+
+    #[test]
+    fn test_write_ply_vertex_values() {
+        let grid: RectangleSphereGrid<f64, 4, 2> = RectangleSphereGrid::from_fn(|_| 3.0);
+
+        let mesh = Mesh::from_grid_with_values(&grid, 1.0, ValueLocation::Vertex, |_, value| *value);
+
+        let mut output = Vec::new();
+        mesh.write_ply(&mut output).unwrap();
+
+        let header = b"end_header\n";
+        let end = output.windows(header.len()).position(|window| window == header).unwrap() + header.len();
+
+        let vertices = mesh.positions().len();
+
+        assert_eq!(vertices * 9 * 4 + mesh.triangles().len() * (1 + 3 * 4), output.len() - end);
+        assert_eq!(3.0, f32::from_le_bytes(output[end + 32..end + 36].try_into().unwrap()));
+    }
+}
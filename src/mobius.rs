@@ -0,0 +1,506 @@
+//! A module containing grids wrapped around Möbius strips.
+
+use std::{f64::consts::PI, hash::{Hash, Hasher}, marker::PhantomData, ops::{Index, IndexMut}, vec};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use static_array::HeapArray2D;
+
+use crate::{boundary::{Boundary, Clamp}, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+/// A grid wrapped around a Möbius strip.
+///
+/// The grid wraps horizontally with a half twist, so stepping off the left or right edge comes
+/// back in on the opposite edge upside down.
+/// The top and bottom edges form the single edge of the strip and are handled by the boundary
+/// policy `B`.
+/// When the policy is `Constant`, stepping off the edge of the strip gives a point outside the
+/// grid which always holds the value set with `set_outside`.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+/// - `B` - The boundary policy for the edge of the strip.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid. This is the number of cells along the strip.
+/// - `H` - The height of the grid. This is the number of cells across the strip.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct MobiusGrid<T, const W: usize, const H: usize, B: Boundary = Clamp> {
+    /// The data held in this grid.
+    data: HeapArray2D<T, W, H>,
+    /// The value of every point outside the grid.
+    outside: T,
+    boundary: PhantomData<B>,
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> MobiusGrid<T, W, H, B> {
+    /// Creates a new grid from a function of each point with the specified value outside the grid.
+    ///
+    /// - `outside` - The value of every point outside the grid.
+    /// - `f` - The function to create each value in the grid.
+    pub fn from_fn_with_outside<F: FnMut(&MobiusPoint<W, H, B>) -> T>(outside: T, mut f: F) -> Self {
+        Self {
+            data: HeapArray2D::from_fn(|y, x| f(&MobiusPoint::new(x as u32, y as u32))),
+            outside,
+            boundary: PhantomData,
+        }
+    }
+
+    /// Creates a new grid from a function of each point with the specified value outside the grid
+    /// computing each value in parallel.
+    ///
+    /// - `outside` - The value of every point outside the grid.
+    /// - `f` - The function to create each value in the grid.
+    pub fn from_fn_par_with_outside<F: Fn(&MobiusPoint<W, H, B>) -> T + Send + Sync>(outside: T, f: F) -> Self where T: Send + Sync {
+        Self {
+            data: HeapArray2D::from_fn_par(|y, x| f(&MobiusPoint::new(x as u32, y as u32))),
+            outside,
+            boundary: PhantomData,
+        }
+    }
+
+    /// Gets the value of every point outside the grid.
+    pub fn outside(&self) -> &T {
+        &self.outside
+    }
+
+    /// Sets the value of every point outside the grid.
+    ///
+    /// - `value` - The new value outside the grid.
+    pub fn set_outside(&mut self, value: T) {
+        self.outside = value;
+    }
+}
+
+impl <T: Clone, const W: usize, const H: usize, B: Boundary> SurfaceGrid<T> for MobiusGrid<T, W, H, B> {
+    type Point = MobiusPoint<W, H, B>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn_with_outside(self.outside.clone(), f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par_with_outside(self.outside.clone(), f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| MobiusPoint::new(x as u32, y as u32))
+            .for_each(|point| self[point] = f(&point))
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        self.data.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+            for (x, value) in subarray.iter_mut().enumerate() {
+                *value = f(&MobiusPoint::new(x as u32, y as u32));
+            }
+        })
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| (MobiusPoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| (MobiusPoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| MobiusPoint::new(x as u32, y as u32))
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| MobiusPoint::new(x as u32, y as u32))
+    }
+}
+
+impl <T: Clone + Default, const W: usize, const H: usize, B: Boundary> StaticSurfaceGrid<T> for MobiusGrid<T, W, H, B> {
+    /// Creates a new grid from a function of each point.
+    /// The value outside the grid is the default value of `T`.
+    ///
+    /// - `f` - The function to create each value in the grid.
+    fn from_fn<F: FnMut(&Self::Point) -> T>(f: F) -> Self {
+        Self::from_fn_with_outside(T::default(), f)
+    }
+
+    /// Creates a new grid from a function of each point computing each value in parallel.
+    /// The value outside the grid is the default value of `T`.
+    ///
+    /// - `f` - The function to create each value in the grid.
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par_with_outside(T::default(), f)
+    }
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> Index<MobiusPoint<W, H, B>> for MobiusGrid<T, W, H, B> {
+    type Output = T;
+
+    fn index(&self, index: MobiusPoint<W, H, B>) -> &Self::Output {
+        if index.outside {
+            &self.outside
+        } else {
+            &self.data[index.y as usize][index.x as usize]
+        }
+    }
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> IndexMut<MobiusPoint<W, H, B>> for MobiusGrid<T, W, H, B> {
+    fn index_mut(&mut self, index: MobiusPoint<W, H, B>) -> &mut Self::Output {
+        if index.outside {
+            &mut self.outside
+        } else {
+            &mut self.data[index.y as usize][index.x as usize]
+        }
+    }
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> IntoIterator for MobiusGrid<T, W, H, B> {
+    type Item = (MobiusPoint<W, H, B>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let data: Vec<_> = self.data.into_iter()
+            .enumerate()
+            .flat_map(|(y, subarray)| subarray.into_iter()
+                      .enumerate()
+                      .map(move |(x, value)| (MobiusPoint::new(x as u32, y as u32), value))
+                      )
+            .collect();
+
+        data.into_iter()
+    }
+}
+
+/// A point on a `MobiusGrid`.
+///
+/// Crossing the twisted seam flips the orientation of the point so that `up` and `down` are
+/// swapped relative to the grid, keeping walks across the seam continuous.
+/// Points compare equal when they refer to the same cell regardless of their orientation.
+///
+/// # Type Parameters
+/// - `B` - The boundary policy for the edge of the strip.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid.
+/// - `H` - The height of the grid.
+#[derive(Debug, Clone, Copy)]
+pub struct MobiusPoint<const W: usize, const H: usize, B: Boundary = Clamp> {
+    /// The X position in the grid.
+    x: u32,
+    /// The Y position in the grid.
+    y: u32,
+    /// Whether the point is upside down relative to the grid.
+    flipped: bool,
+    /// Whether the point is outside the grid.
+    outside: bool,
+    boundary: PhantomData<B>,
+}
+
+impl <const W: usize, const H: usize, B: Boundary> PartialEq for MobiusPoint<W, H, B> {
+    fn eq(&self, other: &Self) -> bool {
+        self.x == other.x && self.y == other.y && self.outside == other.outside
+    }
+}
+
+impl <const W: usize, const H: usize, B: Boundary> Eq for MobiusPoint<W, H, B> {}
+
+impl <const W: usize, const H: usize, B: Boundary> Hash for MobiusPoint<W, H, B> {
+    fn hash<Hs: Hasher>(&self, state: &mut Hs) {
+        self.x.hash(state);
+        self.y.hash(state);
+        self.outside.hash(state);
+    }
+}
+
+impl <const W: usize, const H: usize, B: Boundary> MobiusPoint<W, H, B> {
+    fn new(x: u32, y: u32) -> Self {
+        Self {
+            x: x.rem_euclid(W as u32),
+            y: y.min(H as u32 - 1),
+            flipped: false,
+            outside: false,
+            boundary: PhantomData,
+        }
+    }
+
+    fn new_outside() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            flipped: false,
+            outside: true,
+            boundary: PhantomData,
+        }
+    }
+
+    /// Checks whether the point is outside the grid.
+    pub fn is_outside(&self) -> bool {
+        self.outside
+    }
+
+    /// Checks whether the point is upside down relative to the grid after crossing the seam an
+    /// odd number of times.
+    pub fn is_flipped(&self) -> bool {
+        self.flipped
+    }
+
+    /// Gets the position of the centre of the cell in 3D space on a Möbius strip with the
+    /// specified dimensions.
+    ///
+    /// The strip circles the Y axis and is centred on the origin.
+    /// Points outside the grid are placed at the origin.
+    ///
+    /// - `radius` - The distance from the Y axis to the centre line of the strip.
+    /// - `half_width` - Half of the width of the strip.
+    pub fn mobius_position(&self, radius: f64, half_width: f64) -> (f64, f64, f64) {
+        if self.outside {
+            return (0.0, 0.0, 0.0);
+        }
+
+        let around = (self.x as f64 + 0.5) / W as f64 * PI * 2.0;
+        let across = (1.0 - (2.0 * self.y as f64 + 1.0) / H as f64) * half_width;
+
+        let distance = radius + across * (around / 2.0).cos();
+
+        let x = distance * around.sin();
+        let y = across * (around / 2.0).sin();
+        let z = distance * around.cos();
+
+        (x, y, z)
+    }
+
+    fn vertical(&self, y: Option<u32>) -> Self {
+        match y {
+            Some(y) => Self {
+                y,
+                ..*self
+            },
+            None => Self::new_outside(),
+        }
+    }
+
+    fn across_seam(&self, x: u32) -> Self {
+        Self {
+            x,
+            y: H as u32 - 1 - self.y,
+            flipped: !self.flipped,
+            ..*self
+        }
+    }
+}
+
+impl <const W: usize, const H: usize, B: Boundary> GridPoint for MobiusPoint<W, H, B> {
+    fn up(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        if self.flipped {
+            self.vertical(B::next(self.y, H as u32))
+        } else {
+            self.vertical(B::previous(self.y, H as u32))
+        }
+    }
+
+    fn down(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        if self.flipped {
+            self.vertical(B::previous(self.y, H as u32))
+        } else {
+            self.vertical(B::next(self.y, H as u32))
+        }
+    }
+
+    fn left(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        if self.x == 0 {
+            self.across_seam(W as u32 - 1)
+        } else {
+            Self {
+                x: self.x - 1,
+                ..*self
+            }
+        }
+    }
+
+    fn right(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        if self.x == W as u32 - 1 {
+            self.across_seam(0)
+        } else {
+            Self {
+                x: self.x + 1,
+                ..*self
+            }
+        }
+    }
+
+    /// Gets the position of the point in 3D space.
+    ///
+    /// The point is placed on a Möbius strip with a radius of `scale` and a width of `scale`.
+    /// Use `mobius_position` to choose the dimensions separately.
+    ///
+    /// - `scale` - The scale of the 3D object.
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        self.mobius_position(scale, scale / 2.0)
+    }
+}
+
+impl <const W: usize, const H: usize, B: Boundary> IndexedPoint for MobiusPoint<W, H, B> {
+    const COUNT: usize = W * H;
+
+    fn to_index(&self) -> usize {
+        assert!(!self.outside, "The point is outside the grid");
+
+        self.y as usize * W + self.x as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        Self::new((index % W) as u32, (index / W) as u32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{boundary::{Clamp, Constant, Reflect}, test::assert_indexed_points, GridPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    use super::{MobiusGrid, MobiusPoint};
+
+    #[test]
+    fn test_mobius_point_right_middle() {
+        let point: MobiusPoint<10, 5> = MobiusPoint::new(3, 1);
+
+        assert_eq!(MobiusPoint::new(4, 1), point.right());
+        assert!(!point.right().is_flipped());
+    }
+
+    #[test]
+    fn test_mobius_point_right_seam() {
+        let point: MobiusPoint<10, 5> = MobiusPoint::new(9, 1);
+
+        assert_eq!(MobiusPoint::new(0, 3), point.right());
+        assert!(point.right().is_flipped());
+    }
+
+    #[test]
+    fn test_mobius_point_left_seam() {
+        let point: MobiusPoint<10, 5> = MobiusPoint::new(0, 0);
+
+        assert_eq!(MobiusPoint::new(9, 4), point.left());
+        assert!(point.left().is_flipped());
+    }
+
+    #[test]
+    fn test_mobius_point_up_flipped() {
+        let point: MobiusPoint<10, 5> = MobiusPoint::new(9, 1).right();
+
+        assert_eq!(MobiusPoint::new(0, 4), point.up());
+        assert_eq!(MobiusPoint::new(0, 2), point.down());
+    }
+
+    #[test]
+    fn test_mobius_point_loop() {
+        let start: MobiusPoint<4, 5> = MobiusPoint::new(1, 1);
+
+        let mut point = start;
+        for _ in 0..4 {
+            point = point.right();
+        }
+
+        assert_eq!(MobiusPoint::new(1, 3), point);
+        assert!(point.is_flipped());
+
+        for _ in 0..4 {
+            point = point.right();
+        }
+
+        assert_eq!(start, point);
+        assert!(!point.is_flipped());
+    }
+
+    #[test]
+    fn test_mobius_point_diagonal_commutes() {
+        let point: MobiusPoint<10, 5> = MobiusPoint::new(0, 2);
+
+        assert_eq!(point.up().left(), point.left().up());
+        assert_eq!(point.down().left(), point.left().down());
+    }
+
+    #[test]
+    fn test_mobius_point_up_clamp() {
+        let point: MobiusPoint<10, 5, Clamp> = MobiusPoint::new(3, 0);
+
+        assert_eq!(point, point.up());
+    }
+
+    #[test]
+    fn test_mobius_point_up_reflect_flipped() {
+        let point: MobiusPoint<10, 5, Reflect> = MobiusPoint::new(0, 0).left();
+
+        assert_eq!(MobiusPoint::new(9, 3), point.up());
+    }
+
+    #[test]
+    fn test_mobius_point_down_constant() {
+        let point: MobiusPoint<10, 5, Constant> = MobiusPoint::new(3, 4);
+
+        assert!(point.down().is_outside());
+    }
+
+    #[test]
+    fn test_mobius_from_neighbours_seam() {
+        let grid: MobiusGrid<u32, 10, 5> = MobiusGrid::from_fn(|point| point.y);
+
+        let grid2 = grid.map_neighbours(|_, _, _, _, right| *right);
+
+        assert_eq!(3, grid2[MobiusPoint::new(9, 1)]);
+    }
+
+    #[test]
+    fn test_mobius_position_seam_continuous() {
+        let point: MobiusPoint<20, 5> = MobiusPoint::new(19, 0);
+
+        let (x1, y1, z1) = point.position(1.0);
+        let (x2, y2, z2) = point.right().position(1.0);
+
+        let distance = ((x1 - x2).powi(2) + (y1 - y2).powi(2) + (z1 - z2).powi(2)).sqrt();
+
+        assert!(distance < 0.4);
+    }
+
+    #[test]
+    fn test_mobius_position_centre_line() {
+        let point: MobiusPoint<4, 1> = MobiusPoint::new(0, 0);
+
+        let (x, y, z) = point.mobius_position(2.0, 1.0);
+
+        assert_relative_eq!(2.0 * 0.5_f64.sqrt(), x);
+        assert_relative_eq!(0.0, y);
+        assert_relative_eq!(2.0 * 0.5_f64.sqrt(), z);
+    }
+
+    #[test]
+    fn test_mobius_indexed_points() {
+        assert_indexed_points(&MobiusGrid::<(), 7, 5>::default());
+    }
+}
@@ -0,0 +1,373 @@
+//! A module containing the shapes of neighbourhoods around a point.
+//!
+//! A neighbourhood is found by walking outwards from a point, so it follows the seams of a grid
+//! and never contains the same cell twice, even where only three faces of a cube meet.
+
+use std::{collections::HashSet, hash::Hash};
+
+use crate::{oriented::{Oriented, DIRECTIONS}, GridPoint};
+
+/// The shape of a neighbourhood around a point.
+pub trait Neighbourhood : Copy {
+    /// Gets every point in the neighbourhood of a point, not including the point itself.
+    /// The points are ordered by their distance from the point.
+    ///
+    /// - `point` - The point at the centre of the neighbourhood.
+    fn points<P: GridPoint + Hash>(&self, point: &P) -> Vec<P>;
+}
+
+/// The points that can be reached in at most the specified number of steps including diagonal
+/// steps.
+///
+/// On a flat grid this is the square of side `2r + 1` centred on the point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Moore(pub usize);
+
+impl Neighbourhood for Moore {
+    fn points<P: GridPoint + Hash>(&self, point: &P) -> Vec<P> {
+        search(point, self.0, |point| {
+            let mut steps = vec![point.up(), point.down(), point.left(), point.right()];
+            steps.extend(point.diagonals());
+
+            steps
+        })
+    }
+}
+
+/// The points that can be reached in at most the specified number of steps without diagonal
+/// steps.
+///
+/// On a flat grid this is the diamond of points within a Manhattan distance of `r`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VonNeumann(pub usize);
+
+impl Neighbourhood for VonNeumann {
+    fn points<P: GridPoint + Hash>(&self, point: &P) -> Vec<P> {
+        search(point, self.0, |point| vec![point.up(), point.down(), point.left(), point.right()])
+    }
+}
+
+/// Gets every point within a number of steps of a point in the order that they are reached.
+///
+/// - `point` - The point to start from.
+/// - `radius` - The maximum number of steps.
+/// - `steps` - Gets the points one step away from a point.
+fn search<P: GridPoint + Hash, F: Fn(&P) -> Vec<P>>(point: &P, radius: usize, steps: F) -> Vec<P> {
+    let mut visited = HashSet::from([point.clone()]);
+    let mut found = Vec::new();
+    let mut start = 0;
+
+    for distance in 0..radius {
+        let end = found.len();
+
+        let next: Vec<_> = if distance == 0 {
+            steps(point)
+        } else {
+            found[start..end].iter().flat_map(&steps).collect()
+        };
+
+        for point in next {
+            if visited.insert(point.clone()) {
+                found.push(point);
+            }
+        }
+
+        start = end;
+    }
+
+    found
+}
+
+/// Gets the points touching the corners of a point.
+///
+/// A diagonal is only included if stepping across and then along gives the same point as
+/// stepping along and then across, so a corner where only three cells meet has no diagonal.
+///
+/// - `point` - The point to find the diagonals of.
+pub(crate) fn diagonals<P: GridPoint>(point: &P) -> Vec<P> {
+    DIRECTIONS.iter()
+        .filter_map(|&direction| {
+            let first = Oriented::new(point.clone(), direction).forward().turn_right().forward();
+            let second = Oriented::new(point.clone(), direction.clockwise()).forward().turn_left().forward();
+
+            (first.point() == second.point()).then(|| first.into_point())
+        })
+        .collect()
+}
+
+/// Gets every point that can be reached from the seeds, including by diagonal steps, without
+/// leaving the points that satisfy a predicate.
+///
+/// Only the points found and the points bordering them are visited.
+///
+/// - `seeds` - The points to start from. Seeds that do not satisfy the predicate are skipped.
+/// - `inside` - Checks whether a point should be included.
+pub(crate) fn flood<P: GridPoint + Hash, F: Fn(&P) -> bool>(seeds: Vec<P>, inside: F) -> Vec<P> {
+    let mut visited = HashSet::new();
+    let mut stack = Vec::new();
+    let mut found = Vec::new();
+
+    for seed in seeds {
+        if visited.insert(seed.clone()) && inside(&seed) {
+            stack.push(seed);
+        }
+    }
+
+    while let Some(point) = stack.pop() {
+        let mut steps = vec![point.up(), point.down(), point.left(), point.right()];
+        steps.extend(point.diagonals());
+
+        for step in steps {
+            if visited.insert(step.clone()) && inside(&step) {
+                stack.push(step);
+            }
+        }
+
+        found.push(point);
+    }
+
+    found
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::{sphere::{CubeFace, CubeSphereGrid, CubeSpherePoint}, torus::TorusGrid, GridPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    use super::{Moore, Neighbourhood, VonNeumann};
+
+    #[test]
+    fn test_von_neumann_radius_zero() {
+        let grid: TorusGrid<(), 10, 10> = TorusGrid::default();
+
+        let point = grid.points().next().unwrap();
+
+        assert!(point.neighbourhood(VonNeumann(0)).is_empty());
+    }
+
+    #[test]
+    fn test_von_neumann_radius_one() {
+        let grid: TorusGrid<(), 10, 10> = TorusGrid::default();
+
+        let point = grid.points().nth(33).unwrap();
+
+        assert_eq!(vec![point.up(), point.down(), point.left(), point.right()], point.neighbourhood(VonNeumann(1)));
+    }
+
+    #[test]
+    fn test_von_neumann_radius_two() {
+        let grid: TorusGrid<(), 10, 10> = TorusGrid::default();
+
+        let point = grid.points().nth(33).unwrap();
+
+        let neighbourhood = point.neighbourhood(VonNeumann(2));
+
+        assert_eq!(12, neighbourhood.len());
+        assert!(neighbourhood.contains(&point.up().up()));
+        assert!(neighbourhood.contains(&point.up().left()));
+        assert!(!neighbourhood.contains(&point.up().up().left()));
+    }
+
+    #[test]
+    fn test_moore_radius_one() {
+        let grid: TorusGrid<(), 10, 10> = TorusGrid::default();
+
+        let point = grid.points().nth(33).unwrap();
+
+        let neighbourhood: HashSet<_> = point.neighbourhood(Moore(1)).into_iter().collect();
+
+        let expected = HashSet::from([
+            point.up().left(), point.up(), point.up().right(),
+            point.left(), point.right(),
+            point.down().left(), point.down(), point.down().right(),
+        ]);
+
+        assert_eq!(expected, neighbourhood);
+    }
+
+    #[test]
+    fn test_moore_radius_two() {
+        let grid: TorusGrid<(), 10, 10> = TorusGrid::default();
+
+        let point = grid.points().nth(33).unwrap();
+
+        let neighbourhood = point.neighbourhood(Moore(2));
+
+        assert_eq!(24, neighbourhood.len());
+        assert!(neighbourhood.contains(&point.up().up().left().left()));
+    }
+
+    #[test]
+    fn test_moore_ordered_by_distance() {
+        let grid: TorusGrid<(), 10, 10> = TorusGrid::default();
+
+        let point = grid.points().nth(33).unwrap();
+
+        let inner = Moore(1).points(&point);
+        let outer = Moore(2).points(&point);
+
+        assert_eq!(inner, outer[..8]);
+    }
+
+    #[test]
+    fn test_moore_small_torus_unique() {
+        let grid: TorusGrid<(), 3, 3> = TorusGrid::default();
+
+        for point in grid.points() {
+            assert_eq!(8, point.neighbourhood(Moore(3)).len());
+        }
+    }
+
+    #[test]
+    fn test_moore_cube_corners() {
+        let grid: CubeSphereGrid<(), 5> = CubeSphereGrid::default();
+
+        for point in grid.points() {
+            let neighbourhood: HashSet<_> = point.neighbourhood(Moore(1)).into_iter().collect();
+
+            let corner = [0, 4].contains(&point.x()) && [0, 4].contains(&point.y());
+
+            assert_eq!(if corner { 7 } else { 8 }, neighbourhood.len());
+        }
+    }
+
+    #[test]
+    fn test_moore_cube_unique_and_near() {
+        let grid: CubeSphereGrid<(), 20> = CubeSphereGrid::default();
+
+        for point in grid.points() {
+            let neighbourhood = point.neighbourhood(Moore(2));
+
+            let unique: HashSet<_> = neighbourhood.iter().collect();
+
+            assert_eq!(neighbourhood.len(), unique.len());
+            assert!(!unique.contains(&point));
+
+            let (x, y, z) = point.position(1.0);
+
+            for neighbour in neighbourhood {
+                let (nx, ny, nz) = neighbour.position(1.0);
+
+                assert!((x * nx + y * ny + z * nz).acos() < 0.32);
+            }
+        }
+    }
+
+    #[test]
+    fn test_von_neumann_cube_unique() {
+        let grid: CubeSphereGrid<(), 4> = CubeSphereGrid::default();
+
+        for point in grid.points() {
+            let neighbourhood = point.neighbourhood(VonNeumann(3));
+
+            let unique: HashSet<_> = neighbourhood.iter().collect();
+
+            assert_eq!(neighbourhood.len(), unique.len());
+        }
+    }
+
+    #[test]
+    fn test_diagonals_torus() {
+        let grid: TorusGrid<(), 10, 10> = TorusGrid::default();
+
+        let point = grid.points().nth(33).unwrap();
+
+        assert_eq!(vec![point.up().right(), point.down().right(), point.down().left(), point.up().left()], point.diagonals());
+    }
+
+    #[test]
+    fn test_diagonals_cube_corner() {
+        let point: CubeSpherePoint<5> = CubeSpherePoint::try_new(CubeFace::Front, 0, 0).unwrap();
+
+        let diagonals = point.diagonals();
+
+        assert_eq!(3, diagonals.len());
+        assert_eq!(point.up().right(), diagonals[0]);
+        assert_eq!(point.down().right(), diagonals[1]);
+        assert_eq!(point.down().left(), diagonals[2]);
+    }
+
+    #[test]
+    fn test_diagonals_cube_turned_seam() {
+        let point: CubeSpherePoint<5> = CubeSpherePoint::try_new(CubeFace::Right, 2, 0).unwrap();
+
+        let diagonals = point.diagonals();
+
+        assert_eq!(4, diagonals.len());
+        assert!(diagonals.contains(&CubeSpherePoint::try_new(CubeFace::Top, 4, 3).unwrap()));
+        assert!(diagonals.contains(&CubeSpherePoint::try_new(CubeFace::Top, 4, 1).unwrap()));
+        assert!(!diagonals.contains(&point.up().left()));
+    }
+
+    #[test]
+    fn test_diagonals_touch_direct_neighbours() {
+        let grid: CubeSphereGrid<(), 5> = CubeSphereGrid::default();
+
+        for point in grid.points() {
+            let direct = [point.up(), point.down(), point.left(), point.right()];
+
+            for diagonal in point.diagonals() {
+                let touching = direct.iter()
+                    .filter(|neighbour| [neighbour.up(), neighbour.down(), neighbour.left(), neighbour.right()].contains(&diagonal))
+                    .count();
+
+                assert_eq!(2, touching);
+            }
+        }
+    }
+
+    #[test]
+    fn test_map_neighbours_and_diagonals_corners() {
+        let grid: CubeSphereGrid<usize, 5> = CubeSphereGrid::from_fn(|_| 1);
+
+        let grid = grid.map_neighbours_and_diagonals(|current, direct, diagonals| current + direct.iter().copied().sum::<usize>() + diagonals.iter().copied().sum::<usize>());
+
+        for (point, value) in grid.iter() {
+            let corner = [0, 4].contains(&point.x()) && [0, 4].contains(&point.y());
+
+            assert_eq!(if corner { 8 } else { 9 }, *value);
+        }
+    }
+
+    #[test]
+    fn test_map_neighbours_and_diagonals_par() {
+        let grid: CubeSphereGrid<usize, 5> = CubeSphereGrid::from_fn(|point| point.x() * 5 + point.y());
+
+        let f = |current: &usize, direct: &[&usize; 4], diagonals: &[&usize]| current * 100 + direct.iter().copied().sum::<usize>() * 10 + diagonals.iter().copied().sum::<usize>();
+
+        assert_eq!(grid.map_neighbours_and_diagonals(f), grid.map_neighbours_and_diagonals_par(f));
+    }
+
+    #[test]
+    fn test_set_from_neighbours_and_diagonals_par() {
+        let source: CubeSphereGrid<usize, 5> = CubeSphereGrid::from_fn(|point| point.x() * 5 + point.y());
+        let mut grid: CubeSphereGrid<usize, 5> = CubeSphereGrid::default();
+
+        let f = |current: &usize, direct: &[&usize; 4], diagonals: &[&usize]| current * 100 + direct.iter().copied().sum::<usize>() * 10 + diagonals.iter().copied().sum::<usize>();
+
+        grid.set_from_neighbours_and_diagonals_par(&source, f);
+
+        assert_eq!(source.map_neighbours_and_diagonals(f), grid);
+    }
+
+    #[test]
+    fn test_map_neighbourhood() {
+        let grid: TorusGrid<u32, 10, 10> = TorusGrid::from_fn(|_| 1);
+
+        let grid = grid.map_neighbourhood(Moore(2), |current, neighbours| current + neighbours.iter().copied().sum::<u32>());
+
+        for (_, value) in grid.iter() {
+            assert_eq!(25, *value);
+        }
+    }
+
+    #[test]
+    fn test_map_neighbourhood_par() {
+        let grid: CubeSphereGrid<usize, 5> = CubeSphereGrid::from_fn(|point| point.x() + point.y());
+
+        let sum = |current: &usize, neighbours: &[&usize]| current + neighbours.iter().copied().sum::<usize>();
+
+        assert_eq!(grid.map_neighbourhood(VonNeumann(2), sum), grid.map_neighbourhood_par(VonNeumann(2), sum));
+    }
+}
@@ -0,0 +1,487 @@
+//! A module containing points that carry a heading across the grid.
+//!
+//! The directions of a `GridPoint` are only meaningful locally, so walking off the edge of a cube
+//! face can turn `up` into `left`.
+//! An `Oriented` point rotates its heading at every such seam so that an agent walking forward
+//! keeps going straight over the whole surface.
+
+use std::hash::{Hash, Hasher};
+
+use crate::GridPoint;
+
+/// A direction on a grid relative to the directions of a `GridPoint`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Direction {
+    /// The direction of `GridPoint::up`.
+    #[default]
+    Up,
+    /// The direction of `GridPoint::right`.
+    Right,
+    /// The direction of `GridPoint::down`.
+    Down,
+    /// The direction of `GridPoint::left`.
+    Left,
+}
+
+/// The directions in clockwise order.
+pub(crate) const DIRECTIONS: [Direction; 4] = [Direction::Up, Direction::Right, Direction::Down, Direction::Left];
+
+impl Direction {
+    /// Gets the point one step in this direction from a point.
+    ///
+    /// - `point` - The point to step from.
+    pub fn step<P: GridPoint>(self, point: &P) -> P {
+        match self {
+            Direction::Up => point.up(),
+            Direction::Right => point.right(),
+            Direction::Down => point.down(),
+            Direction::Left => point.left(),
+        }
+    }
+
+    /// Gets the direction facing the other way.
+    pub fn opposite(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// Gets the direction a quarter turn clockwise from this one.
+    pub fn clockwise(self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Gets the direction a quarter turn anticlockwise from this one.
+    pub fn anticlockwise(self) -> Self {
+        self.rotate(3)
+    }
+
+    /// Gets the number of quarter turns clockwise from `Up` to this direction.
+    fn turns(self) -> usize {
+        self as usize
+    }
+
+    /// Gets the direction the specified number of quarter turns clockwise from this one.
+    ///
+    /// - `turns` - The number of quarter turns.
+    fn rotate(self, turns: usize) -> Self {
+        DIRECTIONS[(self.turns() + turns) % 4]
+    }
+}
+
+/// A point on a grid facing in a direction.
+///
+/// Moving the point works out which way it entered the new cell and rotates the heading to
+/// match, so turning and moving forward behave the same on every face of a grid.
+/// Some grids mirror the directions of their points across a seam, such as the back face of a
+/// cube, so the point also tracks whether its directions are mirrored to keep turns on the same
+/// side.
+/// If a move does not leave the cell, such as at a clamped edge, the heading is kept.
+/// If no direction leads back from the new cell, which can happen on grids with polygon cells,
+/// the heading is also kept.
+/// Points compare equal when they refer to the same cell regardless of their heading.
+///
+/// # Type Parameters
+/// - `P` - The type of point that is being oriented.
+#[derive(Debug, Clone, Copy)]
+pub struct Oriented<P: GridPoint> {
+    /// The point on the grid.
+    point: P,
+    /// The direction that the point is facing.
+    heading: Direction,
+    /// Whether the directions of the point are mirrored relative to where it started.
+    mirrored: bool,
+}
+
+impl <P: GridPoint> PartialEq for Oriented<P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.point == other.point
+    }
+}
+
+impl <P: GridPoint> Eq for Oriented<P> {}
+
+impl <P: GridPoint + Hash> Hash for Oriented<P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.point.hash(state);
+    }
+}
+
+impl <P: GridPoint> Oriented<P> {
+    /// Creates a new `Oriented` point.
+    ///
+    /// - `point` - The point on the grid.
+    /// - `heading` - The direction that the point is facing.
+    pub fn new(point: P, heading: Direction) -> Self {
+        Self {
+            point,
+            heading,
+            mirrored: false,
+        }
+    }
+
+    /// Gets the point on the grid.
+    pub fn point(&self) -> &P {
+        &self.point
+    }
+
+    /// Gets the direction that the point is facing relative to the directions of the point.
+    pub fn heading(&self) -> Direction {
+        self.heading
+    }
+
+    /// Checks whether the directions of the point are mirrored relative to where it started,
+    /// in which case turning right turns the heading anticlockwise.
+    pub fn is_mirrored(&self) -> bool {
+        self.mirrored
+    }
+
+    /// Gets the point without its heading.
+    pub fn into_point(self) -> P {
+        self.point
+    }
+
+    /// Moves one step in the direction that the point is facing.
+    pub fn forward(&self) -> Self {
+        self.step(self.heading)
+    }
+
+    /// Moves one step in the opposite direction to the one that the point is facing without
+    /// turning around.
+    pub fn backward(&self) -> Self {
+        self.step(self.heading.opposite())
+    }
+
+    /// Turns a quarter turn to the left without moving.
+    pub fn turn_left(&self) -> Self {
+        Self {
+            heading: if self.mirrored { self.heading.clockwise() } else { self.heading.anticlockwise() },
+            ..self.clone()
+        }
+    }
+
+    /// Turns a quarter turn to the right without moving.
+    pub fn turn_right(&self) -> Self {
+        Self {
+            heading: if self.mirrored { self.heading.anticlockwise() } else { self.heading.clockwise() },
+            ..self.clone()
+        }
+    }
+
+    /// Turns to face the opposite direction without moving.
+    pub fn turn_around(&self) -> Self {
+        Self {
+            heading: self.heading.opposite(),
+            ..self.clone()
+        }
+    }
+
+    /// Moves one step in a direction relative to the directions of the point, rotating the
+    /// heading if the step crosses a seam.
+    ///
+    /// - `direction` - The direction to move in.
+    pub fn step(&self, direction: Direction) -> Self {
+        let point = direction.step(&self.point);
+
+        if point == self.point {
+            return Self {
+                point,
+                ..self.clone()
+            };
+        }
+
+        // Prefer the direction that leads straight back so that grids with only two cells in a
+        // direction keep their heading.
+        let back = std::iter::once(direction.opposite())
+            .chain(DIRECTIONS)
+            .find(|back| back.step(&point) == self.point);
+
+        let Some(back) = back else {
+            return Self {
+                point,
+                ..self.clone()
+            };
+        };
+
+        let arrived = back.opposite();
+
+        // The heading keeps its angle from the direction of travel, which is reversed if the
+        // seam mirrors the directions.
+        let turns = self.heading.turns() + 4 - direction.turns();
+
+        if Self::is_mirrored_step(&self.point, direction, &point, arrived) {
+            Self {
+                point,
+                heading: arrived.rotate(4 - turns % 4),
+                mirrored: !self.mirrored,
+            }
+        } else {
+            Self {
+                point,
+                heading: arrived.rotate(turns),
+                mirrored: self.mirrored,
+            }
+        }
+    }
+
+    /// Checks whether a step mirrors the directions of the point by comparing the cells beside
+    /// the step on each side.
+    ///
+    /// - `from` - The point before the step.
+    /// - `direction` - The direction of the step from `from`.
+    /// - `to` - The point after the step.
+    /// - `arrived` - The direction of the step as seen from `to`.
+    fn is_mirrored_step(from: &P, direction: Direction, to: &P, arrived: Direction) -> bool {
+        for turns in [1, 3] {
+            let beside = direction.step(&direction.rotate(turns).step(from));
+
+            if beside == arrived.rotate(turns).step(to) {
+                return false;
+            }
+
+            if beside == arrived.rotate(4 - turns).step(to) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl <P: GridPoint> GridPoint for Oriented<P> {
+    fn up(&self) -> Self {
+        self.step(Direction::Up)
+    }
+
+    fn down(&self) -> Self {
+        self.step(Direction::Down)
+    }
+
+    fn left(&self) -> Self {
+        self.step(Direction::Left)
+    }
+
+    fn right(&self) -> Self {
+        self.step(Direction::Right)
+    }
+
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        self.point.position(scale)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use itertools::Itertools;
+
+    use crate::{boundary::{Clamp, Reflect}, plane::PlaneGrid, sphere::{CubeFace, CubeSphereGrid, CubeSpherePoint, EquiangularCubeSphereGrid, OctaSphereGrid}, torus::TorusGrid, GridPoint, SurfaceGrid};
+
+    use super::{Direction, Oriented, DIRECTIONS};
+
+    /// Gets everything about an oriented point so that headings are compared as well as cells.
+    fn state<P: GridPoint>(point: &Oriented<P>) -> (P, Direction, bool) {
+        (point.point().clone(), point.heading(), point.is_mirrored())
+    }
+
+    #[test]
+    fn test_direction_opposite() {
+        assert_eq!(Direction::Down, Direction::Up.opposite());
+        assert_eq!(Direction::Left, Direction::Right.opposite());
+    }
+
+    #[test]
+    fn test_direction_clockwise() {
+        assert_eq!(Direction::Right, Direction::Up.clockwise());
+        assert_eq!(Direction::Up, Direction::Left.clockwise());
+    }
+
+    #[test]
+    fn test_direction_anticlockwise() {
+        assert_eq!(Direction::Left, Direction::Up.anticlockwise());
+        assert_eq!(Direction::Down, Direction::Left.anticlockwise());
+    }
+
+    #[test]
+    fn test_oriented_turn_left_four_times() {
+        let point = Oriented::new(CubeSpherePoint::<5>::try_new(CubeFace::Front, 2, 2).unwrap(), Direction::Up);
+
+        assert_eq!(Direction::Left, point.turn_left().heading());
+        assert_eq!(state(&point), state(&point.turn_left().turn_left().turn_left().turn_left()));
+    }
+
+    #[test]
+    fn test_oriented_turn_right_undoes_turn_left() {
+        let point = Oriented::new(CubeSpherePoint::<5>::try_new(CubeFace::Top, 1, 3).unwrap(), Direction::Down);
+
+        assert_eq!(state(&point), state(&point.turn_left().turn_right()));
+        assert_eq!(state(&point), state(&point.turn_around().turn_around()));
+    }
+
+    #[test]
+    fn test_oriented_forward_middle() {
+        let point = Oriented::new(CubeSpherePoint::<5>::try_new(CubeFace::Front, 2, 2).unwrap(), Direction::Right);
+
+        assert_eq!(state(&Oriented::new(CubeSpherePoint::try_new(CubeFace::Front, 3, 2).unwrap(), Direction::Right)), state(&point.forward()));
+    }
+
+    #[test]
+    fn test_oriented_forward_seam_rotates() {
+        // Walking right off the top face enters the top of the right face heading down.
+        let point = Oriented::new(CubeSpherePoint::<5>::try_new(CubeFace::Top, 4, 1).unwrap(), Direction::Right);
+
+        assert_eq!(state(&Oriented::new(CubeSpherePoint::try_new(CubeFace::Right, 3, 0).unwrap(), Direction::Down)), state(&point.forward()));
+    }
+
+    #[test]
+    fn test_oriented_forward_cube_loop() {
+        let grid: CubeSphereGrid<(), 4> = CubeSphereGrid::default();
+
+        for (point, heading) in grid.points().cartesian_product(DIRECTIONS) {
+            let start = Oriented::new(point, heading);
+
+            let end = (0..16).fold(start, |point, _| point.forward());
+
+            assert_eq!(state(&start), state(&end));
+        }
+    }
+
+    #[test]
+    fn test_oriented_forward_equiangular_cube_loop() {
+        let grid: EquiangularCubeSphereGrid<(), 3> = EquiangularCubeSphereGrid::default();
+
+        for (point, heading) in grid.points().cartesian_product(DIRECTIONS) {
+            let start = Oriented::new(point, heading);
+
+            let end = (0..12).fold(start, |point, _| point.forward());
+
+            assert_eq!(state(&start), state(&end));
+        }
+    }
+
+    #[test]
+    fn test_oriented_forward_cube_straight() {
+        let grid: CubeSphereGrid<(), 6> = CubeSphereGrid::default();
+
+        // Three points in a row stay close to a great circle.
+        for (point, heading) in grid.points().cartesian_product(DIRECTIONS) {
+            let start = Oriented::new(point, heading);
+            let middle = start.forward();
+            let end = middle.forward();
+
+            let (ax, ay, az) = start.position(1.0);
+            let (bx, by, bz) = middle.position(1.0);
+            let (cx, cy, cz) = end.position(1.0);
+
+            let (nx, ny, nz) = (ay * bz - az * by, az * bx - ax * bz, ax * by - ay * bx);
+            let length = (nx * nx + ny * ny + nz * nz).sqrt();
+
+            assert!(((nx * cx + ny * cy + nz * cz) / length).abs() < 0.1);
+        }
+    }
+
+    #[test]
+    fn test_oriented_forward_then_back() {
+        let grid: CubeSphereGrid<(), 4> = CubeSphereGrid::default();
+
+        for (point, heading) in grid.points().cartesian_product(DIRECTIONS) {
+            let start = Oriented::new(point, heading);
+
+            assert_eq!(state(&start), state(&start.forward().turn_around().forward().turn_around()));
+            assert_eq!(state(&start), state(&start.forward().backward()));
+        }
+    }
+
+    #[test]
+    fn test_oriented_right_turns_return() {
+        let grid: CubeSphereGrid<(), 4> = CubeSphereGrid::default();
+
+        for (point, heading) in grid.points().cartesian_product(DIRECTIONS) {
+            let start = Oriented::new(point, heading);
+
+            let end = (0..4).fold(start, |point, _| point.forward().turn_right());
+
+            // Walking around a square only fails to close at the corners of the cube.
+            if state(&end) != state(&start) {
+                let corner = [0, 3].contains(&point.x()) && [0, 3].contains(&point.y());
+
+                assert!(corner);
+            }
+        }
+    }
+
+    #[test]
+    fn test_oriented_cube_back_mirrored() {
+        let grid: CubeSphereGrid<(), 4> = CubeSphereGrid::default();
+
+        // The directions on the back face are mirrored so that each direction loops around the cube.
+        for (point, heading) in grid.points().cartesian_product(DIRECTIONS) {
+            let start = Oriented::new(point, heading);
+            let end = start.forward();
+
+            let crossed = (point.face() == CubeFace::Back) != (end.point().face() == CubeFace::Back);
+
+            assert_eq!(crossed, end.is_mirrored());
+        }
+    }
+
+    #[test]
+    fn test_oriented_octa_forward_then_back() {
+        let grid: OctaSphereGrid<(), 4> = OctaSphereGrid::default();
+
+        for (point, heading) in grid.points().cartesian_product(DIRECTIONS) {
+            let start = Oriented::new(point, heading);
+
+            assert_eq!(state(&start), state(&start.forward().turn_around().forward().turn_around()));
+        }
+    }
+
+    #[test]
+    fn test_oriented_torus_keeps_heading() {
+        let grid: TorusGrid<(), 5, 3> = TorusGrid::default();
+
+        for (point, heading) in grid.points().cartesian_product(DIRECTIONS) {
+            let start = Oriented::new(point, heading);
+
+            assert_eq!(heading, start.forward().heading());
+        }
+    }
+
+    #[test]
+    fn test_oriented_plane_clamp_keeps_heading() {
+        let grid: PlaneGrid<(), 3, 3, Clamp> = PlaneGrid::default();
+
+        let point = grid.points().next().unwrap();
+        let start = Oriented::new(point, Direction::Up);
+
+        assert_eq!(state(&start), state(&start.forward()));
+    }
+
+    #[test]
+    fn test_oriented_plane_reflect_bounces() {
+        let grid: PlaneGrid<(), 3, 3, Reflect> = PlaneGrid::default();
+
+        let point = grid.points().next().unwrap();
+        let start = Oriented::new(point, Direction::Up);
+
+        assert_eq!(Direction::Down, start.forward().heading());
+        assert_eq!(point.down(), *start.forward().point());
+    }
+
+    #[test]
+    fn test_oriented_position() {
+        let point = CubeSpherePoint::<5>::try_new(CubeFace::Left, 1, 2).unwrap();
+
+        assert_eq!(point.position(2.0), Oriented::new(point, Direction::Left).position(2.0));
+    }
+
+    #[test]
+    fn test_oriented_equal_ignores_heading() {
+        let point = CubeSpherePoint::<5>::try_new(CubeFace::Back, 1, 2).unwrap();
+
+        let a = Oriented::new(point, Direction::Up);
+        let b = Oriented::new(point, Direction::Left).forward().backward();
+
+        assert_eq!(a, b);
+        assert_eq!(1, HashSet::from([a, b, a.turn_right()]).len());
+    }
+}
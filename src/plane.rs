@@ -0,0 +1,414 @@
+//! A module containing flat rectangular grids.
+
+use std::{marker::PhantomData, ops::{Index, IndexMut}, vec};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use static_array::HeapArray2D;
+
+use crate::{boundary::{Boundary, Clamp}, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+/// A flat rectangular grid.
+///
+/// Every edge of the grid is handled by the boundary policy `B`, so the same rules can be run on
+/// a plane as on a closed surface.
+/// When the policy is `Constant`, stepping off an edge gives a point outside the grid which always
+/// holds the value set with `set_outside`.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+/// - `B` - The boundary policy for every edge.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid.
+/// - `H` - The height of the grid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PlaneGrid<T, const W: usize, const H: usize, B: Boundary = Clamp> {
+    /// The data held in this grid.
+    data: HeapArray2D<T, W, H>,
+    /// The value of every point outside the grid.
+    outside: T,
+    boundary: PhantomData<B>,
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> PlaneGrid<T, W, H, B> {
+    /// Creates a new grid from a function of each point with the specified value outside the grid.
+    ///
+    /// - `outside` - The value of every point outside the grid.
+    /// - `f` - The function to create each value in the grid.
+    pub fn from_fn_with_outside<F: FnMut(&PlanePoint<W, H, B>) -> T>(outside: T, mut f: F) -> Self {
+        Self {
+            data: HeapArray2D::from_fn(|y, x| f(&PlanePoint::new(x as u32, y as u32))),
+            outside,
+            boundary: PhantomData,
+        }
+    }
+
+    /// Creates a new grid from a function of each point with the specified value outside the grid
+    /// computing each value in parallel.
+    ///
+    /// - `outside` - The value of every point outside the grid.
+    /// - `f` - The function to create each value in the grid.
+    pub fn from_fn_par_with_outside<F: Fn(&PlanePoint<W, H, B>) -> T + Send + Sync>(outside: T, f: F) -> Self where T: Send + Sync {
+        Self {
+            data: HeapArray2D::from_fn_par(|y, x| f(&PlanePoint::new(x as u32, y as u32))),
+            outside,
+            boundary: PhantomData,
+        }
+    }
+
+    /// Gets the value of every point outside the grid.
+    pub fn outside(&self) -> &T {
+        &self.outside
+    }
+
+    /// Sets the value of every point outside the grid.
+    ///
+    /// - `value` - The new value outside the grid.
+    pub fn set_outside(&mut self, value: T) {
+        self.outside = value;
+    }
+}
+
+impl <T: Clone, const W: usize, const H: usize, B: Boundary> SurfaceGrid<T> for PlaneGrid<T, W, H, B> {
+    type Point = PlanePoint<W, H, B>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn_with_outside(self.outside.clone(), f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par_with_outside(self.outside.clone(), f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| PlanePoint::new(x as u32, y as u32))
+            .for_each(|point| self[point] = f(&point))
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        self.data.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+            for (x, value) in subarray.iter_mut().enumerate() {
+                *value = f(&PlanePoint::new(x as u32, y as u32));
+            }
+        })
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| (PlanePoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| (PlanePoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| PlanePoint::new(x as u32, y as u32))
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| PlanePoint::new(x as u32, y as u32))
+    }
+}
+
+impl <T: Clone + Default, const W: usize, const H: usize, B: Boundary> StaticSurfaceGrid<T> for PlaneGrid<T, W, H, B> {
+    /// Creates a new grid from a function of each point.
+    /// The value outside the grid is the default value of `T`.
+    ///
+    /// - `f` - The function to create each value in the grid.
+    fn from_fn<F: FnMut(&Self::Point) -> T>(f: F) -> Self {
+        Self::from_fn_with_outside(T::default(), f)
+    }
+
+    /// Creates a new grid from a function of each point computing each value in parallel.
+    /// The value outside the grid is the default value of `T`.
+    ///
+    /// - `f` - The function to create each value in the grid.
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par_with_outside(T::default(), f)
+    }
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> Index<PlanePoint<W, H, B>> for PlaneGrid<T, W, H, B> {
+    type Output = T;
+
+    fn index(&self, index: PlanePoint<W, H, B>) -> &Self::Output {
+        if index.outside {
+            &self.outside
+        } else {
+            &self.data[index.y as usize][index.x as usize]
+        }
+    }
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> IndexMut<PlanePoint<W, H, B>> for PlaneGrid<T, W, H, B> {
+    fn index_mut(&mut self, index: PlanePoint<W, H, B>) -> &mut Self::Output {
+        if index.outside {
+            &mut self.outside
+        } else {
+            &mut self.data[index.y as usize][index.x as usize]
+        }
+    }
+}
+
+impl <T, const W: usize, const H: usize, B: Boundary> IntoIterator for PlaneGrid<T, W, H, B> {
+    type Item = (PlanePoint<W, H, B>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let data: Vec<_> = self.data.into_iter()
+            .enumerate()
+            .flat_map(|(y, subarray)| subarray.into_iter()
+                      .enumerate()
+                      .map(move |(x, value)| (PlanePoint::new(x as u32, y as u32), value))
+                      )
+            .collect();
+
+        data.into_iter()
+    }
+}
+
+/// A point on a `PlaneGrid`.
+///
+/// Stepping off any edge is resolved by the boundary policy `B`.
+/// Points outside the grid stay outside the grid in every direction.
+///
+/// # Type Parameters
+/// - `B` - The boundary policy for every edge.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid.
+/// - `H` - The height of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlanePoint<const W: usize, const H: usize, B: Boundary = Clamp> {
+    /// The X position in the grid.
+    x: u32,
+    /// The Y position in the grid.
+    y: u32,
+    /// Whether the point is outside the grid.
+    outside: bool,
+    boundary: PhantomData<B>,
+}
+
+impl <const W: usize, const H: usize, B: Boundary> PlanePoint<W, H, B> {
+    fn new(x: u32, y: u32) -> Self {
+        Self {
+            x: x.min(W as u32 - 1),
+            y: y.min(H as u32 - 1),
+            outside: false,
+            boundary: PhantomData,
+        }
+    }
+
+    fn new_outside() -> Self {
+        Self {
+            x: 0,
+            y: 0,
+            outside: true,
+            boundary: PhantomData,
+        }
+    }
+
+    /// Checks whether the point is outside the grid.
+    pub fn is_outside(&self) -> bool {
+        self.outside
+    }
+
+    fn step(&self, x: Option<u32>, y: Option<u32>) -> Self {
+        match (x, y) {
+            (Some(x), Some(y)) => Self {
+                x,
+                y,
+                ..*self
+            },
+            _ => Self::new_outside(),
+        }
+    }
+}
+
+impl <const W: usize, const H: usize, B: Boundary> GridPoint for PlanePoint<W, H, B> {
+    fn up(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        self.step(Some(self.x), B::previous(self.y, H as u32))
+    }
+
+    fn down(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        self.step(Some(self.x), B::next(self.y, H as u32))
+    }
+
+    fn left(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        self.step(B::previous(self.x, W as u32), Some(self.y))
+    }
+
+    fn right(&self) -> Self {
+        if self.outside {
+            return *self;
+        }
+
+        self.step(B::next(self.x, W as u32), Some(self.y))
+    }
+
+    /// Gets the position of the centre of the cell in 3D space.
+    ///
+    /// The grid lies in the XY plane centred on the origin with square cells, and its longest side
+    /// is `2 * scale` long.
+    /// Points outside the grid are placed at the origin.
+    ///
+    /// - `scale` - The scale of the 3D object.
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        if self.outside {
+            return (0.0, 0.0, 0.0);
+        }
+
+        let cell = 2.0 * scale / W.max(H) as f64;
+
+        let x = (self.x as f64 + 0.5 - W as f64 / 2.0) * cell;
+        let y = (H as f64 / 2.0 - self.y as f64 - 0.5) * cell;
+
+        (x, y, 0.0)
+    }
+}
+
+impl <const W: usize, const H: usize, B: Boundary> IndexedPoint for PlanePoint<W, H, B> {
+    const COUNT: usize = W * H;
+
+    fn to_index(&self) -> usize {
+        assert!(!self.outside, "The point is outside the grid");
+
+        self.y as usize * W + self.x as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        Self::new((index % W) as u32, (index / W) as u32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{boundary::{Clamp, Constant, Periodic, Reflect}, test::assert_indexed_points, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    use super::{PlaneGrid, PlanePoint};
+
+    #[test]
+    fn test_plane_point_middle() {
+        let point: PlanePoint<10, 5> = PlanePoint::new(3, 2);
+
+        assert_eq!(PlanePoint::new(3, 1), point.up());
+        assert_eq!(PlanePoint::new(3, 3), point.down());
+        assert_eq!(PlanePoint::new(2, 2), point.left());
+        assert_eq!(PlanePoint::new(4, 2), point.right());
+    }
+
+    #[test]
+    fn test_plane_point_periodic() {
+        let point: PlanePoint<10, 5, Periodic> = PlanePoint::new(0, 4);
+
+        assert_eq!(PlanePoint::new(9, 4), point.left());
+        assert_eq!(PlanePoint::new(0, 0), point.down());
+    }
+
+    #[test]
+    fn test_plane_point_clamp() {
+        let point: PlanePoint<10, 5, Clamp> = PlanePoint::new(9, 0);
+
+        assert_eq!(point, point.up());
+        assert_eq!(point, point.right());
+    }
+
+    #[test]
+    fn test_plane_point_reflect() {
+        let point: PlanePoint<10, 5, Reflect> = PlanePoint::new(0, 4);
+
+        assert_eq!(PlanePoint::new(1, 4), point.left());
+        assert_eq!(PlanePoint::new(0, 3), point.down());
+    }
+
+    #[test]
+    fn test_plane_point_constant() {
+        let point: PlanePoint<10, 5, Constant> = PlanePoint::new(9, 2);
+
+        assert!(point.right().is_outside());
+        assert!(point.right().left().is_outside());
+        assert!(!point.left().is_outside());
+    }
+
+    #[test]
+    fn test_plane_from_neighbours_periodic() {
+        let grid: PlaneGrid<u32, 20, 10, Periodic> = PlaneGrid::from_fn(|point| point.x);
+
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert_eq!(20, grid2[PlanePoint::new(0, 0)]);
+    }
+
+    #[test]
+    fn test_plane_from_neighbours_constant() {
+        let grid: PlaneGrid<u32, 20, 10, Constant> = PlaneGrid::from_fn_with_outside(100, |_| 1);
+
+        let grid2 = grid.map_neighbours_diagonals(|up_left, up, _, _, _, _, _, _, _| up_left + up);
+
+        assert_eq!(200, grid2[PlanePoint::new(0, 0)]);
+        assert_eq!(200, grid2[PlanePoint::new(5, 0)]);
+        assert_eq!(101, grid2[PlanePoint::new(0, 5)]);
+        assert_eq!(2, grid2[PlanePoint::new(5, 5)]);
+    }
+
+    #[test]
+    fn test_plane_set_from_neighbours_par() {
+        let grid: PlaneGrid<u32, 20, 10, Reflect> = PlaneGrid::from_fn(|point| point.x + point.y);
+        let grid2 = grid.map_neighbours(|_, _, _, left, _| *left);
+
+        let mut grid3 = grid.clone();
+        grid3.set_from_neighbours_par(&grid, |_, _, _, left, _| *left);
+
+        assert_eq!(grid2, grid3);
+        assert_eq!(1, grid3[PlanePoint::new(0, 0)]);
+    }
+
+    #[test]
+    fn test_plane_position() {
+        let point: PlanePoint<4, 2> = PlanePoint::new(0, 0);
+
+        let (x, y, z) = point.position(2.0);
+
+        assert_relative_eq!(-1.5, x);
+        assert_relative_eq!(0.5, y);
+        assert_relative_eq!(0.0, z);
+    }
+
+    #[test]
+    fn test_plane_indexed_points() {
+        assert_indexed_points(&PlaneGrid::<(), 7, 5>::default());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_plane_outside_has_no_index() {
+        let point: PlanePoint<7, 5, Constant> = PlanePoint::new(0, 0);
+
+        point.left().to_index();
+    }
+}
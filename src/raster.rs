@@ -0,0 +1,837 @@
+//! A module for filling sphere grids from equirectangular rasters such as elevation maps.
+//!
+//! A raster covers the whole sphere with longitude increasing from π at the left edge and
+//! latitude decreasing from the north pole at the top edge, in the same way as
+//! `Projection::Equirectangular`.
+//! With the `images` feature rasters can also be read from PNG and PPM images.
+
+use std::{collections::HashMap, error::Error, f64::consts::PI, fmt::{self, Display, Formatter}, hash::Hash, io};
+
+use rayon::prelude::*;
+
+use crate::{sphere::{SpherePoint, StaticSpherePoint}, StaticSurfaceGrid, SurfaceGrid};
+
+/// The way that the value of each cell is taken from a raster.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Sampling {
+    /// Takes the pixel containing the location of each point.
+    #[default]
+    Nearest,
+    /// Interpolates between the four pixels around the location of each point.
+    Bilinear,
+    /// Averages the pixels whose centres lie within each cell weighted by their area.
+    /// Cells that are too small to contain the centre of any pixel are sampled bilinearly.
+    AreaAverage,
+}
+
+/// A value that can be blended with others when sampling a raster.
+///
+/// Values are blended by adding each of them to a running weighted sum with `accumulate` and
+/// then taking the weighted mean of the sum with `finish`.
+pub trait Interpolate : Clone {
+    /// The running weighted sum of some values.
+    type Accumulator;
+
+    /// Creates a sum of no values.
+    fn accumulator() -> Self::Accumulator;
+
+    /// Adds this value to a running weighted sum.
+    ///
+    /// - `accumulator` - The sum to add to.
+    /// - `weight` - The weight of this value, which is not negative.
+    fn accumulate(&self, accumulator: &mut Self::Accumulator, weight: f64);
+
+    /// Gets the weighted mean of the values in a sum.
+    ///
+    /// - `accumulator` - The sum of the values.
+    /// - `total` - The total weight of the values, which is greater than zero.
+    fn finish(accumulator: Self::Accumulator, total: f64) -> Self;
+}
+
+/// Implements `Interpolate` for float types.
+macro_rules! impl_interpolate_float {
+    ($($t:ty),*) => {
+        $(
+            impl Interpolate for $t {
+                type Accumulator = f64;
+
+                fn accumulator() -> f64 {
+                    0.0
+                }
+
+                fn accumulate(&self, accumulator: &mut f64, weight: f64) {
+                    *accumulator += *self as f64 * weight;
+                }
+
+                fn finish(accumulator: f64, total: f64) -> Self {
+                    (accumulator / total) as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_float!(f32, f64);
+
+/// Implements `Interpolate` for integer types by rounding to the nearest value.
+macro_rules! impl_interpolate_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Interpolate for $t {
+                type Accumulator = f64;
+
+                fn accumulator() -> f64 {
+                    0.0
+                }
+
+                fn accumulate(&self, accumulator: &mut f64, weight: f64) {
+                    *accumulator += *self as f64 * weight;
+                }
+
+                fn finish(accumulator: f64, total: f64) -> Self {
+                    (accumulator / total).round() as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_interpolate_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Takes the value with the greater total weight, such as the majority of a land mask.
+impl Interpolate for bool {
+    /// The total weight of the values that are true.
+    type Accumulator = f64;
+
+    fn accumulator() -> f64 {
+        0.0
+    }
+
+    fn accumulate(&self, accumulator: &mut f64, weight: f64) {
+        if *self {
+            *accumulator += weight;
+        }
+    }
+
+    fn finish(accumulator: f64, total: f64) -> Self {
+        accumulator / total >= 0.5
+    }
+}
+
+impl <T: Interpolate, const N: usize> Interpolate for [T; N] {
+    type Accumulator = [T::Accumulator; N];
+
+    fn accumulator() -> Self::Accumulator {
+        std::array::from_fn(|_| T::accumulator())
+    }
+
+    fn accumulate(&self, accumulator: &mut Self::Accumulator, weight: f64) {
+        for (value, accumulator) in self.iter().zip(accumulator) {
+            value.accumulate(accumulator, weight);
+        }
+    }
+
+    fn finish(accumulator: Self::Accumulator, total: f64) -> Self {
+        accumulator.map(|accumulator| T::finish(accumulator, total))
+    }
+}
+
+/// An error from creating or reading a raster.
+#[derive(Debug)]
+pub enum RasterError {
+    /// The raster has no pixels.
+    Empty,
+    /// The number of values does not match the dimensions of the raster.
+    LengthMismatch {
+        /// The expected number of values.
+        expected: usize,
+        /// The number of values found.
+        found: usize,
+    },
+    /// Reading an image failed.
+    Io(io::Error),
+    /// An image could not be decoded.
+    Format(String),
+}
+
+impl Display for RasterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "A raster must have at least one pixel"),
+            Self::LengthMismatch { expected, found } => write!(f, "Expected {} values but found {}", expected, found),
+            Self::Io(error) => write!(f, "Failed to read the image: {}", error),
+            Self::Format(message) => write!(f, "The image could not be decoded: {}", message),
+        }
+    }
+}
+
+impl Error for RasterError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RasterError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// An equirectangular raster of values covering the whole sphere.
+///
+/// # Type Parameters
+/// - `T` - The type of each pixel.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Raster<T> {
+    /// The width of the raster.
+    width: usize,
+    /// The height of the raster.
+    height: usize,
+    /// The pixels of the raster with rows stored from top to bottom.
+    data: Vec<T>,
+}
+
+/// The number of rows of a raster whose cells are found at once when averaging by area in
+/// parallel.
+const PARALLEL_ROWS: usize = 64;
+
+impl <T> Raster<T> {
+    /// Creates a new raster from its pixels.
+    /// Returns an error if there are no pixels or the number of pixels does not match the
+    /// dimensions.
+    ///
+    /// - `width` - The width of the raster.
+    /// - `height` - The height of the raster.
+    /// - `data` - The pixels with rows stored from top to bottom.
+    pub fn new(width: usize, height: usize, data: Vec<T>) -> Result<Self, RasterError> {
+        if width == 0 || height == 0 {
+            return Err(RasterError::Empty);
+        }
+
+        if data.len() != width * height {
+            return Err(RasterError::LengthMismatch { expected: width * height, found: data.len() });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            data,
+        })
+    }
+
+    /// Creates a new raster by calling the specified function with the X and Y coordinates of each
+    /// pixel.
+    ///
+    /// - `width` - The width of the raster.
+    /// - `height` - The height of the raster.
+    /// - `f` - The function to apply.
+    pub fn from_fn<F: FnMut(usize, usize) -> T>(width: usize, height: usize, mut f: F) -> Self {
+        assert!(width > 0 && height > 0, "A raster must have at least one pixel");
+
+        Self {
+            width,
+            height,
+            data: (0..height)
+                .flat_map(|y| (0..width).map(move |x| (x, y)))
+                .map(|(x, y)| f(x, y))
+                .collect(),
+        }
+    }
+
+    /// Gets the width of this raster.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Gets the height of this raster.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Gets the pixels of this raster with rows stored from top to bottom.
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Gets a pixel of this raster.
+    ///
+    /// - `x` - The X coordinate of the pixel.
+    /// - `y` - The Y coordinate of the pixel.
+    pub fn pixel(&self, x: usize, y: usize) -> &T {
+        assert!(x < self.width && y < self.height, "The pixel is outside the raster");
+
+        &self.data[y * self.width + x]
+    }
+
+    /// Creates a new raster by applying a function to each pixel, such as to turn colours into
+    /// heights.
+    ///
+    /// - `f` - The function to apply.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Raster<U> {
+        Raster {
+            width: self.width,
+            height: self.height,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+
+    /// Gets the latitude and longitude at the centre of a pixel.
+    ///
+    /// - `x` - The X coordinate of the pixel.
+    /// - `y` - The Y coordinate of the pixel.
+    pub fn geographic(&self, x: usize, y: usize) -> (f64, f64) {
+        let latitude = PI / 2.0 - (y as f64 + 0.5) / self.height as f64 * PI;
+        let longitude = ((x as f64 + 0.5) / self.width as f64 * PI * 2.0 - PI).rem_euclid(PI * 2.0);
+
+        (latitude, longitude)
+    }
+
+    /// Gets the pixel containing a location.
+    ///
+    /// - `latitude` - The latitude in radians where 0 is the equator.
+    /// - `longitude` - The longitude in radians.
+    pub fn nearest(&self, latitude: f64, longitude: f64) -> &T {
+        let x = ((longitude / (PI * 2.0) + 0.5).rem_euclid(1.0) * self.width as f64) as usize;
+        let y = ((PI / 2.0 - latitude) / PI * self.height as f64).max(0.0) as usize;
+
+        &self.data[y.min(self.height - 1) * self.width + x.min(self.width - 1)]
+    }
+
+    /// Interpolates between the four pixels around a location.
+    /// Longitudes wrap around the sphere while latitudes beyond the centres of the top and bottom
+    /// rows take the value of those rows.
+    ///
+    /// - `latitude` - The latitude in radians where 0 is the equator.
+    /// - `longitude` - The longitude in radians.
+    pub fn bilinear(&self, latitude: f64, longitude: f64) -> T where T: Interpolate {
+        // The position in pixels relative to the centre of the top left pixel.
+        let x = (longitude / (PI * 2.0) + 0.5).rem_euclid(1.0) * self.width as f64 - 0.5;
+        let y = ((PI / 2.0 - latitude) / PI * self.height as f64 - 0.5).clamp(0.0, (self.height - 1) as f64);
+
+        let left = x.floor();
+        let top = y.floor();
+
+        let tx = x - left;
+        let ty = y - top;
+
+        let x0 = (left as isize).rem_euclid(self.width as isize) as usize;
+        let x1 = (x0 + 1) % self.width;
+        let y0 = top as usize;
+        let y1 = (y0 + 1).min(self.height - 1);
+
+        let mut accumulator = T::accumulator();
+
+        self.pixel(x0, y0).accumulate(&mut accumulator, (1.0 - tx) * (1.0 - ty));
+        self.pixel(x1, y0).accumulate(&mut accumulator, tx * (1.0 - ty));
+        self.pixel(x0, y1).accumulate(&mut accumulator, (1.0 - tx) * ty);
+        self.pixel(x1, y1).accumulate(&mut accumulator, tx * ty);
+
+        T::finish(accumulator, 1.0)
+    }
+
+    /// Sets every cell of a grid from this raster.
+    ///
+    /// - `grid` - The grid to fill.
+    /// - `sampling` - The way that each cell is sampled.
+    pub fn fill<G: SurfaceGrid<T>>(&self, grid: &mut G, sampling: Sampling)
+            where T: Interpolate, G::Point: SpherePoint + Hash {
+        let cells = match (sampling, grid.points().next()) {
+            (Sampling::AreaAverage, Some(origin)) => self.cell_averages(&origin),
+            _ => HashMap::new(),
+        };
+
+        grid.set_from_fn(|point| self.sample(point, sampling, &cells))
+    }
+
+    /// Sets every cell of a grid from this raster in parallel.
+    ///
+    /// - `grid` - The grid to fill.
+    /// - `sampling` - The way that each cell is sampled.
+    pub fn fill_par<G: SurfaceGrid<T>>(&self, grid: &mut G, sampling: Sampling)
+            where T: Interpolate + Send + Sync, G::Point: SpherePoint + Hash + Sync {
+        let cells = match (sampling, grid.points().next()) {
+            (Sampling::AreaAverage, Some(origin)) => self.cell_averages_par(&origin),
+            _ => HashMap::new(),
+        };
+
+        grid.set_from_fn_par(|point| self.sample(point, sampling, &cells))
+    }
+
+    /// Creates a new grid from this raster.
+    ///
+    /// - `sampling` - The way that each cell is sampled.
+    pub fn to_grid<G: StaticSurfaceGrid<T>>(&self, sampling: Sampling) -> G
+            where T: Interpolate, G::Point: StaticSpherePoint + Hash {
+        let cells = match sampling {
+            Sampling::AreaAverage => self.cell_averages(&G::Point::from_geographic(0.0, 0.0)),
+            _ => HashMap::new(),
+        };
+
+        G::from_fn(|point| self.sample(point, sampling, &cells))
+    }
+
+    /// Creates a new grid from this raster in parallel.
+    ///
+    /// - `sampling` - The way that each cell is sampled.
+    pub fn to_grid_par<G: StaticSurfaceGrid<T>>(&self, sampling: Sampling) -> G
+            where T: Interpolate + Send + Sync, G::Point: StaticSpherePoint + Hash + Sync {
+        let cells = match sampling {
+            Sampling::AreaAverage => self.cell_averages_par(&G::Point::from_geographic(0.0, 0.0)),
+            _ => HashMap::new(),
+        };
+
+        G::from_fn_par(|point| self.sample(point, sampling, &cells))
+    }
+
+    /// Gets the value of a cell.
+    ///
+    /// - `point` - The point of the cell.
+    /// - `sampling` - The way that the cell is sampled.
+    /// - `cells` - The average of the pixels within each cell when averaging by area.
+    fn sample<P: SpherePoint + Hash>(&self, point: &P, sampling: Sampling, cells: &HashMap<P, T>) -> T where T: Interpolate {
+        let latitude = point.latitude();
+        let longitude = point.longitude();
+
+        match sampling {
+            Sampling::Nearest => self.nearest(latitude, longitude).clone(),
+            Sampling::Bilinear => self.bilinear(latitude, longitude),
+            Sampling::AreaAverage => match cells.get(point) {
+                Some(value) => value.clone(),
+                None => self.bilinear(latitude, longitude),
+            },
+        }
+    }
+
+    /// Averages the pixels whose centres lie within each cell weighted by their area.
+    ///
+    /// - `origin` - Any point on the grid.
+    fn cell_averages<P: SpherePoint + Hash>(&self, origin: &P) -> HashMap<P, T> where T: Interpolate {
+        let mut sums = HashMap::new();
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (point, i, area) = self.pixel_cell(origin, x, y);
+
+                self.add_pixel(&mut sums, point, i, area);
+            }
+        }
+
+        Self::finish_averages(sums)
+    }
+
+    /// Averages the pixels whose centres lie within each cell weighted by their area, finding
+    /// the cells in parallel.
+    ///
+    /// - `origin` - Any point on the grid.
+    fn cell_averages_par<P: SpherePoint + Hash + Sync + Send>(&self, origin: &P) -> HashMap<P, T> where T: Interpolate + Sync {
+        let mut sums = HashMap::new();
+
+        // The cells are found a few rows at a time so that only those pixels are held at once.
+        for start in (0..self.height).step_by(PARALLEL_ROWS) {
+            let pixels: Vec<_> = (start..(start + PARALLEL_ROWS).min(self.height)).into_par_iter()
+                .flat_map_iter(|y| (0..self.width).map(move |x| self.pixel_cell(origin, x, y)))
+                .collect();
+
+            for (point, i, area) in pixels {
+                self.add_pixel(&mut sums, point, i, area);
+            }
+        }
+
+        Self::finish_averages(sums)
+    }
+
+    /// Adds a pixel to the running sum of the cell containing it.
+    ///
+    /// - `sums` - The weighted sum and total area of the pixels within each cell.
+    /// - `point` - The cell containing the pixel.
+    /// - `i` - The index of the pixel.
+    /// - `area` - The relative area of the pixel.
+    fn add_pixel<P: Hash + Eq>(&self, sums: &mut HashMap<P, (T::Accumulator, f64)>, point: P, i: usize, area: f64) where T: Interpolate {
+        let (accumulator, total) = sums.entry(point).or_insert_with(|| (T::accumulator(), 0.0));
+
+        self.data[i].accumulate(accumulator, area);
+        *total += area;
+    }
+
+    /// Takes the mean of the pixels within each cell from their running sums.
+    ///
+    /// - `sums` - The weighted sum and total area of the pixels within each cell.
+    fn finish_averages<P: Hash + Eq>(sums: HashMap<P, (T::Accumulator, f64)>) -> HashMap<P, T> where T: Interpolate {
+        sums.into_iter()
+            .map(|(point, (accumulator, total))| (point, T::finish(accumulator, total)))
+            .collect()
+    }
+
+    /// Gets the cell containing the centre of a pixel, the index of the pixel and its relative
+    /// area on the sphere.
+    ///
+    /// - `origin` - Any point on the grid.
+    /// - `x` - The X coordinate of the pixel.
+    /// - `y` - The Y coordinate of the pixel.
+    fn pixel_cell<P: SpherePoint>(&self, origin: &P, x: usize, y: usize) -> (P, usize, f64) {
+        let (latitude, longitude) = self.geographic(x, y);
+
+        (origin.at_geographic(latitude, longitude), y * self.width + x, latitude.cos())
+    }
+}
+
+#[cfg(feature = "images")]
+impl Raster<[u8; 4]> {
+    /// Reads a raster of RGBA colours from a PNG image.
+    /// Greyscale, indexed and 16 bit images are converted to 8 bit RGBA.
+    ///
+    /// - `reader` - The reader to read from.
+    pub fn read_png<R: io::Read>(reader: R) -> Result<Self, RasterError> {
+        let mut decoder = png::Decoder::new(reader);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info().map_err(png_error)?;
+
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(png_error)?;
+
+        let width = info.width as usize;
+        let height = info.height as usize;
+
+        let pixel = |bytes: &[u8]| match info.color_type {
+            png::ColorType::Grayscale => Ok([bytes[0], bytes[0], bytes[0], 255]),
+            png::ColorType::GrayscaleAlpha => Ok([bytes[0], bytes[0], bytes[0], bytes[1]]),
+            png::ColorType::Rgb => Ok([bytes[0], bytes[1], bytes[2], 255]),
+            png::ColorType::Rgba => Ok([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            png::ColorType::Indexed => Err(RasterError::Format("Indexed colours were not expanded".to_string())),
+        };
+
+        let channels = info.color_type.samples();
+
+        let data = buffer.chunks(info.line_size)
+            .take(height)
+            .flat_map(|row| row[..width * channels].chunks_exact(channels))
+            .map(pixel)
+            .collect::<Result<_, _>>()?;
+
+        Self::new(width, height, data)
+    }
+
+    /// Reads a raster of RGBA colours from a binary PPM or PGM image.
+    /// Every pixel is opaque.
+    ///
+    /// - `reader` - The reader to read from.
+    pub fn read_ppm<R: io::Read>(mut reader: R) -> Result<Self, RasterError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+
+        let format = |message: &str| RasterError::Format(message.to_string());
+
+        let channels = match bytes.get(..2) {
+            Some(b"P6") => 3,
+            Some(b"P5") => 1,
+            _ => return Err(format("Expected a binary PPM or PGM image")),
+        };
+
+        // The header is the magic number followed by the width, height and maximum value, which
+        // may be separated by comments.
+        let mut position = 2;
+        let mut fields = [0; 3];
+
+        for field in &mut fields {
+            loop {
+                match bytes.get(position) {
+                    Some(b'#') => while bytes.get(position).is_some_and(|byte| *byte != b'\n') {
+                        position += 1;
+                    },
+                    Some(byte) if byte.is_ascii_whitespace() => position += 1,
+                    _ => break,
+                }
+            }
+
+            let start = position;
+
+            while bytes.get(position).is_some_and(u8::is_ascii_digit) {
+                position += 1;
+            }
+
+            *field = std::str::from_utf8(&bytes[start..position])
+                .expect("Digits are valid UTF-8")
+                .parse::<usize>()
+                .map_err(|_| format("Expected a number in the header"))?;
+        }
+
+        let [width, height, maximum] = fields;
+
+        if maximum == 0 || maximum > u16::MAX as usize {
+            return Err(format("The maximum value must be between 1 and 65535"));
+        }
+
+        // A single whitespace character separates the header from the pixels.
+        position += 1;
+
+        let sample_size = if maximum < 256 { 1 } else { 2 };
+
+        let length = width.checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(channels * sample_size))
+            .ok_or_else(|| format("The image is too large"))?;
+
+        let samples = bytes.get(position..)
+            .and_then(|samples| samples.get(..length))
+            .ok_or_else(|| RasterError::Io(io::Error::new(io::ErrorKind::UnexpectedEof, "The image ended early")))?;
+
+        let sample = |bytes: &[u8]| {
+            let value = if sample_size == 1 {
+                bytes[0] as usize
+            } else {
+                u16::from_be_bytes([bytes[0], bytes[1]]) as usize
+            };
+
+            ((value.min(maximum) * 255 + maximum / 2) / maximum) as u8
+        };
+
+        let data = samples.chunks_exact(channels * sample_size)
+            .map(|pixel| {
+                let mut channel = pixel.chunks_exact(sample_size).map(sample);
+
+                if channels == 1 {
+                    let value = channel.next().expect("A pixel has one sample");
+
+                    [value, value, value, 255]
+                } else {
+                    let mut next = || channel.next().expect("A pixel has three samples");
+
+                    [next(), next(), next(), 255]
+                }
+            })
+            .collect();
+
+        Self::new(width, height, data)
+    }
+}
+
+/// Converts an error from decoding a PNG image.
+///
+/// - `error` - The error to convert.
+#[cfg(feature = "images")]
+fn png_error(error: png::DecodingError) -> RasterError {
+    match error {
+        png::DecodingError::IoError(error) => RasterError::Io(error),
+        error => RasterError::Format(error.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use approx::assert_relative_eq;
+
+    use crate::{sphere::{CubeSphereGrid, DynCubeSphereGrid, HealpixSphereGrid, NestedOrdering, RectangleSphereGrid, SpherePoint}, SurfaceGrid};
+
+    use super::{Interpolate, Raster, RasterError, Sampling};
+
+    #[test]
+    fn test_raster_new_wrong_length() {
+        assert!(matches!(
+            Raster::new(3, 2, vec![0; 5]),
+            Err(RasterError::LengthMismatch { expected: 6, found: 5 })
+        ));
+        assert!(matches!(Raster::<u8>::new(0, 2, vec![]), Err(RasterError::Empty)));
+    }
+
+    #[test]
+    fn test_raster_geographic_nearest() {
+        let raster = Raster::from_fn(8, 4, |x, y| (x, y));
+
+        for y in 0..4 {
+            for x in 0..8 {
+                let (latitude, longitude) = raster.geographic(x, y);
+
+                assert_eq!((x, y), *raster.nearest(latitude, longitude));
+            }
+        }
+
+        assert_relative_eq!(PI / 2.0 - PI / 8.0, raster.geographic(0, 0).0);
+        assert_eq!((4, 3), *raster.nearest(-PI / 2.0, 0.0));
+        assert_eq!((7, 2), *raster.nearest(0.0, PI - 0.01));
+    }
+
+    #[test]
+    fn test_raster_bilinear_wraps() {
+        let raster = Raster::new(4, 1, vec![0.0, 0.0, 0.0, 4.0]).unwrap();
+
+        // Halfway between the last pixel and the first pixel.
+        assert_relative_eq!(2.0, raster.bilinear(0.0, PI));
+        assert_relative_eq!(4.0, raster.bilinear(0.0, PI * 3.0 / 4.0));
+        assert_relative_eq!(1.0, raster.bilinear(1.0, PI * 5.0 / 4.0 - PI / 8.0));
+    }
+
+    #[test]
+    fn test_raster_bilinear_gradient() {
+        // A smooth field is reproduced closely.
+        let raster = Raster::from_fn(360, 180, |x, y| x as f64 + y as f64 * 2.0);
+
+        let (latitude, longitude) = raster.geographic(100, 50);
+
+        assert_relative_eq!(200.0, raster.bilinear(latitude, longitude), epsilon = 1e-9);
+        assert_relative_eq!(201.5, raster.bilinear(latitude - PI / 360.0, longitude + PI / 360.0), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_raster_area_average_same_dimensions() {
+        let raster = Raster::from_fn(20, 10, |x, y| (y * 20 + x) as u32);
+
+        let grid: RectangleSphereGrid<u32, 20, 10> = raster.to_grid(Sampling::AreaAverage);
+
+        // Each cell holds the centre of exactly one pixel and the raster starts at longitude π.
+        for (point, value) in grid.iter() {
+            assert_eq!(raster.pixel((point.x() + 10) % 20, point.y()), value);
+        }
+    }
+
+    #[test]
+    fn test_raster_nearest_at_points() {
+        let raster = Raster::from_fn(40, 20, |x, y| [x, y]);
+
+        let grid: CubeSphereGrid<[usize; 2], 3> = raster.to_grid(Sampling::Nearest);
+
+        for (point, value) in grid.iter() {
+            assert_eq!(raster.nearest(point.latitude(), point.longitude()), value);
+        }
+    }
+
+    #[test]
+    fn test_raster_area_average_constant() {
+        let raster = Raster::from_fn(64, 32, |_, _| 7.5);
+
+        let grid: CubeSphereGrid<f64, 4> = raster.to_grid_par(Sampling::AreaAverage);
+
+        assert!(grid.iter().all(|(_, value)| (value - 7.5).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_raster_area_average_preserves_mean() {
+        // A striped raster has detail smaller than the cells of the grid.
+        let raster = Raster::from_fn(256, 128, |x, _| if x % 2 == 0 { 1.0 } else { 0.0 });
+
+        let area: CubeSphereGrid<f64, 4> = raster.to_grid(Sampling::AreaAverage);
+        let nearest: CubeSphereGrid<f64, 4> = raster.to_grid(Sampling::Nearest);
+
+        for (_, value) in area.iter() {
+            assert_relative_eq!(0.5, *value, epsilon = 0.1);
+        }
+
+        assert!(nearest.iter().all(|(_, value)| *value == 0.0 || *value == 1.0));
+    }
+
+    #[test]
+    fn test_raster_area_average_small_cells() {
+        // The grid is finer than the raster so some cells hold no pixel centres.
+        let raster = Raster::from_fn(8, 4, |x, y| (x + y) as f64);
+
+        let mut area = DynCubeSphereGrid::new(16);
+        let mut bilinear = DynCubeSphereGrid::new(16);
+
+        raster.fill(&mut area, Sampling::AreaAverage);
+        raster.fill_par(&mut bilinear, Sampling::Bilinear);
+
+        let missing = area.iter()
+            .zip(bilinear.iter())
+            .filter(|((_, a), (_, b))| a == b)
+            .count();
+
+        assert!(missing > 0);
+        assert!(area.iter().all(|(_, value)| (0.0..=10.0).contains(value)));
+    }
+
+    #[test]
+    fn test_raster_fill_healpix() {
+        let raster = Raster::from_fn(96, 48, |_, y| y < 24);
+
+        let mut grid: HealpixSphereGrid<bool, 4, NestedOrdering> = HealpixSphereGrid::default();
+
+        raster.fill(&mut grid, Sampling::AreaAverage);
+
+        // Cells on the equator are split between both halves.
+        for (point, value) in grid.iter().filter(|(point, _)| point.latitude().abs() > 0.2) {
+            assert_eq!(point.latitude() > 0.0, *value);
+        }
+    }
+
+    /// Gets the weighted mean of some values.
+    fn interpolate<T: Interpolate>(values: &[(T, f64)]) -> T {
+        let mut accumulator = T::accumulator();
+
+        for (value, weight) in values {
+            value.accumulate(&mut accumulator, *weight);
+        }
+
+        T::finish(accumulator, values.iter().map(|(_, weight)| weight).sum())
+    }
+
+    #[test]
+    fn test_raster_interpolate_integer_and_array() {
+        assert_eq!(3u8, interpolate(&[(2u8, 0.4), (4, 0.6)]));
+        assert_eq!(-3i32, interpolate(&[(-2i32, 0.4), (-4, 0.6)]));
+        assert_eq!([10u8, 15, 0, 255], interpolate(&[([0u8, 10, 0, 255], 0.5), ([20, 20, 0, 255], 0.5)]));
+        assert!(interpolate(&[(true, 0.5), (false, 0.5)]));
+        assert!(!interpolate(&[(true, 0.25), (false, 0.75)]));
+    }
+
+    #[test]
+    fn test_raster_map() {
+        let raster = Raster::from_fn(2, 2, |x, y| [x as u8, y as u8, 0, 255]);
+
+        let heights = raster.map(|pixel| pixel[0] as f64 * 100.0);
+
+        assert_eq!(&[0.0, 100.0, 0.0, 100.0], heights.data());
+    }
+
+    #[cfg(feature = "images")]
+    mod images {
+        use crate::{render::{Colormap, Projection, Renderer}, sphere::RectangleSphereGrid, StaticSurfaceGrid};
+
+        use super::{Raster, RasterError};
+
+        #[test]
+        fn test_raster_read_png() {
+            let grid: RectangleSphereGrid<f64, 16, 8> = RectangleSphereGrid::from_fn(|point| (point.x() + point.y()) as f64);
+            let image = Renderer::new(Projection::Equirectangular, Colormap::Viridis).render(&grid, 16, 8, |value| *value);
+
+            let mut bytes = Vec::new();
+            image.write_png(&mut bytes).unwrap();
+
+            let raster = Raster::read_png(&bytes[..]).unwrap();
+
+            assert_eq!(Raster::from_fn(16, 8, |x, y| image.pixel(x, y)), raster);
+        }
+
+        #[test]
+        fn test_raster_read_ppm() {
+            let grid: RectangleSphereGrid<f64, 5, 3> = RectangleSphereGrid::from_fn(|point| point.x() as f64);
+            let image = Renderer::new(Projection::Equirectangular, Colormap::Grayscale).render(&grid, 5, 3, |value| *value);
+
+            let mut bytes = Vec::new();
+            image.write_ppm(&mut bytes).unwrap();
+
+            let raster = Raster::read_ppm(&bytes[..]).unwrap();
+
+            assert_eq!(Raster::from_fn(5, 3, |x, y| image.pixel(x, y)), raster);
+        }
+
+        #[test]
+        fn test_raster_read_pgm_comments() {
+            let bytes = b"P5\n# A comment\n2 1 # Another\n1000\n\x00\x00\x03\xe8";
+
+            let raster = Raster::read_ppm(&bytes[..]).unwrap();
+
+            assert_eq!(&[[0, 0, 0, 255], [255, 255, 255, 255]], raster.data());
+        }
+
+        #[test]
+        fn test_raster_read_ppm_truncated() {
+            assert!(matches!(Raster::read_ppm(&b"P6 2 2 255\n\x00\x00\x00"[..]), Err(RasterError::Io(_))));
+            assert!(matches!(Raster::read_ppm(&b"P3 1 1 255\n0 0 0"[..]), Err(RasterError::Format(_))));
+            assert!(matches!(Raster::read_png(&b"P6 1 1 255\n\x00\x00\x00"[..]), Err(RasterError::Format(_))));
+        }
+    }
+}
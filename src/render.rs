@@ -0,0 +1,671 @@
+//! A module for drawing spherical grids into images without a window.
+//!
+//! A `Renderer` combines a `Projection` that decides which point of the grid each pixel shows
+//! with a `Colormap` that turns the value of that point into a colour.
+//! Images can be drawn into any RGBA buffer or saved as PPM files, or as PNG files with the
+//! `images` feature.
+
+use std::{f64::consts::PI, io::{self, BufWriter, Write}};
+
+use rayon::{iter::{IndexedParallelIterator, ParallelIterator}, slice::ParallelSliceMut};
+
+use crate::{sphere::SpherePoint, SurfaceGrid};
+
+/// The way that the surface of a sphere is flattened into an image.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Projection {
+    /// Maps longitude to the X axis and latitude to the Y axis with longitude 0 in the centre and
+    /// north at the top.
+    #[default]
+    Equirectangular,
+    /// Shows the sphere as a globe seen from far away.
+    /// Pixels outside the globe are given the background colour.
+    Orthographic {
+        /// The latitude at the centre of the globe in radians.
+        latitude: f64,
+        /// The longitude at the centre of the globe in radians.
+        longitude: f64,
+    },
+}
+
+impl Projection {
+    /// Gets the latitude and longitude shown by a pixel.
+    /// Returns `None` if the pixel does not show the sphere.
+    ///
+    /// - `x` - The X coordinate of the pixel.
+    /// - `y` - The Y coordinate of the pixel.
+    /// - `width` - The width of the image.
+    /// - `height` - The height of the image.
+    pub fn geographic(&self, x: usize, y: usize, width: usize, height: usize) -> Option<(f64, f64)> {
+        // Pixels are sampled at their centres.
+        let x = x as f64 + 0.5;
+        let y = y as f64 + 0.5;
+
+        match *self {
+            Projection::Equirectangular => {
+                let latitude = PI / 2.0 - y / height as f64 * PI;
+                let longitude = (x / width as f64 * PI * 2.0 - PI).rem_euclid(PI * 2.0);
+
+                Some((latitude, longitude))
+            },
+            Projection::Orthographic { latitude: centre_latitude, longitude: centre_longitude } => {
+                // The globe fills the shorter side of the image.
+                let radius = width.min(height) as f64 / 2.0;
+
+                let u = (x - width as f64 / 2.0) / radius;
+                let v = (height as f64 / 2.0 - y) / radius;
+
+                let rho = (u * u + v * v).sqrt();
+
+                if rho > 1.0 {
+                    return None;
+                }
+
+                if rho == 0.0 {
+                    return Some((centre_latitude, centre_longitude.rem_euclid(PI * 2.0)));
+                }
+
+                let c = rho.asin();
+
+                let latitude = (c.cos() * centre_latitude.sin() + v * c.sin() * centre_latitude.cos() / rho)
+                    .clamp(-1.0, 1.0)
+                    .asin();
+                let longitude = centre_longitude + (u * c.sin()).atan2(
+                    rho * c.cos() * centre_latitude.cos() - v * c.sin() * centre_latitude.sin()
+                );
+
+                Some((latitude, longitude.rem_euclid(PI * 2.0)))
+            },
+        }
+    }
+}
+
+/// A way of turning values between 0 and 1 into colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Colormap {
+    /// A perceptually uniform map from dark purple through blue and green to yellow.
+    #[default]
+    Viridis,
+    /// A map from black to white.
+    Grayscale,
+    /// A map from blue through white to red for values either side of a midpoint.
+    Diverging,
+}
+
+/// Evenly spaced colours along the viridis colormap.
+const VIRIDIS: [[u8; 3]; 9] = [
+    [68, 1, 84],
+    [71, 44, 122],
+    [59, 81, 139],
+    [44, 113, 142],
+    [33, 144, 141],
+    [39, 173, 129],
+    [92, 200, 99],
+    [170, 220, 50],
+    [253, 231, 37],
+];
+
+/// Evenly spaced colours along the diverging colormap.
+const DIVERGING: [[u8; 3]; 5] = [
+    [59, 76, 192],
+    [141, 176, 254],
+    [245, 245, 245],
+    [244, 154, 123],
+    [180, 4, 38],
+];
+
+impl Colormap {
+    /// Gets the colour of a value as RGBA.
+    /// Values outside 0 to 1 are clamped.
+    ///
+    /// - `value` - The value between 0 and 1.
+    pub fn colour(&self, value: f64) -> [u8; 4] {
+        let value = value.clamp(0.0, 1.0);
+
+        let [r, g, b] = match self {
+            Colormap::Viridis => interpolate(&VIRIDIS, value),
+            Colormap::Grayscale => {
+                let v = (value * 255.0).round() as u8;
+
+                [v, v, v]
+            },
+            Colormap::Diverging => interpolate(&DIVERGING, value),
+        };
+
+        [r, g, b, 255]
+    }
+}
+
+/// Linearly interpolates between evenly spaced colours.
+///
+/// - `colours` - The colours to interpolate between.
+/// - `value` - The value between 0 and 1.
+fn interpolate(colours: &[[u8; 3]], value: f64) -> [u8; 3] {
+    let position = value * (colours.len() - 1) as f64;
+    let i = (position.floor() as usize).min(colours.len() - 2);
+    let t = position - i as f64;
+
+    let [a, b] = [colours[i], colours[i + 1]];
+
+    [0, 1, 2].map(|c| (a[c] as f64 + (b[c] as f64 - a[c] as f64) * t).round() as u8)
+}
+
+/// The range of values that is stretched across a colormap.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ValueRange {
+    /// Uses the smallest and largest finite values in the grid.
+    #[default]
+    Auto,
+    /// Uses a fixed minimum and maximum.
+    Fixed(f64, f64),
+}
+
+/// Draws spherical grids into RGBA images.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Renderer {
+    /// The projection used to flatten the sphere.
+    projection: Projection,
+    /// The colormap used to colour values.
+    colormap: Colormap,
+    /// The range of values stretched across the colormap.
+    range: ValueRange,
+    /// The colour of pixels that do not show the sphere or that have values that are not numbers.
+    background: [u8; 4],
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new(Projection::default(), Colormap::default())
+    }
+}
+
+impl Renderer {
+    /// Creates a new renderer with an automatic value range and a transparent background.
+    ///
+    /// - `projection` - The projection used to flatten the sphere.
+    /// - `colormap` - The colormap used to colour values.
+    pub fn new(projection: Projection, colormap: Colormap) -> Self {
+        Self {
+            projection,
+            colormap,
+            range: ValueRange::Auto,
+            background: [0, 0, 0, 0],
+        }
+    }
+
+    /// Sets the range of values stretched across the colormap.
+    ///
+    /// - `range` - The range of values.
+    pub fn with_range(mut self, range: ValueRange) -> Self {
+        self.range = range;
+        self
+    }
+
+    /// Sets the colour of pixels that do not show the sphere.
+    ///
+    /// - `background` - The colour as RGBA.
+    pub fn with_background(mut self, background: [u8; 4]) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Gets the projection used by this renderer.
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    /// Gets the colormap used by this renderer.
+    pub fn colormap(&self) -> Colormap {
+        self.colormap
+    }
+
+    /// Gets the range of values used by this renderer.
+    pub fn range(&self) -> ValueRange {
+        self.range
+    }
+
+    /// Gets the background colour used by this renderer.
+    pub fn background(&self) -> [u8; 4] {
+        self.background
+    }
+
+    /// Draws a grid into a new image.
+    ///
+    /// - `grid` - The grid to draw.
+    /// - `width` - The width of the image.
+    /// - `height` - The height of the image.
+    /// - `f` - Gets the value of a point that is coloured.
+    pub fn render<T, G: SurfaceGrid<T>, F: Fn(&T) -> f64>(
+                &self, grid: &G, width: usize, height: usize, f: F
+            ) -> Image where G::Point: SpherePoint {
+        let mut image = Image::new(width, height);
+
+        self.render_into(grid, &mut image.pixels, width, height, f);
+
+        image
+    }
+
+    /// Draws a grid into a new image in parallel.
+    ///
+    /// - `grid` - The grid to draw.
+    /// - `width` - The width of the image.
+    /// - `height` - The height of the image.
+    /// - `f` - Gets the value of a point that is coloured.
+    pub fn render_par<T: Sync, G: SurfaceGrid<T> + Sync, F: Fn(&T) -> f64 + Sync>(
+                &self, grid: &G, width: usize, height: usize, f: F
+            ) -> Image where G::Point: SpherePoint + Sync {
+        let mut image = Image::new(width, height);
+
+        self.render_into_par(grid, &mut image.pixels, width, height, f);
+
+        image
+    }
+
+    /// Draws a grid into an existing RGBA buffer with rows stored from top to bottom.
+    ///
+    /// - `grid` - The grid to draw.
+    /// - `frame` - The buffer to draw into with 4 bytes for each pixel.
+    /// - `width` - The width of the buffer.
+    /// - `height` - The height of the buffer.
+    /// - `f` - Gets the value of a point that is coloured.
+    ///
+    /// # Panics
+    /// Panics if the buffer is not exactly `width * height * 4` bytes long.
+    pub fn render_into<T, G: SurfaceGrid<T>, F: Fn(&T) -> f64>(
+                &self, grid: &G, frame: &mut [u8], width: usize, height: usize, f: F
+            ) where G::Point: SpherePoint {
+        assert_eq!(width * height * 4, frame.len(), "The buffer does not match the size of the image");
+
+        let Some((origin, (min, max))) = self.prepare(grid, &f) else {
+            return;
+        };
+
+        for (y, row) in frame.chunks_exact_mut(width * 4).enumerate() {
+            self.render_row(grid, &origin, row, y, width, height, (min, max), &f);
+        }
+    }
+
+    /// Draws a grid into an existing RGBA buffer with rows stored from top to bottom in parallel.
+    ///
+    /// - `grid` - The grid to draw.
+    /// - `frame` - The buffer to draw into with 4 bytes for each pixel.
+    /// - `width` - The width of the buffer.
+    /// - `height` - The height of the buffer.
+    /// - `f` - Gets the value of a point that is coloured.
+    ///
+    /// # Panics
+    /// Panics if the buffer is not exactly `width * height * 4` bytes long.
+    pub fn render_into_par<T: Sync, G: SurfaceGrid<T> + Sync, F: Fn(&T) -> f64 + Sync>(
+                &self, grid: &G, frame: &mut [u8], width: usize, height: usize, f: F
+            ) where G::Point: SpherePoint + Sync {
+        assert_eq!(width * height * 4, frame.len(), "The buffer does not match the size of the image");
+
+        let Some((origin, (min, max))) = self.prepare(grid, &f) else {
+            return;
+        };
+
+        frame.par_chunks_exact_mut(width * 4).enumerate().for_each(|(y, row)| {
+            self.render_row(grid, &origin, row, y, width, height, (min, max), &f);
+        });
+    }
+
+    /// Gets a point on the grid to look up other points from and the range of values to use.
+    /// Returns `None` if there is nothing to draw.
+    ///
+    /// - `grid` - The grid to draw.
+    /// - `f` - Gets the value of a point that is coloured.
+    fn prepare<T, G: SurfaceGrid<T>, F: Fn(&T) -> f64>(
+                &self, grid: &G, f: &F
+            ) -> Option<(G::Point, (f64, f64))> where G::Point: SpherePoint {
+        let origin = grid.points().next()?;
+
+        let range = match self.range {
+            ValueRange::Fixed(min, max) => (min, max),
+            ValueRange::Auto => grid.iter()
+                .map(|(_, value)| f(value))
+                .filter(|value| value.is_finite())
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| (min.min(value), max.max(value))),
+        };
+
+        Some((origin, range))
+    }
+
+    /// Draws a single row of an image.
+    ///
+    /// - `grid` - The grid to draw.
+    /// - `origin` - A point on the grid to look up other points from.
+    /// - `row` - The pixels of the row.
+    /// - `y` - The Y coordinate of the row.
+    /// - `width` - The width of the image.
+    /// - `height` - The height of the image.
+    /// - `range` - The range of values stretched across the colormap.
+    /// - `f` - Gets the value of a point that is coloured.
+    #[allow(clippy::too_many_arguments)]
+    fn render_row<T, G: SurfaceGrid<T>, F: Fn(&T) -> f64>(
+                &self, grid: &G, origin: &G::Point, row: &mut [u8], y: usize,
+                width: usize, height: usize, (min, max): (f64, f64), f: &F
+            ) where G::Point: SpherePoint {
+        for (x, pixel) in row.chunks_exact_mut(4).enumerate() {
+            let colour = self.projection.geographic(x, y, width, height)
+                .map(|(latitude, longitude)| f(&grid[origin.at_geographic(latitude, longitude)]))
+                .filter(|value| !value.is_nan())
+                .map(|value| {
+                    // A grid with a single value is drawn with the middle of the colormap.
+                    let value = if max > min {
+                        (value - min) / (max - min)
+                    } else {
+                        0.5
+                    };
+
+                    self.colormap.colour(value)
+                })
+                .unwrap_or(self.background);
+
+            pixel.copy_from_slice(&colour);
+        }
+    }
+}
+
+/// An RGBA image with rows stored from top to bottom.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Image {
+    /// The width of the image.
+    width: usize,
+    /// The height of the image.
+    height: usize,
+    /// The pixels of the image with 4 bytes for each pixel.
+    pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Creates a new transparent image.
+    ///
+    /// - `width` - The width of the image.
+    /// - `height` - The height of the image.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width * height * 4],
+        }
+    }
+
+    /// Gets the width of this image.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Gets the height of this image.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Gets the pixels of this image as RGBA with rows stored from top to bottom.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Gets the colour of a pixel as RGBA.
+    ///
+    /// - `x` - The X coordinate of the pixel.
+    /// - `y` - The Y coordinate of the pixel.
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let i = (y * self.width + x) * 4;
+
+        [self.pixels[i], self.pixels[i + 1], self.pixels[i + 2], self.pixels[i + 3]]
+    }
+
+    /// Writes this image in the binary PPM format.
+    /// The format has no alpha channel so transparency is discarded.
+    ///
+    /// - `writer` - The writer to write to.
+    pub fn write_ppm<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+
+        for pixel in self.pixels.chunks_exact(4) {
+            writer.write_all(&pixel[..3])?;
+        }
+
+        writer.flush()
+    }
+
+    /// Writes this image in the PNG format.
+    ///
+    /// - `writer` - The writer to write to.
+    #[cfg(feature = "images")]
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "The image is too large for a PNG file");
+
+        let width = u32::try_from(self.width).map_err(|_| too_large())?;
+        let height = u32::try_from(self.height).map_err(|_| too_large())?;
+
+        let mut encoder = png::Encoder::new(BufWriter::new(writer), width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&self.pixels)?;
+        writer.finish()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use approx::assert_relative_eq;
+
+    use crate::{sphere::{CubeSphereGrid, DynRectangleSphereGrid, RectangleSphereGrid, SpherePoint}, StaticSurfaceGrid};
+
+    use super::{Colormap, Image, Projection, Renderer, ValueRange};
+
+    #[test]
+    fn test_colormap_grayscale() {
+        assert_eq!([0, 0, 0, 255], Colormap::Grayscale.colour(0.0));
+        assert_eq!([255, 255, 255, 255], Colormap::Grayscale.colour(1.0));
+        assert_eq!([128, 128, 128, 255], Colormap::Grayscale.colour(0.5));
+    }
+
+    #[test]
+    fn test_colormap_viridis_ends() {
+        assert_eq!([68, 1, 84, 255], Colormap::Viridis.colour(0.0));
+        assert_eq!([253, 231, 37, 255], Colormap::Viridis.colour(1.0));
+    }
+
+    #[test]
+    fn test_colormap_diverging_middle() {
+        assert_eq!([245, 245, 245, 255], Colormap::Diverging.colour(0.5));
+        assert_eq!([59, 76, 192, 255], Colormap::Diverging.colour(0.0));
+        assert_eq!([180, 4, 38, 255], Colormap::Diverging.colour(1.0));
+    }
+
+    #[test]
+    fn test_colormap_clamped() {
+        assert_eq!(Colormap::Viridis.colour(0.0), Colormap::Viridis.colour(-2.0));
+        assert_eq!(Colormap::Viridis.colour(1.0), Colormap::Viridis.colour(3.0));
+    }
+
+    #[test]
+    fn test_projection_equirectangular() {
+        let (latitude, longitude) = Projection::Equirectangular.geographic(0, 0, 4, 2).unwrap();
+
+        assert_relative_eq!(PI / 4.0, latitude);
+        assert_relative_eq!(PI * 2.0 - PI * 3.0 / 4.0, longitude);
+
+        let (latitude, longitude) = Projection::Equirectangular.geographic(2, 1, 4, 2).unwrap();
+
+        assert_relative_eq!(-PI / 4.0, latitude);
+        assert_relative_eq!(PI / 4.0, longitude);
+    }
+
+    #[test]
+    fn test_projection_orthographic_centre() {
+        let projection = Projection::Orthographic { latitude: 0.5, longitude: 1.0 };
+
+        // The centre of an odd sized image is exactly the centre of the globe.
+        let (latitude, longitude) = projection.geographic(50, 50, 101, 101).unwrap();
+
+        assert_relative_eq!(0.5, latitude);
+        assert_relative_eq!(1.0, longitude);
+    }
+
+    #[test]
+    fn test_projection_orthographic_outside() {
+        let projection = Projection::Orthographic { latitude: 0.0, longitude: 0.0 };
+
+        assert_eq!(None, projection.geographic(0, 0, 100, 100));
+        assert_eq!(None, projection.geographic(5, 50, 200, 100));
+        assert!(projection.geographic(50, 50, 200, 100).is_some());
+    }
+
+    #[test]
+    fn test_projection_orthographic_edge() {
+        let projection = Projection::Orthographic { latitude: 0.0, longitude: 0.0 };
+
+        // The top of the globe is the north pole when looking at the equator.
+        let (latitude, _) = projection.geographic(500, 0, 1001, 1001).unwrap();
+
+        assert_relative_eq!(PI / 2.0, latitude, epsilon = 0.05);
+
+        // The right edge of the globe is 90 degrees east.
+        let (latitude, longitude) = projection.geographic(1000, 500, 1001, 1001).unwrap();
+
+        assert_relative_eq!(0.0, latitude, epsilon = 1e-9);
+        assert_relative_eq!(PI / 2.0, longitude, epsilon = 0.05);
+    }
+
+    #[test]
+    fn test_render_latitude() {
+        let grid: RectangleSphereGrid<f64, 32, 16> = RectangleSphereGrid::from_fn(|point| point.latitude());
+
+        let image = Renderer::new(Projection::Equirectangular, Colormap::Grayscale)
+            .render(&grid, 64, 32, |value| *value);
+
+        assert_eq!(64, image.width());
+        assert_eq!(32, image.height());
+        assert_eq!(64 * 32 * 4, image.pixels().len());
+
+        // North is at the top so the top row is the brightest.
+        assert!(image.pixel(10, 0)[0] > image.pixel(10, 16)[0]);
+        assert!(image.pixel(10, 16)[0] > image.pixel(10, 31)[0]);
+        assert_eq!(255, image.pixel(10, 0)[0]);
+        assert_eq!(0, image.pixel(10, 31)[0]);
+    }
+
+    #[test]
+    fn test_render_fixed_range() {
+        let grid: CubeSphereGrid<f64, 4> = CubeSphereGrid::from_fn(|_| 5.0);
+
+        let image = Renderer::new(Projection::Equirectangular, Colormap::Grayscale)
+            .with_range(ValueRange::Fixed(0.0, 10.0))
+            .render(&grid, 8, 4, |value| *value);
+
+        assert!(image.pixels().chunks_exact(4).all(|pixel| pixel == [128, 128, 128, 255]));
+    }
+
+    #[test]
+    fn test_render_constant() {
+        let grid: CubeSphereGrid<f64, 4> = CubeSphereGrid::from_fn(|_| 5.0);
+
+        let image = Renderer::new(Projection::Equirectangular, Colormap::Viridis)
+            .render(&grid, 8, 4, |value| *value);
+
+        assert!(image.pixels().chunks_exact(4).all(|pixel| pixel == Colormap::Viridis.colour(0.5)));
+    }
+
+    #[test]
+    fn test_render_orthographic_background() {
+        let grid: CubeSphereGrid<f64, 4> = CubeSphereGrid::from_fn(|_| 1.0);
+
+        let image = Renderer::new(Projection::Orthographic { latitude: 0.3, longitude: 2.0 }, Colormap::Grayscale)
+            .with_background([1, 2, 3, 4])
+            .render(&grid, 20, 10, |value| *value);
+
+        assert_eq!([1, 2, 3, 4], image.pixel(0, 0));
+        assert_eq!([1, 2, 3, 4], image.pixel(19, 9));
+        assert_eq!([128, 128, 128, 255], image.pixel(10, 5));
+    }
+
+    #[test]
+    fn test_render_nan_background() {
+        let grid: CubeSphereGrid<f64, 4> = CubeSphereGrid::from_fn(|_| f64::NAN);
+
+        let image = Renderer::default()
+            .with_background([9, 9, 9, 9])
+            .render(&grid, 8, 4, |value| *value);
+
+        assert!(image.pixels().chunks_exact(4).all(|pixel| pixel == [9, 9, 9, 9]));
+    }
+
+    #[test]
+    fn test_render_par_matches() {
+        let grid = DynRectangleSphereGrid::from_fn(20, 10, |point| point.longitude().sin());
+
+        let renderer = Renderer::new(Projection::Orthographic { latitude: -0.4, longitude: 5.0 }, Colormap::Diverging);
+
+        assert_eq!(
+            renderer.render(&grid, 30, 20, |value| *value),
+            renderer.render_par(&grid, 30, 20, |value| *value)
+        );
+    }
+
+    #[test]
+    fn test_render_into() {
+        let grid: CubeSphereGrid<f64, 4> = CubeSphereGrid::from_fn(|_| 1.0);
+
+        let mut frame = vec![0; 6 * 3 * 4];
+
+        Renderer::new(Projection::Equirectangular, Colormap::Grayscale)
+            .with_range(ValueRange::Fixed(0.0, 1.0))
+            .render_into(&grid, &mut frame, 6, 3, |value| *value);
+
+        assert!(frame.iter().all(|byte| *byte == 255));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_render_into_wrong_size() {
+        let grid: CubeSphereGrid<f64, 4> = CubeSphereGrid::default();
+
+        let mut frame = vec![0; 10];
+
+        Renderer::default().render_into(&grid, &mut frame, 6, 3, |value| *value);
+    }
+
+    #[test]
+    fn test_write_ppm() {
+        let mut image = Image::new(3, 2);
+        image.pixels[0..4].copy_from_slice(&[10, 20, 30, 40]);
+
+        let mut output = Vec::new();
+        image.write_ppm(&mut output).unwrap();
+
+        let header = b"P6\n3 2\n255\n";
+
+        assert_eq!(header, &output[..header.len()]);
+        assert_eq!(header.len() + 3 * 2 * 3, output.len());
+        assert_eq!([10, 20, 30], output[header.len()..header.len() + 3]);
+    }
+
+    #[test]
+    #[cfg(feature = "images")]
+    fn test_write_png() {
+        let mut image = Image::new(3, 2);
+        image.pixels[4..8].copy_from_slice(&[10, 20, 30, 40]);
+
+        let mut output = Vec::new();
+        image.write_png(&mut output).unwrap();
+
+        let mut reader = png::Decoder::new(&output[..]).read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut pixels).unwrap();
+
+        assert_eq!((3, 2), (info.width, info.height));
+        assert_eq!((png::ColorType::Rgba, png::BitDepth::Eight), (info.color_type, info.bit_depth));
+        assert_eq!(image.pixels(), &pixels[..info.buffer_size()]);
+    }
+}
@@ -0,0 +1,654 @@
+//! A module for saving grids of plain values to a compact binary format and restoring them.
+//!
+//! A snapshot starts with a 32 byte header:
+//!
+//! | Offset | Size | Contents                                            |
+//! |--------|------|-----------------------------------------------------|
+//! | 0      | 8    | The magic bytes `SURFGRID`.                         |
+//! | 8      | 2    | The format version as a little endian `u16`.        |
+//! | 10     | 1    | The grid kind, 0 for a rectangle and 1 for a cube.  |
+//! | 11     | 1    | The endianness of the cells, 0 for little, 1 for big. |
+//! | 12     | 4    | The size of each cell in bytes as a little endian `u32`. |
+//! | 16     | 8    | The width of the grid as a little endian `u64`.     |
+//! | 24     | 8    | The height of the grid as a little endian `u64`.    |
+//!
+//! The header is followed by the raw bytes of every cell in the order of `SurfaceGrid::points`.
+//! Cells are written in the endianness of the machine writing them and swapped when read on a
+//! machine with a different endianness.
+//! Cube grids store the size of each face as both the width and height.
+
+use std::{error::Error, fmt::{self, Display, Formatter}, io::{self, Read, Write}};
+
+use crate::SurfaceGrid;
+
+/// The bytes at the start of every snapshot.
+const MAGIC: [u8; 8] = *b"SURFGRID";
+
+/// The version of the snapshot format written by this crate.
+pub const SNAPSHOT_VERSION: u16 = 1;
+
+/// The size of the header of a snapshot in bytes.
+pub(crate) const HEADER_SIZE: usize = 32;
+
+/// The number of bytes of cells read or written at a time.
+const BUFFER_SIZE: usize = 1 << 16;
+
+/// The order of the bytes of each value in a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endianness {
+    /// The least significant byte comes first.
+    Little,
+    /// The most significant byte comes first.
+    Big,
+}
+
+impl Endianness {
+    /// The endianness of the machine running this code.
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Self = Self::Little;
+
+    /// The endianness of the machine running this code.
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Self = Self::Big;
+}
+
+/// A value that is stored as a fixed number of bytes with no padding or pointers.
+pub trait Pod: Copy + Default {
+    /// The number of bytes used to store the value.
+    const SIZE: usize;
+
+    /// Writes this value to a slice of exactly `SIZE` bytes.
+    ///
+    /// - `bytes` - The bytes to write to.
+    /// - `endianness` - The order to write the bytes in.
+    fn write_bytes(&self, bytes: &mut [u8], endianness: Endianness);
+
+    /// Reads a value from a slice of exactly `SIZE` bytes.
+    ///
+    /// - `bytes` - The bytes to read from.
+    /// - `endianness` - The order the bytes were written in.
+    fn read_bytes(bytes: &[u8], endianness: Endianness) -> Self;
+}
+
+/// Implements `Pod` for number types.
+macro_rules! impl_pod {
+    ($($t:ty),*) => {
+        $(
+            impl Pod for $t {
+                const SIZE: usize = std::mem::size_of::<$t>();
+
+                fn write_bytes(&self, bytes: &mut [u8], endianness: Endianness) {
+                    bytes.copy_from_slice(&match endianness {
+                        Endianness::Little => self.to_le_bytes(),
+                        Endianness::Big => self.to_be_bytes(),
+                    });
+                }
+
+                fn read_bytes(bytes: &[u8], endianness: Endianness) -> Self {
+                    let bytes = bytes.try_into().expect("The slice is the size of the value");
+
+                    match endianness {
+                        Endianness::Little => Self::from_le_bytes(bytes),
+                        Endianness::Big => Self::from_be_bytes(bytes),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Pod for bool {
+    const SIZE: usize = 1;
+
+    fn write_bytes(&self, bytes: &mut [u8], _: Endianness) {
+        bytes[0] = *self as u8;
+    }
+
+    fn read_bytes(bytes: &[u8], _: Endianness) -> Self {
+        bytes[0] != 0
+    }
+}
+
+impl <T: Pod, const N: usize> Pod for [T; N] where [T; N]: Default {
+    const SIZE: usize = T::SIZE * N;
+
+    fn write_bytes(&self, bytes: &mut [u8], endianness: Endianness) {
+        for (value, bytes) in self.iter().zip(bytes.chunks_exact_mut(T::SIZE)) {
+            value.write_bytes(bytes, endianness);
+        }
+    }
+
+    fn read_bytes(bytes: &[u8], endianness: Endianness) -> Self {
+        let mut values = Self::default();
+
+        for (value, bytes) in values.iter_mut().zip(bytes.chunks_exact(T::SIZE)) {
+            *value = T::read_bytes(bytes, endianness);
+        }
+
+        values
+    }
+}
+
+/// The shape of grid stored in a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GridKind {
+    /// A grid made from a single rectangle.
+    Rectangle,
+    /// A grid made from the 6 square faces of a cube.
+    Cube,
+}
+
+/// An error from reading a snapshot.
+#[derive(Debug)]
+pub enum SnapshotError {
+    /// The snapshot could not be read.
+    Io(io::Error),
+    /// The data does not start with the magic bytes of a snapshot.
+    Magic,
+    /// The snapshot was written with a version of the format that is not supported.
+    Version(u16),
+    /// The grid kind in the header is not known.
+    UnknownKind(u8),
+    /// The endianness in the header is not known.
+    UnknownEndianness(u8),
+    /// The snapshot holds a different kind of grid.
+    KindMismatch {
+        /// The kind of grid being read.
+        expected: GridKind,
+        /// The kind of grid in the snapshot.
+        found: GridKind,
+    },
+    /// The cells in the snapshot are a different size to the type being read.
+    ElementSizeMismatch {
+        /// The size of the type being read.
+        expected: usize,
+        /// The size of each cell in the snapshot.
+        found: usize,
+    },
+    /// The cells were written with an endianness that cannot be mapped into memory directly.
+    ForeignEndianness(Endianness),
+    /// The dimensions in the snapshot are not valid for the grid being read.
+    DimensionMismatch {
+        /// The width in the snapshot.
+        width: u64,
+        /// The height in the snapshot.
+        height: u64,
+    },
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "Failed to read snapshot: {}", error),
+            Self::Magic => write!(f, "The data is not a grid snapshot"),
+            Self::Version(version) => write!(f, "Snapshot version {} is not supported", version),
+            Self::UnknownKind(kind) => write!(f, "Unknown grid kind {}", kind),
+            Self::UnknownEndianness(endianness) => write!(f, "Unknown endianness {}", endianness),
+            Self::KindMismatch { expected, found } => write!(f, "Expected a {:?} grid but found a {:?} grid", expected, found),
+            Self::ElementSizeMismatch { expected, found } => write!(f, "Expected cells of {} bytes but found {} bytes", expected, found),
+            Self::ForeignEndianness(endianness) => write!(f, "Cells in {:?} endian order cannot be mapped on this machine", endianness),
+            Self::DimensionMismatch { width, height } => write!(f, "A {}x{} snapshot does not fit this grid", width, height),
+        }
+    }
+}
+
+impl Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SnapshotError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// The contents of the header of a snapshot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct Header {
+    /// The kind of grid in the snapshot.
+    pub(crate) kind: GridKind,
+    /// The endianness of the cells.
+    pub(crate) endianness: Endianness,
+    /// The size of each cell in bytes.
+    pub(crate) element_size: usize,
+    /// The width of the grid.
+    pub(crate) width: u64,
+    /// The height of the grid.
+    pub(crate) height: u64,
+}
+
+impl Header {
+    /// Gets the bytes of this header.
+    pub(crate) fn to_bytes(self) -> io::Result<[u8; HEADER_SIZE]> {
+        let element_size = u32::try_from(self.element_size)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "The cells are too large for a snapshot"))?;
+
+        let mut bytes = [0; HEADER_SIZE];
+        bytes[0..8].copy_from_slice(&MAGIC);
+        bytes[8..10].copy_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        bytes[10] = match self.kind {
+            GridKind::Rectangle => 0,
+            GridKind::Cube => 1,
+        };
+        bytes[11] = match self.endianness {
+            Endianness::Little => 0,
+            Endianness::Big => 1,
+        };
+        bytes[12..16].copy_from_slice(&element_size.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.width.to_le_bytes());
+        bytes[24..32].copy_from_slice(&self.height.to_le_bytes());
+
+        Ok(bytes)
+    }
+
+    /// Reads a header from its bytes.
+    ///
+    /// - `bytes` - The bytes at the start of a snapshot.
+    pub(crate) fn from_bytes(bytes: &[u8; HEADER_SIZE]) -> Result<Self, SnapshotError> {
+        if bytes[0..8] != MAGIC {
+            return Err(SnapshotError::Magic);
+        }
+
+        let version = u16::from_le_bytes([bytes[8], bytes[9]]);
+
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::Version(version));
+        }
+
+        let kind = match bytes[10] {
+            0 => GridKind::Rectangle,
+            1 => GridKind::Cube,
+            kind => return Err(SnapshotError::UnknownKind(kind)),
+        };
+
+        let endianness = match bytes[11] {
+            0 => Endianness::Little,
+            1 => Endianness::Big,
+            endianness => return Err(SnapshotError::UnknownEndianness(endianness)),
+        };
+
+        let field = |range: std::ops::Range<usize>| u64::from_le_bytes(bytes[range].try_into().expect("The header has a fixed size"));
+
+        Ok(Self {
+            kind,
+            endianness,
+            element_size: u32::from_le_bytes(bytes[12..16].try_into().expect("The header has a fixed size")) as usize,
+            width: field(16..24),
+            height: field(24..32),
+        })
+    }
+
+    /// Checks that this header holds the kind of grid and size of cell being read.
+    ///
+    /// - `kind` - The kind of grid being read.
+    /// - `element_size` - The size of each cell being read.
+    pub(crate) fn check(self, kind: GridKind, element_size: usize) -> Result<Self, SnapshotError> {
+        if self.kind != kind {
+            Err(SnapshotError::KindMismatch { expected: kind, found: self.kind })
+        } else if self.element_size != element_size {
+            Err(SnapshotError::ElementSizeMismatch { expected: element_size, found: self.element_size })
+        } else {
+            Ok(self)
+        }
+    }
+}
+
+/// A grid that can be saved to and restored from a snapshot.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+pub trait Snapshot<T: Pod> : SurfaceGrid<T> + Sized {
+    /// The kind of grid stored in snapshots of this grid.
+    const KIND: GridKind;
+
+    /// Gets the width and height stored in snapshots of this grid.
+    fn snapshot_dimensions(&self) -> (usize, usize);
+
+    /// Checks that the dimensions in a snapshot fit this type of grid and gets the number of cells
+    /// that the snapshot holds.
+    ///
+    /// - `width` - The width in the snapshot.
+    /// - `height` - The height in the snapshot.
+    fn snapshot_len(width: u64, height: u64) -> Result<usize, SnapshotError>;
+
+    /// Creates a grid from the cells in a snapshot with dimensions already checked by
+    /// `snapshot_len`.
+    ///
+    /// - `width` - The width in the snapshot.
+    /// - `height` - The height in the snapshot.
+    /// - `values` - The value of each cell in the same order as `SurfaceGrid::points`, to be
+    ///   moved into the storage of the grid.
+    fn from_snapshot_values(width: u64, height: u64, values: Vec<T>) -> Self;
+
+    /// Writes this grid as a snapshot.
+    ///
+    /// - `writer` - The writer to write to.
+    fn write_snapshot<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        let (width, height) = self.snapshot_dimensions();
+
+        let header = Header {
+            kind: Self::KIND,
+            endianness: Endianness::NATIVE,
+            element_size: T::SIZE,
+            width: width as u64,
+            height: height as u64,
+        };
+
+        writer.write_all(&header.to_bytes()?)?;
+
+        if T::SIZE == 0 {
+            return Ok(());
+        }
+
+        let capacity = (BUFFER_SIZE / T::SIZE).max(1) * T::SIZE;
+        let mut buffer = Vec::with_capacity(capacity);
+
+        for (_, value) in self.iter() {
+            let start = buffer.len();
+            buffer.resize(start + T::SIZE, 0);
+            value.write_bytes(&mut buffer[start..], Endianness::NATIVE);
+
+            if buffer.len() == capacity {
+                writer.write_all(&buffer)?;
+                buffer.clear();
+            }
+        }
+
+        writer.write_all(&buffer)
+    }
+
+    /// Reads a grid from a snapshot.
+    ///
+    /// The cells are read in blocks and only take up memory once they have been read, so a
+    /// snapshot with a corrupt header fails to read instead of allocating the grid it claims.
+    ///
+    /// - `reader` - The reader to read from.
+    fn read_snapshot<R: Read>(mut reader: R) -> Result<Self, SnapshotError> {
+        let mut bytes = [0; HEADER_SIZE];
+        reader.read_exact(&mut bytes)?;
+
+        let Header { endianness, width, height, .. } = Header::from_bytes(&bytes)?.check(Self::KIND, T::SIZE)?;
+
+        let len = Self::snapshot_len(width, height)?;
+
+        if T::SIZE == 0 {
+            return Ok(Self::from_snapshot_values(width, height, vec![T::default(); len]));
+        }
+
+        let capacity = (BUFFER_SIZE / T::SIZE).max(1);
+        let mut buffer = vec![0; capacity * T::SIZE];
+
+        // The values grow as they are read so that a corrupt header cannot allocate more memory
+        // than the data in the snapshot. They never grow past the number of cells so that they
+        // can be moved into the grid as they are.
+        let mut values = Vec::new();
+
+        while values.len() < len {
+            let remaining = len - values.len();
+
+            if values.len() == values.capacity() {
+                values.reserve_exact(values.capacity().max(capacity).min(remaining));
+            }
+
+            let bytes = &mut buffer[..capacity.min(remaining) * T::SIZE];
+
+            reader.read_exact(bytes)?;
+
+            values.extend(bytes.chunks_exact(T::SIZE).map(|bytes| T::read_bytes(bytes, endianness)));
+        }
+
+        Ok(Self::from_snapshot_values(width, height, values))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, ErrorKind};
+
+    use crate::{sphere::{CubeSphereGrid, DynCubeSphereGrid, DynRectangleSphereGrid, EquiangularCubeSphereGrid, RectangleSphereGrid}, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    use super::{Endianness, GridKind, Pod, Snapshot, SnapshotError, HEADER_SIZE};
+
+    /// Writes a grid to a snapshot in memory.
+    fn snapshot<T: Pod, G: Snapshot<T>>(grid: &G) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        grid.write_snapshot(&mut bytes).unwrap();
+
+        bytes
+    }
+
+    #[test]
+    fn test_pod_round_trip() {
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let mut bytes = [0; 12];
+
+            [1.5f32, -2.0, 3.25].write_bytes(&mut bytes, endianness);
+
+            assert_eq!([1.5f32, -2.0, 3.25], <[f32; 3]>::read_bytes(&bytes, endianness));
+        }
+    }
+
+    #[test]
+    fn test_pod_endianness() {
+        let mut bytes = [0; 4];
+
+        0x01020304u32.write_bytes(&mut bytes, Endianness::Big);
+        assert_eq!([1, 2, 3, 4], bytes);
+
+        0x01020304u32.write_bytes(&mut bytes, Endianness::Little);
+        assert_eq!([4, 3, 2, 1], bytes);
+    }
+
+    #[test]
+    fn test_snapshot_header() {
+        let grid: RectangleSphereGrid<u16, 5, 3> = RectangleSphereGrid::default();
+
+        let bytes = snapshot(&grid);
+
+        assert_eq!(HEADER_SIZE + 5 * 3 * 2, bytes.len());
+        assert_eq!(b"SURFGRID", &bytes[0..8]);
+        assert_eq!([1, 0], bytes[8..10]);
+        assert_eq!(0, bytes[10]);
+        assert_eq!(2u32.to_le_bytes(), bytes[12..16]);
+        assert_eq!(5u64.to_le_bytes(), bytes[16..24]);
+        assert_eq!(3u64.to_le_bytes(), bytes[24..32]);
+    }
+
+    #[test]
+    fn test_snapshot_points_order() {
+        let grid: CubeSphereGrid<u32, 3> = CubeSphereGrid::from_fn(|point| point.to_index() as u32);
+
+        let bytes = snapshot(&grid);
+
+        for (i, (point, _)) in grid.iter().enumerate() {
+            let start = HEADER_SIZE + i * 4;
+
+            assert_eq!(point.to_index() as u32, u32::read_bytes(&bytes[start..start + 4], Endianness::NATIVE));
+        }
+    }
+
+    #[test]
+    fn test_snapshot_rect_round_trip() {
+        let grid: RectangleSphereGrid<f64, 20, 10> = RectangleSphereGrid::from_fn(|point| point.to_index() as f64 * 0.5);
+
+        let bytes = snapshot(&grid);
+
+        assert_eq!(grid, RectangleSphereGrid::read_snapshot(&bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_cube_round_trip() {
+        let grid: EquiangularCubeSphereGrid<[i16; 3], 7> = EquiangularCubeSphereGrid::from_fn(|point| {
+            [point.x() as i16, -(point.y() as i16), point.to_index() as i16]
+        });
+
+        let bytes = snapshot(&grid);
+
+        assert_eq!(grid, EquiangularCubeSphereGrid::read_snapshot(&bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_large_round_trip() {
+        // Larger than a single block.
+        let grid: CubeSphereGrid<u64, 64> = CubeSphereGrid::from_fn(|point| point.to_index() as u64 * 3);
+
+        let bytes = snapshot(&grid);
+
+        assert_eq!(grid, CubeSphereGrid::read_snapshot(&bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_dyn_round_trip() {
+        let grid = DynRectangleSphereGrid::from_fn(6, 4, |point| point.x() as u8 + point.y() as u8 * 10);
+
+        let bytes = snapshot(&grid);
+
+        assert_eq!(grid, DynRectangleSphereGrid::read_snapshot(&bytes[..]).unwrap());
+
+        let grid = DynCubeSphereGrid::from_fn(5, |point| (point.x() + point.y() * 7) as u32);
+
+        let bytes = snapshot(&grid);
+
+        assert_eq!(grid, DynCubeSphereGrid::read_snapshot(&bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_static_dyn_compatible() {
+        let grid: CubeSphereGrid<bool, 4> = CubeSphereGrid::from_fn(|point| point.x() == point.y());
+
+        let bytes = snapshot(&grid);
+
+        let result = DynCubeSphereGrid::<bool>::read_snapshot(&bytes[..]).unwrap();
+
+        assert!(grid.iter().map(|(_, value)| value).eq(result.iter().map(|(_, value)| value)));
+    }
+
+    #[test]
+    fn test_snapshot_swapped_endianness() {
+        let grid: RectangleSphereGrid<u32, 2, 2> = RectangleSphereGrid::from_fn(|point| 0x01020304 * (point.to_index() as u32 + 1));
+
+        let mut bytes = snapshot(&grid);
+
+        // Rewrite the snapshot as if it came from a machine with the other endianness.
+        let other = match Endianness::NATIVE {
+            Endianness::Little => Endianness::Big,
+            Endianness::Big => Endianness::Little,
+        };
+
+        bytes[11] = 1 - bytes[11];
+
+        for (i, (_, value)) in grid.iter().enumerate() {
+            let start = HEADER_SIZE + i * 4;
+
+            value.write_bytes(&mut bytes[start..start + 4], other);
+        }
+
+        assert_eq!(grid, RectangleSphereGrid::read_snapshot(&bytes[..]).unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_wrong_dimensions() {
+        let grid: RectangleSphereGrid<u8, 5, 3> = RectangleSphereGrid::default();
+
+        let bytes = snapshot(&grid);
+
+        assert!(matches!(
+            RectangleSphereGrid::<u8, 3, 5>::read_snapshot(&bytes[..]),
+            Err(SnapshotError::DimensionMismatch { width: 5, height: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_wrong_kind() {
+        let grid: CubeSphereGrid<u8, 3> = CubeSphereGrid::default();
+
+        let bytes = snapshot(&grid);
+
+        assert!(matches!(
+            DynRectangleSphereGrid::<u8>::read_snapshot(&bytes[..]),
+            Err(SnapshotError::KindMismatch { expected: GridKind::Rectangle, found: GridKind::Cube })
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_wrong_element_size() {
+        let grid: CubeSphereGrid<u8, 3> = CubeSphereGrid::default();
+
+        let bytes = snapshot(&grid);
+
+        assert!(matches!(
+            CubeSphereGrid::<u16, 3>::read_snapshot(&bytes[..]),
+            Err(SnapshotError::ElementSizeMismatch { expected: 2, found: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_bad_header() {
+        let grid: CubeSphereGrid<u8, 3> = CubeSphereGrid::default();
+
+        let mut bytes = snapshot(&grid);
+        bytes[0] = b'X';
+
+        assert!(matches!(CubeSphereGrid::<u8, 3>::read_snapshot(&bytes[..]), Err(SnapshotError::Magic)));
+
+        let mut bytes = snapshot(&grid);
+        bytes[8] = 9;
+
+        assert!(matches!(CubeSphereGrid::<u8, 3>::read_snapshot(&bytes[..]), Err(SnapshotError::Version(9))));
+
+        let mut bytes = snapshot(&grid);
+        bytes[11] = 7;
+
+        assert!(matches!(CubeSphereGrid::<u8, 3>::read_snapshot(&bytes[..]), Err(SnapshotError::UnknownEndianness(7))));
+    }
+
+    #[test]
+    fn test_snapshot_truncated() {
+        let grid: CubeSphereGrid<u8, 3> = CubeSphereGrid::default();
+
+        let bytes = snapshot(&grid);
+
+        let error = CubeSphereGrid::<u8, 3>::read_snapshot(&bytes[..bytes.len() - 1]).unwrap_err();
+
+        assert!(matches!(&error, SnapshotError::Io(error) if error.kind() == ErrorKind::UnexpectedEof));
+    }
+
+    #[test]
+    fn test_snapshot_huge_dimensions() {
+        let grid = DynCubeSphereGrid::from_fn(2, |_| 1u64);
+
+        let mut bytes = snapshot(&grid);
+        bytes[16..24].copy_from_slice(&65535u64.to_le_bytes());
+        bytes[24..32].copy_from_slice(&65535u64.to_le_bytes());
+
+        // The header claims far more cells than the snapshot holds.
+        let error = DynCubeSphereGrid::<u64>::read_snapshot(&bytes[..]).unwrap_err();
+
+        assert!(matches!(&error, SnapshotError::Io(error) if error.kind() == ErrorKind::UnexpectedEof));
+
+        let grid = DynRectangleSphereGrid::from_fn(2, 2, |_| 1u8);
+
+        let mut bytes = snapshot(&grid);
+        bytes[16..24].copy_from_slice(&(u32::MAX as u64).to_le_bytes());
+        bytes[24..32].copy_from_slice(&(u32::MAX as u64).to_le_bytes());
+
+        assert!(DynRectangleSphereGrid::<u8>::read_snapshot(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_error_display() {
+        assert_eq!("Expected cells of 2 bytes but found 1 bytes", SnapshotError::ElementSizeMismatch { expected: 2, found: 1 }.to_string());
+        assert_eq!("A 5x3 snapshot does not fit this grid", SnapshotError::DimensionMismatch { width: 5, height: 3 }.to_string());
+        assert_eq!(
+            "Failed to read snapshot: broken",
+            SnapshotError::from(io::Error::other("broken")).to_string()
+        );
+    }
+}
@@ -1,20 +1,38 @@
 //! A module containing grids wrapped around spheres.
 
-use std::{f64::consts::PI, ops::{Index, IndexMut}, vec, fmt::Debug};
+use std::{error::Error, f64::consts::PI, fmt::{self, Debug, Display, Formatter}, hash::Hash, marker::PhantomData, ops::{Index, IndexMut}, vec};
 
 use itertools::Itertools;
 use rayon::prelude::*;
-use static_array::HeapArray2D;
 
-use crate::{GridPoint, SurfaceGrid};
+use crate::{neighbourhood::{self, Moore}, storage::Storage, CellPoint, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+mod cubemap;
+mod ellipsoid;
+mod healpix;
+mod icosa;
+#[cfg(feature = "mmap")]
+mod mapped;
+mod octa;
+#[cfg(feature = "serde")]
+mod serialize;
+mod snapshot;
+
+pub use cubemap::{CubemapError, CubemapFace, CubemapLayout};
+pub use ellipsoid::Ellipsoid;
+pub use healpix::{HealpixOrdering, HealpixSphereGrid, HealpixSpherePoint, NestedOrdering, RingOrdering};
+pub use icosa::{IcosaSphereGrid, IcosaSpherePoint};
+#[cfg(feature = "mmap")]
+pub use mapped::{MappedCubeSphereGrid, MappedRectangleSphereGrid};
+pub use octa::{OctaSphereGrid, OctaSpherePoint};
 
 /// A point on a spherical grid.
 pub trait SpherePoint : GridPoint {
-    /// Gets a sphere point for the specified geographic coordinates.
+    /// Gets the point on the same grid as this point for the specified geographic coordinates.
     ///
     /// - `latitude` - The latitude of the point in radians where 0 is the equator.
     /// - `longitude` - The longitude of the point in radians.
-    fn from_geographic(latitude: f64, longitude: f64) -> Self;
+    fn at_geographic(&self, latitude: f64, longitude: f64) -> Self;
 
     /// Gets the latitude of this point.
     fn latitude(&self) -> f64;
@@ -28,70 +46,243 @@ pub trait SpherePoint : GridPoint {
     fn sphere_coordinates(&self) -> (f64, f64) {
         (self.longitude(), self.latitude())
     }
+
+    /// Gets the earth centred, earth fixed coordinates of this point on the surface of an
+    /// ellipsoid, treating the latitude of this point as a geodetic latitude.
+    ///
+    /// - `ellipsoid` - The ellipsoid that the grid is wrapped around.
+    fn ecef(&self, ellipsoid: &Ellipsoid) -> (f64, f64, f64) {
+        ellipsoid.geodetic_to_ecef(self.latitude(), self.longitude(), 0.0)
+    }
+
+    /// Gets the point on the same grid as this point for the specified earth centred, earth fixed
+    /// coordinates.
+    ///
+    /// - `x` - The X coordinate.
+    /// - `y` - The Y coordinate.
+    /// - `z` - The Z coordinate.
+    /// - `ellipsoid` - The ellipsoid that the grid is wrapped around.
+    fn at_ecef(&self, x: f64, y: f64, z: f64, ellipsoid: &Ellipsoid) -> Self {
+        let (latitude, longitude, _) = ellipsoid.ecef_to_geodetic(x, y, z);
+
+        self.at_geographic(latitude, longitude)
+    }
+
+    /// Gets the length of the shortest path along the surface of an ellipsoid between this point
+    /// and another point.
+    ///
+    /// - `other` - The other point.
+    /// - `ellipsoid` - The ellipsoid that the grid is wrapped around.
+    fn ellipsoid_distance_to(&self, other: &Self, ellipsoid: &Ellipsoid) -> f64 {
+        ellipsoid.distance(self.latitude(), self.longitude(), other.latitude(), other.longitude())
+    }
+
+    /// Gets the length of the great circle path along the surface of a sphere between this point
+    /// and another point.
+    ///
+    /// - `other` - The other point.
+    /// - `radius` - The radius of the sphere.
+    fn distance_to(&self, other: &Self, radius: f64) -> f64 {
+        let (latitude1, longitude1) = (self.latitude(), self.longitude());
+        let (latitude2, longitude2) = (other.latitude(), other.longitude());
+
+        // The haversine formula stays accurate for points that are close together.
+        let a = ((latitude2 - latitude1) / 2.0).sin().powi(2)
+            + latitude1.cos() * latitude2.cos() * ((longitude2 - longitude1) / 2.0).sin().powi(2);
+
+        2.0 * a.sqrt().min(1.0).asin() * radius
+    }
+
+    /// Gets the direction in which to start moving along a great circle to reach another point.
+    /// The bearing is in radians clockwise from north between 0 and 2π.
+    ///
+    /// - `other` - The other point.
+    fn initial_bearing_to(&self, other: &Self) -> f64 {
+        let (latitude1, longitude1) = (self.latitude(), self.longitude());
+        let (latitude2, longitude2) = (other.latitude(), other.longitude());
+
+        let difference = longitude2 - longitude1;
+
+        let y = difference.sin() * latitude2.cos();
+        let x = latitude1.cos() * latitude2.sin() - latitude1.sin() * latitude2.cos() * difference.cos();
+
+        y.atan2(x).rem_euclid(2.0 * PI)
+    }
+
+    /// Gets the point on the same grid as this point reached by moving along a great circle.
+    ///
+    /// - `bearing` - The direction to start moving in radians clockwise from north.
+    /// - `distance` - The distance to move along the surface of the sphere.
+    /// - `radius` - The radius of the sphere.
+    fn destination(&self, bearing: f64, distance: f64, radius: f64) -> Self where Self: Sized {
+        let (latitude, longitude) = (self.latitude(), self.longitude());
+
+        let angle = distance / radius;
+
+        let destination_latitude = (latitude.sin() * angle.cos() + latitude.cos() * angle.sin() * bearing.cos())
+            .clamp(-1.0, 1.0)
+            .asin();
+
+        let destination_longitude = longitude + (bearing.sin() * angle.sin() * latitude.cos())
+            .atan2(angle.cos() - latitude.sin() * destination_latitude.sin());
+
+        self.at_geographic(destination_latitude, destination_longitude.rem_euclid(2.0 * PI))
+    }
+}
+
+/// A point on a spherical grid with dimensions that are known at compile time.
+pub trait StaticSpherePoint : SpherePoint {
+    /// Gets a sphere point for the specified geographic coordinates.
+    ///
+    /// - `latitude` - The latitude of the point in radians where 0 is the equator.
+    /// - `longitude` - The longitude of the point in radians.
+    fn from_geographic(latitude: f64, longitude: f64) -> Self;
+
+    /// Gets a sphere point for the specified earth centred, earth fixed coordinates.
+    ///
+    /// - `x` - The X coordinate.
+    /// - `y` - The Y coordinate.
+    /// - `z` - The Z coordinate.
+    /// - `ellipsoid` - The ellipsoid that the grid is wrapped around.
+    fn from_ecef(x: f64, y: f64, z: f64, ellipsoid: &Ellipsoid) -> Self where Self: Sized {
+        let (latitude, longitude, _) = ellipsoid.ecef_to_geodetic(x, y, z);
+
+        Self::from_geographic(latitude, longitude)
+    }
+}
+
+/// Gets the position in 3D space of a location on a sphere.
+///
+/// - `latitude` - The latitude of the location in radians.
+/// - `longitude` - The longitude of the location in radians.
+/// - `scale` - The radius of the sphere.
+fn geographic_position(latitude: f64, longitude: f64, scale: f64) -> (f64, f64, f64) {
+    let y = scale * latitude.sin();
+    let radius = scale * latitude.cos();
+
+    (radius * longitude.sin(), y, radius * longitude.cos())
+}
+
+/// Gets every point within a distance of a point along the surface of a sphere.
+///
+/// - `centre` - The point at the centre of the region.
+/// - `distance` - The maximum distance from the centre.
+/// - `radius` - The radius of the sphere.
+pub(crate) fn points_within_distance<P: SpherePoint + Hash>(centre: &P, distance: f64, radius: f64) -> Vec<P> {
+    neighbourhood::flood(vec![centre.clone()], |point| centre.distance_to(point, radius) <= distance)
+}
+
+/// Gets every point inside a range of latitudes and longitudes.
+///
+/// - `grid_point` - Any point on the grid to search.
+/// - `south` - The lowest latitude in radians.
+/// - `north` - The highest latitude in radians.
+/// - `west` - The longitude of the western edge in radians.
+/// - `east` - The longitude of the eastern edge in radians.
+pub(crate) fn points_in_box<P: SpherePoint + Hash>(grid_point: &P, south: f64, north: f64, west: f64, east: f64) -> Vec<P> {
+    // The span is measured eastwards so that a box crossing the antimeridian has a western edge
+    // with a greater longitude than its eastern edge.
+    let span = if east - west >= 2.0 * PI {
+        2.0 * PI
+    } else {
+        (east - west).rem_euclid(2.0 * PI)
+    };
+
+    let inside = |point: &P| {
+        let latitude = point.latitude();
+
+        // Every longitude meets at the poles.
+        (south..=north).contains(&latitude)
+            && (latitude.cos() < 1e-9 || (point.longitude() - west).rem_euclid(2.0 * PI) <= span)
+    };
+
+    // Rows of cells do not follow lines of latitude on every grid, so the cells inside a thin box
+    // are not always connected to each other. The search spreads through every cell that may be
+    // next to a cell in the box and then keeps only the cells inside it.
+    let near = |point: &P| {
+        let latitude = point.latitude();
+
+        let size = point.neighbourhood(Moore(1)).iter()
+            .map(|neighbour| point.distance_to(neighbour, 1.0))
+            .fold(0.0, f64::max);
+
+        // Both distances are no more than the distance from the point to the box.
+        let latitude_distance = (south - latitude).max(latitude - north).max(0.0);
+
+        let offset = (point.longitude() - west).rem_euclid(2.0 * PI);
+        let longitude_distance = if offset <= span {
+            0.0
+        } else {
+            let angle = (offset - span).min(2.0 * PI - offset).min(PI / 2.0);
+
+            (latitude.cos() * angle.sin()).clamp(0.0, 1.0).asin()
+        };
+
+        latitude_distance <= size && longitude_distance <= size
+    };
+
+    let seed = grid_point.at_geographic((south + north) / 2.0, (west + span / 2.0).rem_euclid(2.0 * PI));
+
+    // The point at the centre of the box may be just outside it for boxes smaller than a cell.
+    let mut seeds = seed.neighbourhood(Moore(1));
+    seeds.push(seed);
+
+    neighbourhood::flood(seeds, near)
+        .into_iter()
+        .filter(inside)
+        .collect()
 }
 
 /// A grid for a sphere based on the equirectangular projection.
 ///
 /// # Type Parameters
 /// - `T` - The type of data that the grid holds.
+/// - `D` - The storage holding the data.
 ///
 /// # Constant Parameters
 /// - `W` - The width of the grid.
 /// - `H` - The height of the grid.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
-pub struct RectangleSphereGrid<T, const W: usize, const H: usize> {
-    /// The data held in this grid.
-    data: HeapArray2D<T, W, H>,
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RectangleSphereGrid<T, const W: usize, const H: usize, D: Storage<T> = Vec<T>> {
+    /// The data held in this grid stored row by row.
+    data: D,
+    /// The type of data that the grid holds.
+    value: PhantomData<T>,
 }
 
-impl <T, const W: usize, const H: usize> SurfaceGrid<T> for RectangleSphereGrid<T, W, H> {
+impl <T, const W: usize, const H: usize, D: Storage<T>> SurfaceGrid<T> for RectangleSphereGrid<T, W, H, D> {
     type Point = RectangleSpherePoint<W, H>;
 
-    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
-        Self {
-            data: HeapArray2D::from_fn(|y, x| {
-                let point = RectangleSpherePoint::new(x as u32, y as u32);
-
-                f(&point)
-            })
-        }
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(f)
     }
 
-    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
-        Self {
-            data: HeapArray2D::from_fn_par(|y, x| {
-                let point = RectangleSpherePoint::new(x as u32, y as u32);
-
-                f(&point)
-            })
-        }
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(f)
     }
 
     fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
-        (0..H).cartesian_product(0..W)
-            .map(|(y, x)| RectangleSpherePoint::new(x as u32, y as u32))
-            .for_each(|point| self[point] = f(&point))
+        for (i, value) in self.data.iter_mut().enumerate() {
+            *value = f(&RectangleSpherePoint::from_index(i));
+        }
     }
 
     fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
-        self.data.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
-            for x in 0..W {
-                let point = RectangleSpherePoint::new(x as u32, y as u32);
-
-                subarray[x] = f(&point);
-            }
+        self.data.par_iter_mut().enumerate().for_each(|(i, value)| {
+            *value = f(&RectangleSpherePoint::from_index(i));
         })
     }
 
     fn iter<'a>(&'a self) -> impl Iterator<Item = (RectangleSpherePoint<W, H>, &'a T)> where T: 'a {
-        (0..H).cartesian_product(0..W)
-            .map(|(y, x)| (RectangleSpherePoint::new(x as u32, y as u32), &self.data[y][x]))
+        self.data.iter()
+            .enumerate()
+            .map(|(i, value)| (RectangleSpherePoint::from_index(i), value))
     }
 
     fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
-        (0..H).cartesian_product(0..W)
-            .par_bridge()
-            .map(|(y, x)| (RectangleSpherePoint::new(x as u32, y as u32), &self.data[y][x]))
+        self.data.par_iter()
+            .enumerate()
+            .map(|(i, value)| (RectangleSpherePoint::from_index(i), value))
     }
 
     fn points(&self) -> impl Iterator<Item = Self::Point> {
@@ -100,38 +291,66 @@ impl <T, const W: usize, const H: usize> SurfaceGrid<T> for RectangleSphereGrid<
     }
 
     fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
-        (0..H).cartesian_product(0..W)
-            .par_bridge()
-            .map(|(y, x)| RectangleSpherePoint::new(x as u32, y as u32))
+        (0..W * H).into_par_iter()
+            .map(RectangleSpherePoint::from_index)
+    }
+}
+
+impl <const W: usize, const H: usize> RectangleSphereGrid<f64, W, H> {
+    /// Creates a grid holding the area of each cell on the surface of a sphere.
+    ///
+    /// - `radius` - The radius of the sphere.
+    pub fn areas(radius: f64) -> Self {
+        Self::from_fn(|point| point.area(radius))
+    }
+}
+
+impl <T: Default, const W: usize, const H: usize, D: Storage<T>> Default for RectangleSphereGrid<T, W, H, D> {
+    fn default() -> Self {
+        Self::from_fn(|_| T::default())
+    }
+}
+
+impl <T, const W: usize, const H: usize, D: Storage<T>> StaticSurfaceGrid<T> for RectangleSphereGrid<T, W, H, D> {
+    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
+        Self {
+            data: D::from_fn(W * H, |i| f(&RectangleSpherePoint::from_index(i))),
+            value: PhantomData,
+        }
+    }
+
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self {
+            data: D::from_fn_par(W * H, |i| f(&RectangleSpherePoint::from_index(i))),
+            value: PhantomData,
+        }
     }
 }
 
-impl <T, const W: usize, const H: usize> Index<RectangleSpherePoint<W, H>> for RectangleSphereGrid<T, W, H> {
+impl <T, const W: usize, const H: usize, D: Storage<T>> Index<RectangleSpherePoint<W, H>> for RectangleSphereGrid<T, W, H, D> {
     type Output = T;
 
     fn index(&self, index: RectangleSpherePoint<W, H>) -> &Self::Output {
-        &self.data[index.y as usize][index.x as usize]
+        &self.data[index.to_index()]
     }
 }
 
-impl <T, const W: usize, const H: usize> IndexMut<RectangleSpherePoint<W, H>> for RectangleSphereGrid<T, W, H> {
+impl <T, const W: usize, const H: usize, D: Storage<T>> IndexMut<RectangleSpherePoint<W, H>> for RectangleSphereGrid<T, W, H, D> {
     fn index_mut(&mut self, index: RectangleSpherePoint<W, H>) -> &mut Self::Output {
-        &mut self.data[index.y as usize][index.x as usize]
+        &mut self.data[index.to_index()]
     }
 }
 
-impl <T, const W: usize, const H: usize> IntoIterator for RectangleSphereGrid<T, W, H> {
+impl <T, const W: usize, const H: usize, D: Storage<T>> IntoIterator for RectangleSphereGrid<T, W, H, D> {
     type Item = (RectangleSpherePoint<W, H>, T);
 
     type IntoIter = vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        let data: Vec<_> = self.data.into_iter()
+        let data: Vec<_> = self.data.into_vec()
+            .into_iter()
             .enumerate()
-            .flat_map(|(y, subarray)| subarray.into_iter()
-                      .enumerate()
-                      .map(move |(x, value)| (RectangleSpherePoint::new(x as u32, y as u32), value))
-                      )
+            .map(|(i, value)| (RectangleSpherePoint::from_index(i), value))
             .collect();
 
         data.into_iter()
@@ -153,1826 +372,3709 @@ pub struct RectangleSpherePoint<const W: usize, const H: usize> {
 
 impl <const W: usize, const H: usize> RectangleSpherePoint<W, H> {
     fn new(x: u32, y: u32) -> Self {
-        let x = (x + y / H as u32).rem_euclid(W as u32);
-        let y = y.rem_euclid(H as u32);
+        Self::from_dyn(DynRectangleSpherePoint::new(x, y, W as u32, H as u32))
+    }
+
+    /// Creates a new `RectangleSpherePoint`.
+    /// Returns an error if the coordinates are outside the grid.
+    ///
+    /// - `x` - The X position in the grid.
+    /// - `y` - The Y position in the grid.
+    pub fn try_new(x: usize, y: usize) -> Result<Self, PointError> {
+        PointError::check(x, y, W, H)?;
+
+        Ok(Self::new(x as u32, y as u32))
+    }
+
+    /// Gets the X position in the grid.
+    pub fn x(&self) -> usize {
+        self.x as usize
+    }
 
+    /// Gets the Y position in the grid.
+    pub fn y(&self) -> usize {
+        self.y as usize
+    }
+
+    /// Converts this point into the equivalent point on a `DynRectangleSphereGrid`.
+    fn to_dyn(self) -> DynRectangleSpherePoint {
+        DynRectangleSpherePoint {
+            x: self.x,
+            y: self.y,
+            width: W as u32,
+            height: H as u32,
+        }
+    }
+
+    /// Converts a point on a `DynRectangleSphereGrid` with the same dimensions into a point on
+    /// this grid.
+    fn from_dyn(point: DynRectangleSpherePoint) -> Self {
         Self {
-            x,
-            y
+            x: point.x,
+            y: point.y,
         }
     }
+
+    /// Gets the area of the cell at this point on the surface of an ellipsoid.
+    ///
+    /// - `ellipsoid` - The ellipsoid that the grid is wrapped around.
+    pub fn ellipsoid_area(&self, ellipsoid: &Ellipsoid) -> f64 {
+        self.to_dyn().ellipsoid_area(ellipsoid)
+    }
+
+    /// Gets the positions of the corners of the cell at this point in 3D space.
+    /// The corners are in clockwise order when viewed from outside the sphere: north west,
+    /// north east, south east, south west.
+    ///
+    /// - `scale` - The radius of the sphere.
+    pub fn corners(&self, scale: f64) -> [(f64, f64, f64); 4] {
+        self.to_dyn().corners(scale)
+    }
+
+    /// Gets the solid angle covered by the cell at this point in steradians.
+    pub fn solid_angle(&self) -> f64 {
+        self.to_dyn().solid_angle()
+    }
+
+    /// Gets the area of the cell at this point on the surface of a sphere.
+    ///
+    /// - `radius` - The radius of the sphere.
+    pub fn area(&self, radius: f64) -> f64 {
+        self.to_dyn().area(radius)
+    }
 }
 
 impl <const W: usize, const H: usize> GridPoint for RectangleSpherePoint<W, H> {
     fn up(&self) -> Self {
-        if self.x >= W as u32 / 2 {
-            if self.y == H as u32 - 1 {
-                Self {
-                    x: (self.x + W as u32 / 2).rem_euclid(W as u32),
-                    y: H as u32 - 1,
-                }
-            } else {
-                Self {
-                    x: self.x,
-                    y: self.y + 1,
-                }
-            }
-        } else {
-            if self.y == 0 {
-                Self {
-                    x: (self.x + W as u32 / 2).rem_euclid(W as u32),
-                    y: 0,
-                }
-            } else {
-                Self {
-                    x: self.x,
-                    y: self.y - 1,
-                }
-            }
-        }
+        Self::from_dyn(self.to_dyn().up())
     }
 
     fn down(&self) -> Self {
-        if self.x < W as u32 / 2 {
-            if self.y == H as u32 - 1 {
-                Self {
-                    x: (self.x + W as u32 / 2).rem_euclid(W as u32),
-                    y: H as u32 - 1,
-                }
-            } else {
-                Self {
-                    x: self.x,
-                    y: self.y + 1,
-                }
-            }
-        } else {
-            if self.y == 0 {
-                Self {
-                    x: (self.x + W as u32 / 2).rem_euclid(W as u32),
-                    y: 0,
-                }
-            } else {
-                Self {
-                    x: self.x,
-                    y: self.y - 1,
-                }
-            }
-        }
+        Self::from_dyn(self.to_dyn().down())
     }
 
     fn left(&self) -> Self {
-        Self {
-            x: (self.x as i32 - 1).rem_euclid(W as i32) as u32,
-            y: self.y
-        }
+        Self::from_dyn(self.to_dyn().left())
     }
 
     fn right(&self) -> Self {
-        Self {
-            x: (self.x + 1).rem_euclid(W as u32),
-            y: self.y
-        }
+        Self::from_dyn(self.to_dyn().right())
     }
 
     fn position(&self, scale: f64) -> (f64, f64, f64) {
-        let (long, lat) = self.sphere_coordinates();
-
-        let y = scale * lat.sin();
-        let radius = scale * lat.cos();
-
-        let x = radius * long.sin();
-        let z = radius * long.cos();
+        self.to_dyn().position(scale)
+    }
+}
 
-        (x, y, z)
+impl <const W: usize, const H: usize> CellPoint for RectangleSpherePoint<W, H> {
+    fn corners(&self, scale: f64) -> [(f64, f64, f64); 4] {
+        RectangleSpherePoint::corners(self, scale)
     }
 }
 
-impl <const W: usize, const H: usize> SpherePoint for RectangleSpherePoint<W, H> {
-    fn from_geographic(latitude: f64, longitude: f64) -> Self {
-        let latitude = -latitude;
+impl <const W: usize, const H: usize> IndexedPoint for RectangleSpherePoint<W, H> {
+    const COUNT: usize = W * H;
 
-        let x = ((longitude / (PI * 2.0) * W as f64) as i32).rem_euclid(W as i32) as u32;
-        let y = (latitude + PI / 2.0) / PI;
+    fn to_index(&self) -> usize {
+        self.y as usize * W + self.x as usize
+    }
 
-        let y = ((2 * (y.ceil() as i32).rem_euclid(2) - 1)
-            * ((y * H as f64) as i32).rem_euclid(H as i32)
-            + H as i32 * (y.floor() as i32).rem_euclid(2)) as u32;
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
 
-        let y = if y == 100 {
-            99
-        } else {
-            y
-        };
+        Self::new((index % W) as u32, (index / W) as u32)
+    }
+}
 
-        Self {
-            x, y
-        }
+impl <const W: usize, const H: usize> SpherePoint for RectangleSpherePoint<W, H> {
+    fn at_geographic(&self, latitude: f64, longitude: f64) -> Self {
+        Self::from_geographic(latitude, longitude)
     }
-    
+
     fn latitude(&self) -> f64 {
-        -(self.y as f64 / H as f64 * PI - PI / 2.0)
+        self.to_dyn().latitude()
     }
 
     fn longitude(&self) -> f64 {
-        self.x as f64 / W as f64 * PI * 2.0
+        self.to_dyn().longitude()
     }
 }
 
-/// A grid that wraps a cube around a sphere in order to determine grid positions.
+impl <const W: usize, const H: usize> StaticSpherePoint for RectangleSpherePoint<W, H> {
+    fn from_geographic(latitude: f64, longitude: f64) -> Self {
+        Self::from_dyn(DynRectangleSpherePoint::from_geographic(latitude, longitude, W as u32, H as u32))
+    }
+}
+
+/// A grid for a sphere based on the equirectangular projection with dimensions chosen at runtime.
 ///
-/// # Type Parameters.
-/// - `T` - The type of element stored in each grid cell.
+/// This behaves in the same way as a `RectangleSphereGrid` with the same dimensions.
 ///
-/// # Constant Parameters
-/// - `S` - The size of each side of each face.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
-pub struct CubeSphereGrid<T, const S: usize> {
-    top: HeapArray2D<T, S, S>,
-    left: HeapArray2D<T, S, S>,
-    front: HeapArray2D<T, S, S>,
-    right: HeapArray2D<T, S, S>,
-    back: HeapArray2D<T, S, S>,
-    bottom: HeapArray2D<T, S, S>,
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DynRectangleSphereGrid<T> {
+    /// The width of the grid.
+    width: usize,
+    /// The height of the grid.
+    height: usize,
+    /// The data held in this grid stored row by row.
+    data: Vec<T>,
 }
 
-impl <T: Debug, const S: usize> SurfaceGrid<T> for CubeSphereGrid<T, S> {
-    type Point = CubeSpherePoint<S>;
+impl <T> DynRectangleSphereGrid<T> {
+    /// Creates a new grid filled with the default value of `T`.
+    ///
+    /// - `width` - The width of the grid.
+    /// - `height` - The height of the grid.
+    pub fn new(width: usize, height: usize) -> Self where T: Default {
+        Self::from_fn(width, height, |_| T::default())
+    }
+
+    /// Creates a new grid by calling the specified function for each point in the grid.
+    ///
+    /// - `width` - The width of the grid.
+    /// - `height` - The height of the grid.
+    /// - `f` - The function to apply.
+    pub fn from_fn<F: FnMut(&DynRectangleSpherePoint) -> T>(width: usize, height: usize, mut f: F) -> Self {
+        assert!(width > 0 && height > 0, "A grid must have a width and height greater than zero");
+        assert!(width <= u32::MAX as usize && height <= u32::MAX as usize, "A grid must fit within u32 coordinates");
 
-    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
         Self {
-            top: HeapArray2D::from_fn(|y, x| f(&CubeSpherePoint::new(CubeFace::Top, x as u16, y as u16))),
-            left: HeapArray2D::from_fn(|y, x| f(&CubeSpherePoint::new(CubeFace::Left, x as u16, y as u16))),
-            front: HeapArray2D::from_fn(|y, x| f(&CubeSpherePoint::new(CubeFace::Front, x as u16, y as u16))),
-            right: HeapArray2D::from_fn(|y, x| f(&CubeSpherePoint::new(CubeFace::Right, x as u16, y as u16))),
-            back: HeapArray2D::from_fn(|y, x| f(&CubeSpherePoint::new(CubeFace::Back, x as u16, y as u16))),
-            bottom: HeapArray2D::from_fn(|y, x| f(&CubeSpherePoint::new(CubeFace::Bottom, x as u16, y as u16))),
+            width,
+            height,
+            data: (0..height).cartesian_product(0..width)
+                .map(|(y, x)| f(&DynRectangleSpherePoint::new(x as u32, y as u32, width as u32, height as u32)))
+                .collect(),
         }
     }
 
-    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+    /// Creates a new grid by calling the specified function in parallel for each point in the
+    /// grid.
+    ///
+    /// - `width` - The width of the grid.
+    /// - `height` - The height of the grid.
+    /// - `f` - The function to apply.
+    pub fn from_fn_par<F: Fn(&DynRectangleSpherePoint) -> T + Send + Sync>(width: usize, height: usize, f: F) -> Self where T: Send + Sync {
+        assert!(width > 0 && height > 0, "A grid must have a width and height greater than zero");
+        assert!(width <= u32::MAX as usize && height <= u32::MAX as usize, "A grid must fit within u32 coordinates");
+
         Self {
-            top: HeapArray2D::from_fn_par(|y, x| f(&CubeSpherePoint::new(CubeFace::Top, x as u16, y as u16))),
-            left: HeapArray2D::from_fn_par(|y, x| f(&CubeSpherePoint::new(CubeFace::Left, x as u16, y as u16))),
-            front: HeapArray2D::from_fn_par(|y, x| f(&CubeSpherePoint::new(CubeFace::Front, x as u16, y as u16))),
-            right: HeapArray2D::from_fn_par(|y, x| f(&CubeSpherePoint::new(CubeFace::Right, x as u16, y as u16))),
-            back: HeapArray2D::from_fn_par(|y, x| f(&CubeSpherePoint::new(CubeFace::Back, x as u16, y as u16))),
-            bottom: HeapArray2D::from_fn_par(|y, x| f(&CubeSpherePoint::new(CubeFace::Bottom, x as u16, y as u16))),
+            width,
+            height,
+            data: (0..width * height).into_par_iter()
+                .map(|i| f(&DynRectangleSpherePoint::new((i % width) as u32, (i / width) as u32, width as u32, height as u32)))
+                .collect(),
         }
     }
 
-    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
-        [
-            CubeFace::Top,
-            CubeFace::Left,
-            CubeFace::Front,
-            CubeFace::Right,
-            CubeFace::Back,
-            CubeFace::Bottom,
-        ].into_iter()
-            .cartesian_product(0..S)
-            .cartesian_product(0..S)
-            .map(|((face, x), y)| CubeSpherePoint::new(face, x as u16, y as u16))
-            .map(|point| (point, f(&point)))
-            .for_each(|(point, value)| self[point] = value)
+    /// Gets the width of this grid.
+    pub fn width(&self) -> usize {
+        self.width
     }
 
-    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
-        for face in [
-            CubeFace::Top,
-            CubeFace::Left,
-            CubeFace::Front,
-            CubeFace::Right,
-            CubeFace::Back,
-            CubeFace::Bottom,
-        ] {
-            match face {
-                CubeFace::Front => &mut self.front,
-                CubeFace::Back => &mut self.back,
-                CubeFace::Left => &mut self.left,
-                CubeFace::Right => &mut self.right,
-                CubeFace::Top => &mut self.top,
-                CubeFace::Bottom => &mut self.bottom,
-            }.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| for x in 0..S {
-                let point = CubeSpherePoint::new(face, x as u16, y as u16);
-
-                subarray[x] = f(&point);
-            });
-        }
+    /// Gets the height of this grid.
+    pub fn height(&self) -> usize {
+        self.height
     }
 
-    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
-        self.points()
-            .map(|point| (point, &self[point]))
+    /// Gets the point on this grid for the specified geographic coordinates.
+    ///
+    /// - `latitude` - The latitude of the point in radians where 0 is the equator.
+    /// - `longitude` - The longitude of the point in radians.
+    pub fn point_at_geographic(&self, latitude: f64, longitude: f64) -> DynRectangleSpherePoint {
+        DynRectangleSpherePoint::from_geographic(latitude, longitude, self.width as u32, self.height as u32)
     }
 
-    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
-        self.par_points()
-            .map(|point| (point, &self[point]))
+    /// Gets the point on this grid at the specified index into the data.
+    ///
+    /// - `i` - The index of the point.
+    fn point_at(&self, i: usize) -> DynRectangleSpherePoint {
+        DynRectangleSpherePoint::new((i % self.width) as u32, (i / self.width) as u32, self.width as u32, self.height as u32)
     }
 
-    fn points(&self) -> impl Iterator<Item = Self::Point> {
-        [
-            CubeFace::Top,
-            CubeFace::Left,
-            CubeFace::Front,
-            CubeFace::Right,
-            CubeFace::Back,
-            CubeFace::Bottom,
-        ].into_iter()
-            .cartesian_product(0..S)
-            .cartesian_product(0..S)
-            .map(|((face, x), y)| CubeSpherePoint::new(face, x as u16, y as u16))
+    /// Gets the index into the data of the specified point.
+    ///
+    /// - `point` - The point to get the index of.
+    fn index_of(&self, point: &DynRectangleSpherePoint) -> usize {
+        assert!(point.width as usize == self.width && point.height as usize == self.height,
+            "The point does not belong to a grid with the same dimensions");
+
+        point.y as usize * self.width + point.x as usize
     }
+}
 
-    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
-        [
-            CubeFace::Top,
-            CubeFace::Left,
-            CubeFace::Front,
-            CubeFace::Right,
-            CubeFace::Back,
-            CubeFace::Bottom,
-        ].into_iter()
-            .cartesian_product(0..S)
-            .cartesian_product(0..S)
-            .par_bridge()
-            .map(|((face, x), y)| CubeSpherePoint::new(face, x as u16, y as u16))
+impl DynRectangleSphereGrid<f64> {
+    /// Creates a grid holding the area of each cell on the surface of a sphere.
+    ///
+    /// - `width` - The width of the grid.
+    /// - `height` - The height of the grid.
+    /// - `radius` - The radius of the sphere.
+    pub fn areas(width: usize, height: usize, radius: f64) -> Self {
+        Self::from_fn(width, height, |point| point.area(radius))
     }
 }
 
-impl <T, const S: usize> Index<CubeSpherePoint<S>> for CubeSphereGrid<T, S> {
-    type Output = T;
+impl <T> SurfaceGrid<T> for DynRectangleSphereGrid<T> {
+    type Point = DynRectangleSpherePoint;
 
-    fn index(&self, index: CubeSpherePoint<S>) -> &Self::Output {
-        match index.face {
-            CubeFace::Front => &self.front[index.y as usize][index.x as usize],
-            CubeFace::Back => &self.back[index.y as usize][index.x as usize],
-            CubeFace::Left => &self.left[index.y as usize][index.x as usize],
-            CubeFace::Right => &self.right[index.y as usize][index.x as usize],
-            CubeFace::Top => &self.top[index.y as usize][index.x as usize],
-            CubeFace::Bottom => &self.bottom[index.y as usize][index.x as usize],
-        }
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(self.width, self.height, f)
     }
-}
 
-impl <T, const S: usize> IndexMut<CubeSpherePoint<S>> for CubeSphereGrid<T, S> {
-    fn index_mut(&mut self, index: CubeSpherePoint<S>) -> &mut Self::Output {
-        match index.face {
-            CubeFace::Front => &mut self.front[index.y as usize][index.x as usize],
-            CubeFace::Back => &mut self.back[index.y as usize][index.x as usize],
-            CubeFace::Left => &mut self.left[index.y as usize][index.x as usize],
-            CubeFace::Right => &mut self.right[index.y as usize][index.x as usize],
-            CubeFace::Top => &mut self.top[index.y as usize][index.x as usize],
-            CubeFace::Bottom => &mut self.bottom[index.y as usize][index.x as usize],
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(self.width, self.height, f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        for i in 0..self.data.len() {
+            let point = self.point_at(i);
+
+            self.data[i] = f(&point);
         }
     }
-}
 
-impl <T, const S: usize> IntoIterator for CubeSphereGrid<T, S> {
-    type Item = (CubeSpherePoint<S>, T);
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        let (width, height) = (self.width, self.height);
 
-    type IntoIter = vec::IntoIter<Self::Item>;
+        self.data.par_iter_mut().enumerate().for_each(|(i, value)| {
+            let point = DynRectangleSpherePoint::new((i % width) as u32, (i / width) as u32, width as u32, height as u32);
 
-    fn into_iter(self) -> Self::IntoIter {
-        let mut data: Vec<_> = self.top.into_iter()
-            .enumerate()
-            .flat_map(|(y, subarray)| subarray.into_iter()
-                        .enumerate()
-                        .map(move |(x, value)| (CubeSpherePoint::new(CubeFace::Top, x as u16, y as u16), value))
-                      )
-            .collect();
+            *value = f(&point);
+        })
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        self.data.iter()
+            .enumerate()
+            .map(|(i, value)| (self.point_at(i), value))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        self.data.par_iter()
+            .enumerate()
+            .map(|(i, value)| (self.point_at(i), value))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        (0..self.data.len())
+            .map(|i| self.point_at(i))
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        let (width, height) = (self.width, self.height);
+
+        (0..self.data.len())
+            .into_par_iter()
+            .map(move |i| DynRectangleSpherePoint::new((i % width) as u32, (i / width) as u32, width as u32, height as u32))
+    }
+}
+
+impl <T> Index<DynRectangleSpherePoint> for DynRectangleSphereGrid<T> {
+    type Output = T;
+
+    fn index(&self, index: DynRectangleSpherePoint) -> &Self::Output {
+        &self.data[self.index_of(&index)]
+    }
+}
+
+impl <T> IndexMut<DynRectangleSpherePoint> for DynRectangleSphereGrid<T> {
+    fn index_mut(&mut self, index: DynRectangleSpherePoint) -> &mut Self::Output {
+        let i = self.index_of(&index);
+
+        &mut self.data[i]
+    }
+}
+
+impl <T> IntoIterator for DynRectangleSphereGrid<T> {
+    type Item = (DynRectangleSpherePoint, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let (width, height) = (self.width as u32, self.height as u32);
 
-        data.extend(self.left.into_iter()
-                    .enumerate()
-                    .flat_map(|(y, subarray)| subarray.into_iter()
-                              .enumerate()
-                              .map(move |(x, value)| (CubeSpherePoint::new(CubeFace::Left, x as u16, y as u16), value))
-                              ));
-        data.extend(self.front.into_iter()
-                    .enumerate()
-                    .flat_map(|(y, subarray)| subarray.into_iter()
-                              .enumerate()
-                              .map(move |(x, value)| (CubeSpherePoint::new(CubeFace::Front, x as u16, y as u16), value))
-                              ));
-        data.extend(self.right.into_iter()
-                    .enumerate()
-                    .flat_map(|(y, subarray)| subarray.into_iter()
-                              .enumerate()
-                              .map(move |(x, value)| (CubeSpherePoint::new(CubeFace::Right, x as u16, y as u16), value))
-                              ));
-        data.extend(self.back.into_iter()
-                    .enumerate()
-                    .flat_map(|(y, subarray)| subarray.into_iter()
-                              .enumerate()
-                              .map(move |(x, value)| (CubeSpherePoint::new(CubeFace::Back, x as u16, y as u16), value))
-                              ));
-        data.extend(self.bottom.into_iter()
-                    .enumerate()
-                    .flat_map(|(y, subarray)| subarray.into_iter()
-                              .enumerate()
-                              .map(move |(x, value)| (CubeSpherePoint::new(CubeFace::Bottom, x as u16, y as u16), value))
-                              ));
+        let data: Vec<_> = self.data.into_iter()
+            .enumerate()
+            .map(|(i, value)| (DynRectangleSpherePoint::new(i as u32 % width, i as u32 / width, width, height), value))
+            .collect();
 
         data.into_iter()
     }
 }
 
-/// A point on a `CubeSphereGrid`.
+/// A point on a `DynRectangleSphereGrid`.
 ///
-/// # Constant Parameters
-/// - `S` - The size of each side of each face.
+/// The point stores the dimensions of the grid that it belongs to.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct CubeSpherePoint<const S: usize> {
-    face: CubeFace,
-    x: u16,
-    y: u16,
+pub struct DynRectangleSpherePoint {
+    /// The X position in the grid.
+    x: u32,
+    /// The Y position in the grid.
+    y: u32,
+    /// The width of the grid.
+    width: u32,
+    /// The height of the grid.
+    height: u32,
 }
 
-impl <const S: usize> CubeSpherePoint<S> {
-    /// Creates a new `CubeSpherePoint`.
+impl DynRectangleSpherePoint {
+    fn new(x: u32, y: u32, width: u32, height: u32) -> Self {
+        let x = (x + y / height).rem_euclid(width);
+        let y = y.rem_euclid(height);
+
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Gets the point for the specified geographic coordinates on a grid of the specified size.
     ///
-    /// - `face` - The face on which the point lies.
-    /// - `x` - The X position on the face.
-    /// - `y` - The Y position on the face.
-    fn new(face: CubeFace, x: u16, y: u16) -> Self {
+    /// - `latitude` - The latitude of the point in radians where 0 is the equator.
+    /// - `longitude` - The longitude of the point in radians.
+    /// - `width` - The width of the grid.
+    /// - `height` - The height of the grid.
+    fn from_geographic(latitude: f64, longitude: f64, width: u32, height: u32) -> Self {
+        let latitude = -latitude;
+
+        let x = ((longitude / (PI * 2.0) * width as f64) as i32).rem_euclid(width as i32) as u32;
+        let y = (latitude + PI / 2.0) / PI;
+
+        let y = ((2 * (y.ceil() as i32).rem_euclid(2) - 1)
+            * ((y * height as f64) as i32).rem_euclid(height as i32)
+            + height as i32 * (y.floor() as i32).rem_euclid(2)) as u32;
+
+        // Account for the south pole landing one past the last row.
+        let y = if y == height {
+            height - 1
+        } else {
+            y
+        };
+
         Self {
-            face,
-            // Clamp to account for floating point rounding error.
-            x: x.clamp(0, S as u16 - 1),
-            y: y.clamp(0, S as u16 - 1)
+            x, y, width, height
         }
     }
+
+    /// Creates a new `DynRectangleSpherePoint`.
+    /// Returns an error if the coordinates are outside the grid.
+    ///
+    /// - `x` - The X position in the grid.
+    /// - `y` - The Y position in the grid.
+    /// - `width` - The width of the grid.
+    /// - `height` - The height of the grid.
+    pub fn try_new(x: usize, y: usize, width: usize, height: usize) -> Result<Self, PointError> {
+        PointError::check(x, y, width, height)?;
+
+        Ok(Self::new(x as u32, y as u32, width as u32, height as u32))
+    }
+
+    /// Gets the X position in the grid.
+    pub fn x(&self) -> usize {
+        self.x as usize
+    }
+
+    /// Gets the Y position in the grid.
+    pub fn y(&self) -> usize {
+        self.y as usize
+    }
+
+    /// Gets the width of the grid that this point belongs to.
+    pub fn width(&self) -> usize {
+        self.width as usize
+    }
+
+    /// Gets the height of the grid that this point belongs to.
+    pub fn height(&self) -> usize {
+        self.height as usize
+    }
+
+    /// Gets the area of the cell at this point on the surface of an ellipsoid.
+    ///
+    /// - `ellipsoid` - The ellipsoid that the grid is wrapped around.
+    pub fn ellipsoid_area(&self, ellipsoid: &Ellipsoid) -> f64 {
+        let (south, north) = self.latitude_bounds();
+
+        ellipsoid.quadrangle_area(south, north, 2.0 * PI / self.width as f64)
+    }
+
+    /// Gets the latitudes of the southern and northern edges of the cell at this point.
+    fn latitude_bounds(&self) -> (f64, f64) {
+        let north = PI / 2.0 - self.y as f64 / self.height as f64 * PI;
+        let south = north - PI / self.height as f64;
+
+        (south, north)
+    }
+
+    /// Gets the positions of the corners of the cell at this point in 3D space.
+    /// The corners are in clockwise order when viewed from outside the sphere: north west,
+    /// north east, south east, south west.
+    ///
+    /// - `scale` - The radius of the sphere.
+    pub fn corners(&self, scale: f64) -> [(f64, f64, f64); 4] {
+        let (south, north) = self.latitude_bounds();
+
+        let west = self.x as f64 / self.width as f64 * PI * 2.0;
+        let east = (self.x + 1) as f64 / self.width as f64 * PI * 2.0;
+
+        [
+            geographic_position(north, west, scale),
+            geographic_position(north, east, scale),
+            geographic_position(south, east, scale),
+            geographic_position(south, west, scale),
+        ]
+    }
+
+    /// Gets the solid angle covered by the cell at this point in steradians.
+    pub fn solid_angle(&self) -> f64 {
+        let (south, north) = self.latitude_bounds();
+
+        2.0 * PI / self.width as f64 * (north.sin() - south.sin())
+    }
+
+    /// Gets the area of the cell at this point on the surface of a sphere.
+    ///
+    /// - `radius` - The radius of the sphere.
+    pub fn area(&self, radius: f64) -> f64 {
+        self.solid_angle() * radius * radius
+    }
 }
 
-impl <const S: usize> GridPoint for CubeSpherePoint<S> {
+impl GridPoint for DynRectangleSpherePoint {
     fn up(&self) -> Self {
-        match self.face {
-            CubeFace::Front => if self.y == 0 {
-                Self {
-                    face: CubeFace::Top,
-                    x: self.x,
-                    y: S as u16 - 1,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Front,
-                    x: self.x,
-                    y: self.y - 1,
-                }
-            },
-            CubeFace::Back => if self.y == 0 {
-                Self {
-                    face: CubeFace::Bottom,
-                    x: self.x,
-                    y: S as u16 - 1,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Back,
-                    x: self.x,
-                    y: self.y - 1,
-                }
-            },
-            CubeFace::Left => if self.y == 0 {
+        if self.x >= self.width / 2 {
+            if self.y == self.height - 1 {
                 Self {
-                    face: CubeFace::Top,
-                    x: 0,
-                    y: self.x
-                }
-            } else {
-                Self {
-                    face: CubeFace::Left,
-                    x: self.x,
-                    y: self.y - 1,
-                }
-            },
-            CubeFace::Right => if self.y == 0 {
-                Self {
-                    face: CubeFace::Top,
-                    x: S as u16 - 1,
-                    y: self.x
-                }
-            } else {
-                Self {
-                    face: CubeFace::Right,
-                    x: self.x,
-                    y: self.y - 1,
-                }
-            },
-            CubeFace::Top => if self.y == 0 {
-                Self {
-                    face: CubeFace::Back,
-                    x: self.x,
-                    y: S as u16 - 1,
+                    x: (self.x + self.width / 2).rem_euclid(self.width),
+                    y: self.height - 1,
+                    ..*self
                 }
             } else {
                 Self {
-                    face: CubeFace::Top,
-                    x: self.x,
-                    y: self.y - 1,
+                    y: self.y + 1,
+                    ..*self
                 }
-            },
-            CubeFace::Bottom => if self.y == 0 {
+            }
+        } else {
+            if self.y == 0 {
                 Self {
-                    face: CubeFace::Front,
-                    x: self.x,
-                    y: S as u16 - 1,
+                    x: (self.x + self.width / 2).rem_euclid(self.width),
+                    y: 0,
+                    ..*self
                 }
             } else {
                 Self {
-                    face: CubeFace::Bottom,
-                    x: self.x,
                     y: self.y - 1,
+                    ..*self
                 }
-            },
+            }
         }
     }
 
     fn down(&self) -> Self {
-        match self.face {
-            CubeFace::Front => if self.y == S as u16 - 1 {
-                Self {
-                    face: CubeFace::Bottom,
-                    x: self.x,
-                    y: 0,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Front,
-                    x: self.x,
-                    y: self.y + 1,
-                }
-            },
-            CubeFace::Back => if self.y == S as u16 - 1 {
-                Self {
-                    face: CubeFace::Top,
-                    x: self.x,
-                    y: 0,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Back,
-                    x: self.x,
-                    y: self.y + 1,
-                }
-            },
-            CubeFace::Left => if self.y == S as u16 - 1 {
-                Self {
-                    face: CubeFace::Bottom,
-                    x: 0,
-                    y: self.x
-                }
-            } else {
-                Self {
-                    face: CubeFace::Left,
-                    x: self.x,
-                    y: self.y + 1,
-                }
-            },
-            CubeFace::Right => if self.y == S as u16 - 1 {
-                Self {
-                    face: CubeFace::Bottom,
-                    x: 0,
-                    y: self.x
-                }
-            } else {
-                Self {
-                    face: CubeFace::Right,
-                    x: self.x,
-                    y: self.y + 1,
-                }
-            },
-            CubeFace::Top => if self.y == S as u16 - 1 {
+        if self.x < self.width / 2 {
+            if self.y == self.height - 1 {
                 Self {
-                    face: CubeFace::Front,
-                    x: self.x,
-                    y: 0,
+                    x: (self.x + self.width / 2).rem_euclid(self.width),
+                    y: self.height - 1,
+                    ..*self
                 }
             } else {
                 Self {
-                    face: CubeFace::Top,
-                    x: self.x,
                     y: self.y + 1,
+                    ..*self
                 }
-            },
-            CubeFace::Bottom => if self.y == S as u16 - 1 {
+            }
+        } else {
+            if self.y == 0 {
                 Self {
-                    face: CubeFace::Back,
-                    x: self.x,
+                    x: (self.x + self.width / 2).rem_euclid(self.width),
                     y: 0,
+                    ..*self
                 }
             } else {
                 Self {
-                    face: CubeFace::Bottom,
-                    x: self.x,
-                    y: self.y + 1,
+                    y: self.y - 1,
+                    ..*self
                 }
-            },
+            }
         }
     }
 
     fn left(&self) -> Self {
-        match self.face {
-            CubeFace::Front => if self.x == 0 {
-                Self {
-                    face: CubeFace::Left,
-                    x: S as u16 - 1,
-                    y: self.y
-                }
-            } else {
-                Self {
-                    face: CubeFace::Front,
-                    x: self.x - 1,
-                    y: self.y
-                }
-            },
-            CubeFace::Back => if self.x == S as u16 - 1 {
-                Self {
-                    face: CubeFace::Right,
-                    x: S as u16 - 1,
-                    y: self.y
-                }
-            } else {
-                Self {
-                    face: CubeFace::Back,
-                    x: self.x + 1,
-                    y: self.y
-                }
-            },
-            CubeFace::Left => if self.x == 0 {
-                Self {
-                    face: CubeFace::Back,
-                    x: 0,
-                    y: self.y,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Left,
-                    x: self.x - 1,
-                    y: self.y
-                }
-            },
-            CubeFace::Right => if self.x == 0 {
-                Self {
-                    face: CubeFace::Front,
-                    x: S as u16 - 1,
-                    y: self.y
-                }
-            } else {
-                Self {
-                    face: CubeFace::Right,
-                    x: self.x - 1,
-                    y: self.y,
-                }
-            },
-            CubeFace::Top => if self.x == 0 {
-                Self {
-                    face: CubeFace::Left,
-                    x: self.y,
-                    y: 0,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Top,
-                    x: self.x - 1,
-                    y: self.y
-                }
-            },
-            CubeFace::Bottom => if self.x == 0 {
-                Self {
-                    face: CubeFace::Left,
-                    x: self.y,
-                    y: S as u16 - 1,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Bottom,
-                    x: self.x - 1,
-                    y: self.y
-                }
-            },
+        Self {
+            x: (self.x as i64 - 1).rem_euclid(self.width as i64) as u32,
+            ..*self
         }
     }
 
     fn right(&self) -> Self {
-        match self.face {
-            CubeFace::Front => if self.x == S as u16 - 1 {
-                Self {
-                    face: CubeFace::Right,
-                    x: 0,
-                    y: self.y
-                }
-            } else {
-                Self {
-                    face: CubeFace::Front,
-                    x: self.x + 1,
-                    y: self.y
-                }
-            },
-            CubeFace::Back => if self.x == 0 {
-                Self {
-                    face: CubeFace::Left,
-                    x: 0,
-                    y: self.y
-                }
-            } else {
-                Self {
-                    face: CubeFace::Back,
-                    x: self.x - 1,
-                    y: self.y
-                }
-            },
-            CubeFace::Left => if self.x == S as u16 - 1 {
-                Self {
-                    face: CubeFace::Front,
-                    x: 0,
-                    y: self.y,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Left,
-                    x: self.x + 1,
-                    y: self.y
-                }
-            },
-            CubeFace::Right => if self.x == S as u16 - 1 {
-                Self {
-                    face: CubeFace::Back,
-                    x: S as u16 - 1,
-                    y: self.y
-                }
-            } else {
-                Self {
-                    face: CubeFace::Right,
-                    x: self.x + 1,
-                    y: self.y,
-                }
-            },
-            CubeFace::Top => if self.x == S as u16 - 1{
-                Self {
-                    face: CubeFace::Right,
-                    x: self.y,
-                    y: 0,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Top,
-                    x: self.x + 1,
-                    y: self.y
-                }
-            },
-            CubeFace::Bottom => if self.x == S as u16 - 1 {
-                Self {
-                    face: CubeFace::Right,
-                    x: self.y,
-                    y: S as u16 - 1,
-                }
-            } else {
-                Self {
-                    face: CubeFace::Bottom,
-                    x: self.x + 1,
-                    y: self.y
-                }
-            },
+        Self {
+            x: (self.x + 1).rem_euclid(self.width),
+            ..*self
         }
     }
 
     fn position(&self, scale: f64) -> (f64, f64, f64) {
-        let x = self.x as f64;
-        let y = self.y as f64;
+        let (long, lat) = self.sphere_coordinates();
 
-        let (x, y, z) = match self.face {
-            CubeFace::Front => (x * 2.0 - S as f64, y * 2.0 - S as f64, S as f64),
-            CubeFace::Back => (x * 2.0 - S as f64, -y * 2.0 + S as f64, -(S as f64)),
-            CubeFace::Left => (-(S as f64), y * 2.0 - S as f64, x * 2.0 -(S as f64)),
-            CubeFace::Right => (S as f64, y * 2.0 - S as f64, S as f64 - x * 2.0),
-            CubeFace::Top => (x * 2.0 - S as f64, S as f64, y * 2.0 - S as f64),
-            CubeFace::Bottom => (x * 2.0 - S as f64, -(S as f64), S as f64 - y * 2.0),
-        };
+        let y = scale * lat.sin();
+        let radius = scale * lat.cos();
 
-        let length = (x * x + y * y + z * z).sqrt();
+        let x = radius * long.sin();
+        let z = radius * long.cos();
 
-        (x / length * scale, y / length * scale, z / length * scale)
+        (x, y, z)
     }
 }
 
-impl <const S: usize> SpherePoint for CubeSpherePoint<S> {
-    fn from_geographic(latitude: f64, longitude: f64) -> Self {
-        let y = latitude.sin();
+impl CellPoint for DynRectangleSpherePoint {
+    fn corners(&self, scale: f64) -> [(f64, f64, f64); 4] {
+        DynRectangleSpherePoint::corners(self, scale)
+    }
+}
 
-        let radius = latitude.cos();
+impl SpherePoint for DynRectangleSpherePoint {
+    fn at_geographic(&self, latitude: f64, longitude: f64) -> Self {
+        Self::from_geographic(latitude, longitude, self.width, self.height)
+    }
 
-        let x = radius * longitude.sin();
-        let z = radius * longitude.cos();
+    fn latitude(&self) -> f64 {
+        -(self.y as f64 / self.height as f64 * PI - PI / 2.0)
+    }
 
-        let longitude = longitude.rem_euclid(2.0 * PI);
+    fn longitude(&self) -> f64 {
+        self.x as f64 / self.width as f64 * PI * 2.0
+    }
+}
 
-        let face = if y > 0.0 {
-            let scale = S as f64 / y;
+/// A projection deciding how the cells on each face of a cube sphere grid are spaced.
+pub trait CubeProjection: Debug + Clone + Copy + PartialEq + Eq + Hash + Default + Send + Sync {
+    /// Maps a position across a face, from -1 at one edge to 1 at the other, onto the plane
+    /// touching the sphere at the centre of the face.
+    ///
+    /// - `position` - The position across the face.
+    fn to_plane(position: f64) -> f64;
 
-            let z = z * scale;
-            let x = x * scale;
-            
-            let x2 = (x + S as f64) / 2.0;
-            let y2 = (z + S as f64) / 2.0;
+    /// Maps a coordinate on the plane touching the sphere at the centre of a face back to a
+    /// position across the face.
+    /// This is the inverse of `to_plane`.
+    ///
+    /// - `plane` - The coordinate on the plane.
+    fn from_plane(plane: f64) -> f64;
+}
 
-            if (x2 as i32) >= 0 && (x2 as i32) < (S as i32) && (y2 as i32) > 0 && (y2 as i32) < (S as i32) {
-                return CubeSpherePoint::new(CubeFace::Top, x2 as u16, y2 as u16);
-            }
-                
-            if longitude > PI / 4.0 + 3.0 * PI / 2.0 {
-                CubeFace::Front
-            } else if longitude > PI / 4.0 + 2.0 * PI / 2.0 {
-                CubeFace::Left
-            } else if longitude > PI / 4.0 + PI / 2.0 {
-                CubeFace::Back
-            } else if longitude > PI / 4.0 {
-                CubeFace::Right
-            } else {
-                CubeFace::Front
-            }
-        } else {
-            let scale = -(S as f64) / y;
+/// Spaces cells evenly across the flat faces of the cube, so cells near the corners of each face
+/// cover a smaller part of the sphere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Gnomonic;
 
-            let z = z * scale;
-            let x = x * scale;
-            
-            let x2 = (x + S as f64) / 2.0;
-            let y2 = (S as f64 - z) / 2.0;
+impl CubeProjection for Gnomonic {
+    fn to_plane(position: f64) -> f64 {
+        position
+    }
 
-            if (x2 as i32) >= 0 && (x2 as i32) < (S as i32) && (y2 as i32) > 0 && (y2 as i32) < (S as i32) {
-                return CubeSpherePoint::new(CubeFace::Bottom, x2 as u16, y2 as u16);
-            }
-            if longitude > PI / 4.0 + 3.0 * PI / 2.0 {
-                CubeFace::Front
-            } else if longitude > PI / 4.0 + 2.0 * PI / 2.0 {
-                CubeFace::Left
-            } else if longitude > PI / 4.0 + PI / 2.0 {
-                CubeFace::Back
-            } else if longitude > PI / 4.0 {
-                CubeFace::Right
-            } else {
-                CubeFace::Front
-            }
-        };
+    fn from_plane(plane: f64) -> f64 {
+        plane
+    }
+}
 
-        match face {
-            CubeFace::Front => {
-                let scale = S as f64 / z;
+/// Spaces cells by equal angle from the centre of the sphere, so cells cover a much more uniform
+/// part of the sphere.
+/// This is the layout used by most cubed sphere atmospheric models.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Equiangular;
 
-                let x2 = (x * scale + S as f64) / 2.0;
-                let y2 = (y * scale + S as f64) / 2.0;
+impl CubeProjection for Equiangular {
+    fn to_plane(position: f64) -> f64 {
+        (position * PI / 4.0).tan()
+    }
 
-                CubeSpherePoint::new(CubeFace::Front, x2 as u16, y2 as u16)
-            },
-            CubeFace::Back => {
-                let scale = -(S as f64) / z;
-
-                let x = x * scale;
-                let y = y * scale;
-                
-                let x2 = (x + S as f64) / 2.0;
-                let y2 = (y - S as f64) / -2.0;
-                
-                CubeSpherePoint::new(CubeFace::Back, x2 as u16, y2 as u16)
-            },
-            CubeFace::Left => {
-                let scale = -(S as f64) / x;
+    fn from_plane(plane: f64) -> f64 {
+        plane.atan() * 4.0 / PI
+    }
+}
 
-                let z = z * scale;
-                let y = y * scale;
+/// A grid that wraps a cube around a sphere in order to determine grid positions.
+///
+/// # Type Parameters.
+/// - `T` - The type of element stored in each grid cell.
+/// - `P` - The projection used to space the cells on each face.
+/// - `D` - The storage holding the data.
+///
+/// # Constant Parameters
+/// - `S` - The size of each side of each face.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CubeSphereGrid<T, const S: usize, P: CubeProjection = Gnomonic, D: Storage<T> = Vec<T>> {
+    /// The data held in this grid stored face by face and then column by column.
+    data: D,
+    /// The type of data that the grid holds.
+    value: PhantomData<T>,
+    /// The projection used to space the cells.
+    projection: PhantomData<P>,
+}
 
-                let x2 = (z + S as f64) / 2.0;
-                let y2 = (y + S as f64) / 2.0;
-                
-                CubeSpherePoint::new(CubeFace::Left, x2 as u16, y2 as u16)
-            },
-            CubeFace::Right => {
-                let scale = S as f64 / x;
-
-                let z = z * scale;
-                let y = y * scale;
-                
-                let x2 = (S as f64 - z) / 2.0;
-                let y2 = (y + S as f64) / 2.0;
-                
-                CubeSpherePoint::new(CubeFace::Right, x2 as u16, y2 as u16)
-            },
-            CubeFace::Top => {
-                let scale = S as f64 / y;
-
-                let z = z * scale;
-                let x = x * scale;
-                
-                let x2 = (x + S as f64) / 2.0;
-                let y2 = (z + S as f64) / 2.0;
-                
-                CubeSpherePoint::new(CubeFace::Top, x2 as u16, y2 as u16)
-            },
-            CubeFace::Bottom => {
-                let scale = -(S as f64) / y;
-
-                let z = z * scale;
-                let x = x * scale;
-                
-                let x2 = (x + S as f64) / 2.0;
-                let y2 = (S as f64 - z) / 2.0;
-                
-                CubeSpherePoint::new(CubeFace::Bottom, x2 as u16, y2 as u16)
-            },
+/// A `CubeSphereGrid` with cells spaced by equal angle.
+pub type EquiangularCubeSphereGrid<T, const S: usize> = CubeSphereGrid<T, S, Equiangular>;
+
+impl <T: Debug, const S: usize, P: CubeProjection, D: Storage<T>> SurfaceGrid<T> for CubeSphereGrid<T, S, P, D> {
+    type Point = CubeSpherePoint<S, P>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        for (i, value) in self.data.iter_mut().enumerate() {
+            *value = f(&CubeSpherePoint::from_index(i));
+        }
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        self.data.par_iter_mut().enumerate().for_each(|(i, value)| {
+            *value = f(&CubeSpherePoint::from_index(i));
+        })
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        self.data.iter()
+            .enumerate()
+            .map(|(i, value)| (CubeSpherePoint::from_index(i), value))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        self.data.par_iter()
+            .enumerate()
+            .map(|(i, value)| (CubeSpherePoint::from_index(i), value))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        CUBE_FACES.into_iter()
+            .cartesian_product(0..S)
+            .cartesian_product(0..S)
+            .map(|((face, x), y)| CubeSpherePoint::new(face, x as u16, y as u16))
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        (0..6 * S * S).into_par_iter()
+            .map(CubeSpherePoint::from_index)
+    }
+}
+
+impl <const S: usize, P: CubeProjection> CubeSphereGrid<f64, S, P> {
+    /// Creates a grid holding the area of each cell on the surface of a sphere.
+    ///
+    /// - `radius` - The radius of the sphere.
+    pub fn areas(radius: f64) -> Self {
+        Self::from_fn(|point| point.area(radius))
+    }
+}
+
+impl <T: Debug + Default, const S: usize, P: CubeProjection, D: Storage<T>> Default for CubeSphereGrid<T, S, P, D> {
+    fn default() -> Self {
+        Self::from_fn(|_| T::default())
+    }
+}
+
+impl <T: Debug, const S: usize, P: CubeProjection, D: Storage<T>> StaticSurfaceGrid<T> for CubeSphereGrid<T, S, P, D> {
+    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
+        Self {
+            data: D::from_fn(6 * S * S, |i| f(&CubeSpherePoint::from_index(i))),
+            value: PhantomData,
+            projection: PhantomData,
+        }
+    }
+
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self {
+            data: D::from_fn_par(6 * S * S, |i| f(&CubeSpherePoint::from_index(i))),
+            value: PhantomData,
+            projection: PhantomData,
+        }
+    }
+}
+
+impl <T, const S: usize, P: CubeProjection, D: Storage<T>> Index<CubeSpherePoint<S, P>> for CubeSphereGrid<T, S, P, D> {
+    type Output = T;
+
+    fn index(&self, index: CubeSpherePoint<S, P>) -> &Self::Output {
+        &self.data[index.to_index()]
+    }
+}
+
+impl <T, const S: usize, P: CubeProjection, D: Storage<T>> IndexMut<CubeSpherePoint<S, P>> for CubeSphereGrid<T, S, P, D> {
+    fn index_mut(&mut self, index: CubeSpherePoint<S, P>) -> &mut Self::Output {
+        &mut self.data[index.to_index()]
+    }
+}
+
+impl <T, const S: usize, P: CubeProjection, D: Storage<T>> IntoIterator for CubeSphereGrid<T, S, P, D> {
+    type Item = (CubeSpherePoint<S, P>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let data: Vec<_> = self.data.into_vec()
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| (CubeSpherePoint::from_index(i), value))
+            .collect();
+
+        data.into_iter()
+    }
+}
+
+/// A point on a `CubeSphereGrid`.
+///
+/// # Type Parameters
+/// - `P` - The projection used to space the cells on each face.
+///
+/// # Constant Parameters
+/// - `S` - The size of each side of each face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CubeSpherePoint<const S: usize, P: CubeProjection = Gnomonic> {
+    face: CubeFace,
+    x: u16,
+    y: u16,
+    projection: PhantomData<P>,
+}
+
+impl <const S: usize, P: CubeProjection> CubeSpherePoint<S, P> {
+    /// Creates a new `CubeSpherePoint`.
+    ///
+    /// - `face` - The face on which the point lies.
+    /// - `x` - The X position on the face.
+    /// - `y` - The Y position on the face.
+    fn new(face: CubeFace, x: u16, y: u16) -> Self {
+        Self::from_dyn(DynCubeSpherePoint::new(face, x, y, S as u16))
+    }
+
+    /// Creates a new `CubeSpherePoint`.
+    /// Returns an error if the coordinates are outside the face.
+    ///
+    /// - `face` - The face on which the point lies.
+    /// - `x` - The X position on the face.
+    /// - `y` - The Y position on the face.
+    pub fn try_new(face: CubeFace, x: usize, y: usize) -> Result<Self, PointError> {
+        PointError::check(x, y, S, S)?;
+
+        Ok(Self::new(face, x as u16, y as u16))
+    }
+
+    /// Gets the face on which this point lies.
+    pub fn face(&self) -> CubeFace {
+        self.face
+    }
+
+    /// Gets the X position on the face.
+    pub fn x(&self) -> usize {
+        self.x as usize
+    }
+
+    /// Gets the Y position on the face.
+    pub fn y(&self) -> usize {
+        self.y as usize
+    }
+
+    /// Converts this point into the equivalent point on a `DynCubeSphereGrid`.
+    /// The neighbours of the point are the same but its position uses the gnomonic projection.
+    fn to_dyn(self) -> DynCubeSpherePoint {
+        DynCubeSpherePoint {
+            face: self.face,
+            x: self.x,
+            y: self.y,
+            size: S as u16,
+        }
+    }
+
+    /// Converts a point on a `DynCubeSphereGrid` with the same size into a point on this grid.
+    fn from_dyn(point: DynCubeSpherePoint) -> Self {
+        Self {
+            face: point.face,
+            x: point.x,
+            y: point.y,
+            projection: PhantomData,
+        }
+    }
+
+    /// Gets the area of the cell at this point on the surface of an ellipsoid.
+    ///
+    /// - `ellipsoid` - The ellipsoid that the grid is wrapped around.
+    pub fn ellipsoid_area(&self, ellipsoid: &Ellipsoid) -> f64 {
+        self.to_dyn().projected_ellipsoid_area::<P>(ellipsoid)
+    }
+
+    /// Gets the positions of the corners of the cell at this point in 3D space.
+    /// The corners are in clockwise order when viewed from outside the sphere starting from the
+    /// corner between the up and left neighbours.
+    ///
+    /// - `scale` - The radius of the sphere.
+    pub fn corners(&self, scale: f64) -> [(f64, f64, f64); 4] {
+        self.to_dyn().projected_corners::<P>(scale)
+    }
+
+    /// Gets the solid angle covered by the cell at this point in steradians.
+    pub fn solid_angle(&self) -> f64 {
+        self.to_dyn().projected_solid_angle::<P>()
+    }
+
+    /// Gets the area of the cell at this point on the surface of a sphere.
+    ///
+    /// - `radius` - The radius of the sphere.
+    pub fn area(&self, radius: f64) -> f64 {
+        self.solid_angle() * radius * radius
+    }
+}
+
+impl <const S: usize, P: CubeProjection> GridPoint for CubeSpherePoint<S, P> {
+    fn up(&self) -> Self {
+        Self::from_dyn(self.to_dyn().up())
+    }
+
+    fn down(&self) -> Self {
+        Self::from_dyn(self.to_dyn().down())
+    }
+
+    fn left(&self) -> Self {
+        Self::from_dyn(self.to_dyn().left())
+    }
+
+    fn right(&self) -> Self {
+        Self::from_dyn(self.to_dyn().right())
+    }
+
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        self.to_dyn().projected_position::<P>(scale)
+    }
+}
+
+impl <const S: usize, P: CubeProjection> CellPoint for CubeSpherePoint<S, P> {
+    fn corners(&self, scale: f64) -> [(f64, f64, f64); 4] {
+        CubeSpherePoint::corners(self, scale)
+    }
+}
+
+impl <const S: usize, P: CubeProjection> IndexedPoint for CubeSpherePoint<S, P> {
+    const COUNT: usize = 6 * S * S;
+
+    fn to_index(&self) -> usize {
+        let face = CUBE_FACES.iter()
+            .position(|face| *face == self.face)
+            .expect("All faces are stored");
+
+        // The points of a cube sphere grid go down each column of each face in turn.
+        (face * S + self.x as usize) * S + self.y as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        let face = CUBE_FACES[index / (S * S)];
+        let index = index % (S * S);
+
+        Self::new(face, (index / S) as u16, (index % S) as u16)
+    }
+}
+
+impl <const S: usize, P: CubeProjection> SpherePoint for CubeSpherePoint<S, P> {
+    fn at_geographic(&self, latitude: f64, longitude: f64) -> Self {
+        Self::from_geographic(latitude, longitude)
+    }
+
+    fn latitude(&self) -> f64 {
+        let (x, y, z) = self.position(1.0);
+
+        let distance = (x * x + z * z).sqrt();
+
+        (y / distance).atan()
+    }
+
+    fn longitude(&self) -> f64 {
+        let (x, _, z) = self.position(1.0);
+
+        x.atan2(z).rem_euclid(2.0 * PI)
+    }
+}
+
+impl <const S: usize, P: CubeProjection> StaticSpherePoint for CubeSpherePoint<S, P> {
+    fn from_geographic(latitude: f64, longitude: f64) -> Self {
+        Self::from_dyn(DynCubeSpherePoint::from_projected_geographic::<P>(latitude, longitude, S as u16))
+    }
+}
+
+/// The order in which the faces of a cube sphere grid are stored and iterated.
+const CUBE_FACES: [CubeFace; 6] = [
+    CubeFace::Top,
+    CubeFace::Left,
+    CubeFace::Front,
+    CubeFace::Right,
+    CubeFace::Back,
+    CubeFace::Bottom,
+];
+
+/// A grid that wraps a cube around a sphere with a size chosen at runtime.
+///
+/// This behaves in the same way as a `CubeSphereGrid` with the same size.
+///
+/// # Type Parameters
+/// - `T` - The type of element stored in each grid cell.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DynCubeSphereGrid<T> {
+    /// The size of each side of each face.
+    size: usize,
+    /// The data held in this grid stored face by face and then column by column.
+    data: Vec<T>,
+}
+
+impl <T> DynCubeSphereGrid<T> {
+    /// Creates a new grid filled with the default value of `T`.
+    ///
+    /// - `size` - The size of each side of each face.
+    pub fn new(size: usize) -> Self where T: Default {
+        Self::from_fn(size, |_| T::default())
+    }
+
+    /// Creates a new grid by calling the specified function for each point in the grid.
+    ///
+    /// - `size` - The size of each side of each face.
+    /// - `f` - The function to apply.
+    pub fn from_fn<F: FnMut(&DynCubeSpherePoint) -> T>(size: usize, mut f: F) -> Self {
+        assert!(size > 0, "A grid must have a size greater than zero");
+        assert!(size <= u16::MAX as usize, "A grid must fit within u16 coordinates");
+
+        Self {
+            size,
+            data: (0..6 * size * size)
+                .map(|i| f(&Self::point_at_sized(i, size)))
+                .collect(),
+        }
+    }
+
+    /// Creates a new grid by calling the specified function in parallel for each point in the
+    /// grid.
+    ///
+    /// - `size` - The size of each side of each face.
+    /// - `f` - The function to apply.
+    pub fn from_fn_par<F: Fn(&DynCubeSpherePoint) -> T + Send + Sync>(size: usize, f: F) -> Self where T: Send + Sync {
+        assert!(size > 0, "A grid must have a size greater than zero");
+        assert!(size <= u16::MAX as usize, "A grid must fit within u16 coordinates");
+
+        Self {
+            size,
+            data: (0..6 * size * size).into_par_iter()
+                .map(|i| f(&Self::point_at_sized(i, size)))
+                .collect(),
+        }
+    }
+
+    /// Gets the size of each side of each face of this grid.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Gets the point on this grid for the specified geographic coordinates.
+    ///
+    /// - `latitude` - The latitude of the point in radians where 0 is the equator.
+    /// - `longitude` - The longitude of the point in radians.
+    pub fn point_at_geographic(&self, latitude: f64, longitude: f64) -> DynCubeSpherePoint {
+        DynCubeSpherePoint::from_geographic(latitude, longitude, self.size as u16)
+    }
+
+    /// Gets the point at the specified index into the data of a grid of the specified size.
+    ///
+    /// - `i` - The index of the point.
+    /// - `size` - The size of each side of each face.
+    fn point_at_sized(i: usize, size: usize) -> DynCubeSpherePoint {
+        let face = CUBE_FACES[i / (size * size)];
+        let i = i % (size * size);
+
+        DynCubeSpherePoint::new(face, (i / size) as u16, (i % size) as u16, size as u16)
+    }
+
+    /// Gets the index into the data of the specified point.
+    ///
+    /// - `point` - The point to get the index of.
+    fn index_of(&self, point: &DynCubeSpherePoint) -> usize {
+        assert!(point.size as usize == self.size, "The point does not belong to a grid with the same size");
+
+        let face = CUBE_FACES.iter()
+            .position(|face| *face == point.face)
+            .expect("All faces are stored");
+
+        (face * self.size + point.x as usize) * self.size + point.y as usize
+    }
+}
+
+impl DynCubeSphereGrid<f64> {
+    /// Creates a grid holding the area of each cell on the surface of a sphere.
+    ///
+    /// - `size` - The size of each side of each face.
+    /// - `radius` - The radius of the sphere.
+    pub fn areas(size: usize, radius: f64) -> Self {
+        Self::from_fn(size, |point| point.area(radius))
+    }
+}
+
+impl <T> SurfaceGrid<T> for DynCubeSphereGrid<T> {
+    type Point = DynCubeSpherePoint;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(self.size, f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(self.size, f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        for point in self.points().collect::<Vec<_>>() {
+            self[point] = f(&point);
+        }
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        let size = self.size;
+
+        self.data.par_iter_mut().enumerate().for_each(|(i, value)| {
+            *value = f(&Self::point_at_sized(i, size));
+        })
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        self.points()
+            .map(|point| (point, &self[point]))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        self.par_points()
+            .map(|point| (point, &self[point]))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        let size = self.size;
+
+        CUBE_FACES.into_iter()
+            .cartesian_product(0..size)
+            .cartesian_product(0..size)
+            .map(move |((face, x), y)| DynCubeSpherePoint::new(face, x as u16, y as u16, size as u16))
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        self.points()
+            .par_bridge()
+    }
+}
+
+impl <T> Index<DynCubeSpherePoint> for DynCubeSphereGrid<T> {
+    type Output = T;
+
+    fn index(&self, index: DynCubeSpherePoint) -> &Self::Output {
+        &self.data[self.index_of(&index)]
+    }
+}
+
+impl <T> IndexMut<DynCubeSpherePoint> for DynCubeSphereGrid<T> {
+    fn index_mut(&mut self, index: DynCubeSpherePoint) -> &mut Self::Output {
+        let i = self.index_of(&index);
+
+        &mut self.data[i]
+    }
+}
+
+impl <T> IntoIterator for DynCubeSphereGrid<T> {
+    type Item = (DynCubeSpherePoint, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let size = self.size;
+
+        let data: Vec<_> = self.data.into_iter()
+            .enumerate()
+            .map(|(i, value)| (Self::point_at_sized(i, size), value))
+            .collect();
+
+        data.into_iter()
+    }
+}
+
+/// A point on a `DynCubeSphereGrid`.
+///
+/// The point stores the size of the grid that it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DynCubeSpherePoint {
+    face: CubeFace,
+    x: u16,
+    y: u16,
+    size: u16,
+}
+
+impl DynCubeSpherePoint {
+    /// Creates a new `DynCubeSpherePoint`.
+    ///
+    /// - `face` - The face on which the point lies.
+    /// - `x` - The X position on the face.
+    /// - `y` - The Y position on the face.
+    /// - `size` - The size of each side of each face.
+    fn new(face: CubeFace, x: u16, y: u16, size: u16) -> Self {
+        Self {
+            face,
+            // Clamp to account for floating point rounding error.
+            x: x.clamp(0, size - 1),
+            y: y.clamp(0, size - 1),
+            size,
+        }
+    }
+
+    /// Gets the point for the specified geographic coordinates on a grid of the specified size.
+    ///
+    /// - `latitude` - The latitude of the point in radians where 0 is the equator.
+    /// - `longitude` - The longitude of the point in radians.
+    /// - `size` - The size of each side of each face.
+    fn from_geographic(latitude: f64, longitude: f64, size: u16) -> Self {
+        Self::from_projected_geographic::<Gnomonic>(latitude, longitude, size)
+    }
+
+    /// Gets the point for the specified geographic coordinates on a grid of the specified size
+    /// with cells spaced by the specified projection.
+    ///
+    /// - `latitude` - The latitude of the point in radians where 0 is the equator.
+    /// - `longitude` - The longitude of the point in radians.
+    /// - `size` - The size of each side of each face.
+    fn from_projected_geographic<P: CubeProjection>(latitude: f64, longitude: f64, size: u16) -> Self {
+        let y = latitude.sin();
+
+        let radius = latitude.cos();
+
+        let x = radius * longitude.sin();
+        let z = radius * longitude.cos();
+
+        // The face is the one facing the largest component of the position.
+        // Each face is then projected onto the plane touching the sphere at its centre.
+        let (face, u, v) = if y.abs() >= x.abs() && y.abs() >= z.abs() {
+            if y > 0.0 {
+                (CubeFace::Top, x / y, z / y)
+            } else {
+                (CubeFace::Bottom, -x / y, z / y)
+            }
+        } else if z.abs() >= x.abs() {
+            if z > 0.0 {
+                (CubeFace::Front, x / z, -y / z)
+            } else {
+                (CubeFace::Back, -x / z, -y / z)
+            }
+        } else if x > 0.0 {
+            (CubeFace::Right, -z / x, -y / x)
+        } else {
+            (CubeFace::Left, -z / x, y / x)
+        };
+
+        let cell = |plane: f64| ((P::from_plane(plane) * size as f64 + size as f64) / 2.0).floor().max(0.0) as u16;
+
+        Self::new(face, cell(u), cell(v), size)
+    }
+
+    /// Gets the position of this point when the cells are spaced by the specified projection.
+    ///
+    /// - `scale` - The radius of the sphere.
+    fn projected_position<P: CubeProjection>(&self, scale: f64) -> (f64, f64, f64) {
+        // Use the centre of the cell.
+        let (x, y, z) = self.face_position::<P>(self.x as f64 + 0.5, self.y as f64 + 0.5);
+
+        (x * scale, y * scale, z * scale)
+    }
+
+    /// Gets the position on the unit sphere of a location on the face of this point in cell
+    /// units when the cells are spaced by the specified projection.
+    ///
+    /// - `x` - The X position on the face from 0 to the size of the face.
+    /// - `y` - The Y position on the face from 0 to the size of the face.
+    fn face_position<P: CubeProjection>(&self, x: f64, y: f64) -> (f64, f64, f64) {
+        let size = self.size as f64;
+
+        let u = P::to_plane(x * 2.0 / size - 1.0);
+        let v = P::to_plane(y * 2.0 / size - 1.0);
+
+        let (x, y, z) = match self.face {
+            CubeFace::Front => (u, -v, 1.0),
+            CubeFace::Back => (u, v, -1.0),
+            CubeFace::Left => (-1.0, -v, u),
+            CubeFace::Right => (1.0, -v, -u),
+            CubeFace::Top => (u, 1.0, v),
+            CubeFace::Bottom => (u, -1.0, -v),
+        };
+
+        let length = (x * x + y * y + z * z).sqrt();
+
+        (x / length, y / length, z / length)
+    }
+
+    /// Gets the area of the cell at this point on the surface of an ellipsoid when the cells are
+    /// spaced by the specified projection.
+    ///
+    /// - `ellipsoid` - The ellipsoid that the grid is wrapped around.
+    fn projected_ellipsoid_area<P: CubeProjection>(&self, ellipsoid: &Ellipsoid) -> f64 {
+        // The edges of a cell curve on the ellipsoid so the cell is split into smaller cells.
+        const DIVISIONS: usize = 4;
+
+        let corner = |i: usize, j: usize| {
+            let (x, y, z) = self.face_position::<P>(
+                self.x as f64 + i as f64 / DIVISIONS as f64,
+                self.y as f64 + j as f64 / DIVISIONS as f64,
+            );
+
+            (y.atan2((x * x + z * z).sqrt()), x.atan2(z))
+        };
+
+        (0..DIVISIONS).cartesian_product(0..DIVISIONS)
+            .map(|(i, j)| ellipsoid.polygon_area(&[
+                corner(i, j),
+                corner(i + 1, j),
+                corner(i + 1, j + 1),
+                corner(i, j + 1),
+            ]))
+            .sum()
+    }
+
+    /// Gets the positions of the corners of the cell at this point when the cells are spaced by
+    /// the specified projection.
+    ///
+    /// - `scale` - The radius of the sphere.
+    fn projected_corners<P: CubeProjection>(&self, scale: f64) -> [(f64, f64, f64); 4] {
+        let (x, y) = (self.x as f64, self.y as f64);
+
+        // The directions on the back face are mirrored so left is at the higher X position.
+        let corners = if self.face == CubeFace::Back {
+            [(x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0), (x, y)]
+        } else {
+            [(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)]
+        };
+
+        corners.map(|(x, y)| {
+            let (x, y, z) = self.face_position::<P>(x, y);
+
+            (x * scale, y * scale, z * scale)
+        })
+    }
+
+    /// Gets the solid angle covered by the cell at this point when the cells are spaced by the
+    /// specified projection.
+    fn projected_solid_angle<P: CubeProjection>(&self) -> f64 {
+        let size = self.size as f64;
+
+        let plane = |position: u16| P::to_plane(position as f64 * 2.0 / size - 1.0);
+
+        // The solid angle of the rectangle on the plane touching the face between the origin and
+        // a corner.
+        let corner = |u: f64, v: f64| (u * v / (1.0 + u * u + v * v).sqrt()).atan();
+
+        let (left, right) = (plane(self.x), plane(self.x + 1));
+        let (top, bottom) = (plane(self.y), plane(self.y + 1));
+
+        corner(right, bottom) - corner(left, bottom) - corner(right, top) + corner(left, top)
+    }
+
+    /// Creates a new `DynCubeSpherePoint`.
+    /// Returns an error if the coordinates are outside the face.
+    ///
+    /// - `face` - The face on which the point lies.
+    /// - `x` - The X position on the face.
+    /// - `y` - The Y position on the face.
+    /// - `size` - The size of each side of each face.
+    pub fn try_new(face: CubeFace, x: usize, y: usize, size: usize) -> Result<Self, PointError> {
+        PointError::check(x, y, size, size)?;
+
+        Ok(Self::new(face, x as u16, y as u16, size as u16))
+    }
+
+    /// Gets the face on which this point lies.
+    pub fn face(&self) -> CubeFace {
+        self.face
+    }
+
+    /// Gets the X position on the face.
+    pub fn x(&self) -> usize {
+        self.x as usize
+    }
+
+    /// Gets the Y position on the face.
+    pub fn y(&self) -> usize {
+        self.y as usize
+    }
+
+    /// Gets the size of each side of each face of the grid that this point belongs to.
+    pub fn size(&self) -> usize {
+        self.size as usize
+    }
+
+    /// Gets the area of the cell at this point on the surface of an ellipsoid.
+    ///
+    /// - `ellipsoid` - The ellipsoid that the grid is wrapped around.
+    pub fn ellipsoid_area(&self, ellipsoid: &Ellipsoid) -> f64 {
+        self.projected_ellipsoid_area::<Gnomonic>(ellipsoid)
+    }
+
+    /// Gets the positions of the corners of the cell at this point in 3D space.
+    /// The corners are in clockwise order when viewed from outside the sphere starting from the
+    /// corner between the up and left neighbours.
+    ///
+    /// - `scale` - The radius of the sphere.
+    pub fn corners(&self, scale: f64) -> [(f64, f64, f64); 4] {
+        self.projected_corners::<Gnomonic>(scale)
+    }
+
+    /// Gets the solid angle covered by the cell at this point in steradians.
+    pub fn solid_angle(&self) -> f64 {
+        self.projected_solid_angle::<Gnomonic>()
+    }
+
+    /// Gets the area of the cell at this point on the surface of a sphere.
+    ///
+    /// - `radius` - The radius of the sphere.
+    pub fn area(&self, radius: f64) -> f64 {
+        self.solid_angle() * radius * radius
+    }
+}
+
+impl GridPoint for DynCubeSpherePoint {
+    fn up(&self) -> Self {
+        match self.face {
+            CubeFace::Front => if self.y == 0 {
+                Self {
+                    face: CubeFace::Top,
+                    x: self.x,
+                    y: self.size - 1,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Front,
+                    x: self.x,
+                    y: self.y - 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Back => if self.y == 0 {
+                Self {
+                    face: CubeFace::Bottom,
+                    x: self.x,
+                    y: self.size - 1,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Back,
+                    x: self.x,
+                    y: self.y - 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Left => if self.y == 0 {
+                Self {
+                    face: CubeFace::Top,
+                    x: 0,
+                    y: self.x,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Left,
+                    x: self.x,
+                    y: self.y - 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Right => if self.y == 0 {
+                Self {
+                    face: CubeFace::Top,
+                    x: self.size - 1,
+                    y: self.size - 1 - self.x,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Right,
+                    x: self.x,
+                    y: self.y - 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Top => if self.y == 0 {
+                Self {
+                    face: CubeFace::Back,
+                    x: self.x,
+                    y: self.size - 1,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Top,
+                    x: self.x,
+                    y: self.y - 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Bottom => if self.y == 0 {
+                Self {
+                    face: CubeFace::Front,
+                    x: self.x,
+                    y: self.size - 1,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Bottom,
+                    x: self.x,
+                    y: self.y - 1,
+                    size: self.size,
+                }
+            },
+        }
+    }
+
+    fn down(&self) -> Self {
+        match self.face {
+            CubeFace::Front => if self.y == self.size - 1 {
+                Self {
+                    face: CubeFace::Bottom,
+                    x: self.x,
+                    y: 0,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Front,
+                    x: self.x,
+                    y: self.y + 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Back => if self.y == self.size - 1 {
+                Self {
+                    face: CubeFace::Top,
+                    x: self.x,
+                    y: 0,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Back,
+                    x: self.x,
+                    y: self.y + 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Left => if self.y == self.size - 1 {
+                Self {
+                    face: CubeFace::Bottom,
+                    x: 0,
+                    y: self.size - 1 - self.x,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Left,
+                    x: self.x,
+                    y: self.y + 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Right => if self.y == self.size - 1 {
+                Self {
+                    face: CubeFace::Bottom,
+                    x: self.size - 1,
+                    y: self.x,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Right,
+                    x: self.x,
+                    y: self.y + 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Top => if self.y == self.size - 1 {
+                Self {
+                    face: CubeFace::Front,
+                    x: self.x,
+                    y: 0,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Top,
+                    x: self.x,
+                    y: self.y + 1,
+                    size: self.size,
+                }
+            },
+            CubeFace::Bottom => if self.y == self.size - 1 {
+                Self {
+                    face: CubeFace::Back,
+                    x: self.x,
+                    y: 0,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Bottom,
+                    x: self.x,
+                    y: self.y + 1,
+                    size: self.size,
+                }
+            },
+        }
+    }
+
+    fn left(&self) -> Self {
+        match self.face {
+            CubeFace::Front => if self.x == 0 {
+                Self {
+                    face: CubeFace::Left,
+                    x: self.size - 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Front,
+                    x: self.x - 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Back => if self.x == self.size - 1 {
+                Self {
+                    face: CubeFace::Right,
+                    x: self.size - 1,
+                    y: self.size - 1 - self.y,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Back,
+                    x: self.x + 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Left => if self.x == 0 {
+                Self {
+                    face: CubeFace::Back,
+                    x: 0,
+                    y: self.size - 1 - self.y,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Left,
+                    x: self.x - 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Right => if self.x == 0 {
+                Self {
+                    face: CubeFace::Front,
+                    x: self.size - 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Right,
+                    x: self.x - 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Top => if self.x == 0 {
+                Self {
+                    face: CubeFace::Left,
+                    x: self.y,
+                    y: 0,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Top,
+                    x: self.x - 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Bottom => if self.x == 0 {
+                Self {
+                    face: CubeFace::Left,
+                    x: self.size - 1 - self.y,
+                    y: self.size - 1,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Bottom,
+                    x: self.x - 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+        }
+    }
+
+    fn right(&self) -> Self {
+        match self.face {
+            CubeFace::Front => if self.x == self.size - 1 {
+                Self {
+                    face: CubeFace::Right,
+                    x: 0,
+                    y: self.y,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Front,
+                    x: self.x + 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Back => if self.x == 0 {
+                Self {
+                    face: CubeFace::Left,
+                    x: 0,
+                    y: self.size - 1 - self.y,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Back,
+                    x: self.x - 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Left => if self.x == self.size - 1 {
+                Self {
+                    face: CubeFace::Front,
+                    x: 0,
+                    y: self.y,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Left,
+                    x: self.x + 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Right => if self.x == self.size - 1 {
+                Self {
+                    face: CubeFace::Back,
+                    x: self.size - 1,
+                    y: self.size - 1 - self.y,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Right,
+                    x: self.x + 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Top => if self.x == self.size - 1{
+                Self {
+                    face: CubeFace::Right,
+                    x: self.size - 1 - self.y,
+                    y: 0,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Top,
+                    x: self.x + 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+            CubeFace::Bottom => if self.x == self.size - 1 {
+                Self {
+                    face: CubeFace::Right,
+                    x: self.y,
+                    y: self.size - 1,
+                    size: self.size,
+                }
+            } else {
+                Self {
+                    face: CubeFace::Bottom,
+                    x: self.x + 1,
+                    y: self.y,
+                    size: self.size,
+                }
+            },
+        }
+    }
+
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        self.projected_position::<Gnomonic>(scale)
+    }
+}
+
+impl CellPoint for DynCubeSpherePoint {
+    fn corners(&self, scale: f64) -> [(f64, f64, f64); 4] {
+        DynCubeSpherePoint::corners(self, scale)
+    }
+}
+
+impl SpherePoint for DynCubeSpherePoint {
+    fn at_geographic(&self, latitude: f64, longitude: f64) -> Self {
+        Self::from_geographic(latitude, longitude, self.size)
+    }
+
+    fn latitude(&self) -> f64 {
+        let (x, y, z) = self.position(1.0);
+
+        let distance = (x * x + z * z).sqrt();
+
+        (y / distance).atan()
+    }
+
+    fn longitude(&self) -> f64 {
+        let (x, _, z) = self.position(1.0);
+
+        x.atan2(z).rem_euclid(2.0 * PI)
+    }
+}
+
+/// A face of a cube.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u32)] // For better alignment.
+pub enum CubeFace {
+    /// The face centred on the equator at longitude 0.
+    Front,
+    /// The face centred on the equator at longitude π.
+    Back,
+    /// The face centred on the equator at longitude 3π/2.
+    Left,
+    /// The face centred on the equator at longitude π/2.
+    Right,
+    /// The face centred on the north pole.
+    Top,
+    /// The face centred on the south pole.
+    Bottom,
+}
+
+/// An error from creating a point with coordinates outside its grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PointError {
+    /// The X coordinate is not less than the width of the grid.
+    XOutOfRange {
+        /// The X coordinate.
+        x: usize,
+        /// The width of the grid.
+        width: usize,
+    },
+    /// The Y coordinate is not less than the height of the grid.
+    YOutOfRange {
+        /// The Y coordinate.
+        y: usize,
+        /// The height of the grid.
+        height: usize,
+    },
+}
+
+impl PointError {
+    /// Checks that a pair of coordinates lie within a grid.
+    ///
+    /// - `x` - The X coordinate.
+    /// - `y` - The Y coordinate.
+    /// - `width` - The width of the grid.
+    /// - `height` - The height of the grid.
+    fn check(x: usize, y: usize, width: usize, height: usize) -> Result<(), Self> {
+        if x >= width {
+            Err(Self::XOutOfRange { x, width })
+        } else if y >= height {
+            Err(Self::YOutOfRange { y, height })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Display for PointError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::XOutOfRange { x, width } => write!(f, "X coordinate {} is outside a grid of width {}", x, width),
+            Self::YOutOfRange { y, height } => write!(f, "Y coordinate {} is outside a grid of height {}", y, height),
+        }
+    }
+}
+
+impl Error for PointError {}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, f64::consts::PI, hint::black_box};
+
+    use approx::assert_relative_eq;
+
+    use crate::{test::assert_indexed_points, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid, sphere::{CubeSpherePoint, CubeFace, CubeSphereGrid, OctaSphereGrid}};
+
+    use super::{RectangleSpherePoint, SpherePoint, StaticSpherePoint, RectangleSphereGrid, DynRectangleSphereGrid, DynRectangleSpherePoint, DynCubeSphereGrid, DynCubeSpherePoint, CubeProjection, Ellipsoid, Equiangular, EquiangularCubeSphereGrid, Gnomonic, PointError};
+
+    #[test]
+    fn test_rect_point_up_middle() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(3, 4);
+
+        assert_eq!(RectangleSpherePoint::new(3, 3), point.up());
+    }
+    
+    #[test]
+    fn test_rect_point_up_top_left() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 0);
+
+        assert_eq!(RectangleSpherePoint::new(5, 0), point.up());
+    }
+    
+    #[test]
+    fn test_rect_point_up_top_right() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 0);
+
+        assert_eq!(RectangleSpherePoint::new(9, 1), point.up());
+    }
+    
+    #[test]
+    fn test_rect_point_up_bottom_left() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 9);
+
+        assert_eq!(RectangleSpherePoint::new(0, 8), point.up());
+    }
+    
+    #[test]
+    fn test_rect_point_up_bottom_right() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 9);
+
+        assert_eq!(RectangleSpherePoint::new(4, 9), point.up());
+    }
+    
+    #[test]
+    fn test_rect_point_down_middle() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(3, 4);
+
+        assert_eq!(RectangleSpherePoint::new(3, 5), point.down());
+    }
+    
+    #[test]
+    fn test_rect_point_down_top_left() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 0);
+
+        assert_eq!(RectangleSpherePoint::new(0, 1), point.down());
+    }
+    
+    #[test]
+    fn test_rect_point_down_top_right() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 0);
+
+        assert_eq!(RectangleSpherePoint::new(4, 0), point.down());
+    }
+    
+    #[test]
+    fn test_rect_point_down_bottom_left() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 9);
+
+        assert_eq!(RectangleSpherePoint::new(5, 9), point.down());
+    }
+    
+    #[test]
+    fn test_rect_point_down_bottom_right() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 9);
+
+        assert_eq!(RectangleSpherePoint::new(9, 8), point.down());
+    }
+
+    #[test]
+    fn test_rect_point_left_middle() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(5, 5);
+
+        assert_eq!(RectangleSpherePoint::new(4, 5), point.left());
+    }
+    
+    #[test]
+    fn test_rect_point_left_left() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 5);
+
+        assert_eq!(RectangleSpherePoint::new(9, 5), point.left());
+    }
+   
+    #[test]
+    fn test_rect_point_left_right() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 5);
+
+        assert_eq!(RectangleSpherePoint::new(8, 5), point.left());
+    }
+
+    #[test]
+    fn test_rect_point_right_middle() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(5, 5);
+
+        assert_eq!(RectangleSpherePoint::new(6, 5), point.right());
+    }
+    
+    #[test]
+    fn test_rect_point_right_left() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 5);
+
+        assert_eq!(RectangleSpherePoint::new(1, 5), point.right());
+    }
+   
+    #[test]
+    fn test_rect_point_right_right() {
+        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 5);
+
+        assert_eq!(RectangleSpherePoint::new(0, 5), point.right());
+    }
+
+    #[test]
+    fn test_rect_point_from_geographic_equator() {
+        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(0.0, PI);
+
+        assert_eq!(RectangleSpherePoint::new(50, 50), point);
+    }
+    
+    #[test]
+    fn test_rect_point_from_geographic_north_pole() {
+        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(PI / 2.0, PI);
+
+        assert_eq!(RectangleSpherePoint::new(50, 0), point);
+    }
+    
+    #[test]
+    fn test_rect_point_from_geographic_south_pole() {
+        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(-PI / 2.0, PI);
+
+        assert_eq!(RectangleSpherePoint::new(50, 99), point);
+    }
+    
+    #[test]
+    fn test_rect_point_from_geographic_equator_wrap_north() {
+        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(-PI, PI);
+
+        assert_eq!(RectangleSpherePoint::new(50, 50), point);
+    }
+    
+    #[test]
+    fn test_rect_point_from_geographic_equator_wrap_south() {
+        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(PI, PI);
+
+        assert_eq!(RectangleSpherePoint::new(50, 50), point);
+    }
+    
+    #[test]
+    fn test_rect_point_from_geographic_east() {
+        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(0.0, PI * 2.0);
+
+        assert_eq!(RectangleSpherePoint::new(0, 50), point);
+    }
+    
+    #[test]
+    fn test_rect_point_from_geographic_west() {
+        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(0.0, 0.0);
+
+        assert_eq!(RectangleSpherePoint::new(0, 50), point);
+    }
+
+    #[test]
+    fn test_rect_point_up_loop() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 3);
+
+        assert_eq!(start, start.up().up().up().up().up().up().up().up().up().up());
+    }
+    
+    #[test]
+    fn test_rect_point_down_loop() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+
+        assert_eq!(start, start.down().down().down().down().down().down().down().down().down().down());
+    }
+    
+    #[test]
+    fn test_rect_point_left_loop() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 3);
+
+        assert_eq!(start, start.left().left().left().left().left().left().left().left().left().left());
+    }
+    
+    #[test]
+    fn test_rect_point_right_loop() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 3);
+
+        assert_eq!(start, start.right().right().right().right().right().right().right().right().right().right());
+    }
+
+    #[test]
+    fn test_rect_point_up_inverse_middle() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+
+        assert_eq!(start, start.up().down());
+    }
+    
+    #[test]
+    fn test_rect_point_down_inverse_middle() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+
+        assert_eq!(start, start.down().up());
+    }
+    
+    #[test]
+    fn test_rect_point_left_inverse_middle() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+
+        assert_eq!(start, start.left().right());
+    }
+    
+    #[test]
+    fn test_rect_point_right_inverse_middle() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+
+        assert_eq!(start, start.right().left());
+    }
+    
+    #[test]
+    fn test_rect_point_up_inverse_edge() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 0);
+
+        assert_eq!(start, start.up().down());
+    }
+    
+    #[test]
+    fn test_rect_point_down_inverse_edge() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 4);
+
+        assert_eq!(start, start.down().up());
+    }
+    
+    #[test]
+    fn test_rect_point_left_inverse_edge() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 0);
+
+        assert_eq!(start, start.left().right());
+    }
+    
+    #[test]
+    fn test_rect_point_right_inverse_edge() {
+        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(9, 0);
+
+        assert_eq!(start, start.right().left());
+    }
+
+    #[test]
+    fn test_rect_from_fn() {
+        let grid: RectangleSphereGrid<u32, 200, 100> = RectangleSphereGrid::from_fn(|point| point.x + point.y);
+
+        assert_eq!(15, grid[RectangleSpherePoint::new(5, 10)]);
+    }
+
+    #[test]
+    fn test_rect_from_neighbours() {
+        let grid: RectangleSphereGrid<u32, 20, 10> = RectangleSphereGrid::from_fn(|point| point.x);
+
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert_eq!(25, grid2[RectangleSpherePoint::new(5, 3)])
+    }
+    
+    #[test]
+    fn test_rect_from_neighbours_diagonals() {
+        let grid: RectangleSphereGrid<u32, 20, 10> = RectangleSphereGrid::from_fn(|point| point.x);
+
+        let grid2 = grid.map_neighbours_diagonals(|up_left, up, up_right, left, current, right, down_left, down, down_right| up_left + up + up_right + left + current + right + down_left + down + down_right);
+
+        assert_eq!(4 * 3 + 5 * 3 + 6 * 3, grid2[RectangleSpherePoint::new(5, 3)])
+    }
+
+    #[test]
+    fn test_rect_point_latitude_0() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 0.0);
+
+        assert_relative_eq!(0.0, point.latitude());
+    }
+    
+    #[test]
+    fn test_rect_point_latitude_1() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.0, 0.0);
+
+        assert_relative_eq!(1.0, point.latitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_latitude_minus_1() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.0, 0.0);
+
+        assert_relative_eq!(-1.0, point.latitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_latitude_0_far() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 4.0);
+
+        assert_relative_eq!(0.0, point.latitude());
+    }
+    
+    #[test]
+    fn test_rect_point_latitude_1_far() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.0, 2.0);
+
+        assert_relative_eq!(1.0, point.latitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_latitude_minus_1_far() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.0, 3.0);
+
+        assert_relative_eq!(-1.0, point.latitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_0() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 0.0);
+
+        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_1() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 1.0);
+
+        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_2() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 2.0);
+
+        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_4() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 4.0);
+
+        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_6() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 6.0);
+
+        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_0_north() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 0.0);
+
+        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_1_north() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 1.0);
+
+        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_2_north() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 2.0);
+
+        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_4_north() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 4.0);
+
+        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_6_north() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 6.0);
+
+        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_0_south() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 0.0);
+
+        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_1_south() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 1.0);
+
+        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_2_south() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 2.0);
+
+        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_4_south() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 4.0);
+
+        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.001);
+    }
+    
+    #[test]
+    fn test_rect_point_longitude_6_south() {
+        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 6.0);
+
+        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.001);
+    }
+
+    #[test]
+    fn test_cube_point_up_middle() {
+        let point: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 3, 4);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 3, 3), point.up());
+    }
+    
+    #[test]
+    fn test_cube_point_up_top() {
+        let point: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 0, 0);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Top, 0, 4), point.up());
+    }
+    
+    #[test]
+    fn test_cube_point_up_bottom() {
+        let point: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 0, 4);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 0, 3), point.up());
+    }
+    
+    #[test]
+    fn test_cube_point_down_middle() {
+        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 3, 4);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 3, 5), point.down());
+    }
+    
+    #[test]
+    fn test_cube_point_down_top() {
+        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 0);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 0, 1), point.down());
+    }
+    
+    #[test]
+    fn test_cube_point_down_bottom() {
+        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 9);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Bottom, 0, 0), point.down());
+    }
+
+    #[test]
+    fn test_cube_point_left_middle() {
+        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Left, 5, 5);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Left, 4, 5), point.left());
+    }
+    
+    #[test]
+    fn test_cube_point_left_left() {
+        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Left, 0, 5);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Back, 0, 4), point.left());
+    }
+   
+    #[test]
+    fn test_cube_point_left_right() {
+        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Left, 9, 5);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Left, 8, 5), point.left());
+    }
+
+    #[test]
+    fn test_cube_point_right_middle() {
+        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Right, 5, 5);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Right, 6, 5), point.right());
+    }
+    
+    #[test]
+    fn test_cube_point_right_left() {
+        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Right, 0, 5);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Right, 1, 5), point.right());
+    }
+   
+    #[test]
+    fn test_cube_point_right_right() {
+        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Right, 9, 5);
+
+        assert_eq!(CubeSpherePoint::new(CubeFace::Back, 9, 4), point.right());
+    }
+
+    #[test]
+    fn test_cube_point_neighbours_adjacent() {
+        let grid: CubeSphereGrid<(), 5> = CubeSphereGrid::default();
+
+        for point in grid.points() {
+            let (x, y, z) = point.position(1.0);
+
+            for neighbour in [point.up(), point.down(), point.left(), point.right()] {
+                assert!([neighbour.up(), neighbour.down(), neighbour.left(), neighbour.right()].contains(&point));
+
+                let (nx, ny, nz) = neighbour.position(1.0);
+
+                assert!((x * nx + y * ny + z * nz).acos() < 0.6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_cube_point_side_up_is_north() {
+        let grid: CubeSphereGrid<(), 6> = CubeSphereGrid::default();
+
+        for point in grid.points() {
+            match point.face() {
+                CubeFace::Front | CubeFace::Left | CubeFace::Right => assert!(point.up().latitude() > point.latitude()),
+                // The back face continues on from the top face so it is upside down.
+                CubeFace::Back => assert!(point.up().latitude() < point.latitude()),
+                CubeFace::Top | CubeFace::Bottom => {},
+            }
         }
     }
 
-    fn latitude(&self) -> f64 {
-        let (x, y, z) = self.position(1.0);
+    #[test]
+    fn test_cube_point_from_geographic_equator() {
+        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(0.0, PI);
 
-        let distance = (x * x + z * z).sqrt();
+        assert_eq!(CubeSpherePoint::new(CubeFace::Back, 50, 50), point);
+    }
+    
+    #[test]
+    fn test_cube_point_from_geographic_north_pole() {
+        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(PI / 2.0, PI);
 
-        (y / distance).atan()
+        assert_eq!(CubeSpherePoint::new(CubeFace::Top, 50, 50), point);
     }
+    
+    #[test]
+    fn test_cube_point_from_geographic_south_pole() {
+        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(-PI / 2.0, PI);
 
-    fn longitude(&self) -> f64 {
-        let (x, _, z) = self.position(1.0);
+        assert_eq!(CubeSpherePoint::new(CubeFace::Bottom, 50, 50), point);
+    }
+    
+    #[test]
+    fn test_cube_point_from_geographic_east() {
+        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(0.0, -PI / 2.0);
 
-        x.atan2(z).rem_euclid(2.0 * PI)
+        assert_eq!(CubeSpherePoint::new(CubeFace::Left, 50, 50), point);
     }
-}
+    
+    #[test]
+    fn test_cube_point_from_geographic_west() {
+        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(0.0, PI / 2.0);
 
-/// A face of a cube.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[repr(u32)] // For better alignment.
-enum CubeFace {
-    Front,
-    Back,
-    Left,
-    Right,
-    Top,
-    Bottom,
-}
+        assert_eq!(CubeSpherePoint::new(CubeFace::Right, 50, 50), point);
+    }
+    
+    #[test]
+    fn test_cube_point_from_geographic_less_west() {
+        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(0.0, PI / 2.0 - 0.2);
 
-#[cfg(test)]
-mod test {
-    use std::{f64::consts::PI, hint::black_box};
+        assert_eq!(CubeSpherePoint::new(CubeFace::Right, 39, 50), point);
+    }
 
-    use approx::assert_relative_eq;
 
-    use crate::{GridPoint, SurfaceGrid, sphere::{CubeSpherePoint, CubeFace, CubeSphereGrid}};
+    #[test]
+    fn test_cube_point_up_loop() {
+        let start: CubeSpherePoint<3> = CubeSpherePoint::new(CubeFace::Bottom, 1, 2);
 
-    use super::{RectangleSpherePoint, SpherePoint, RectangleSphereGrid};
+        assert_eq!(start, start.up().up().up()
+                   .up().up().up()
+                   .up().up().up()
+                   .up().up().up());
+    }
+    
+    #[test]
+    fn test_cube_point_down_loop() {
+        let start: CubeSpherePoint<3> = CubeSpherePoint::new(CubeFace::Top, 0, 0);
 
+        assert_eq!(start, start.down().down().down()
+                   .down().down().down()
+                   .down().down().down()
+                   .down().down().down());
+    }
+    
     #[test]
-    fn test_rect_point_up_middle() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(3, 4);
+    fn test_cube_point_left_loop() {
+        let start: CubeSpherePoint<3> = CubeSpherePoint::new(CubeFace::Back, 1, 0);
 
-        assert_eq!(RectangleSpherePoint::new(3, 3), point.up());
+        assert_eq!(start, start.left().left().left()
+                   .left().left().left()
+                   .left().left().left()
+                   .left().left().left());
     }
     
     #[test]
-    fn test_rect_point_up_top_left() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 0);
+    fn test_cube_point_right_loop() {
+        let start: CubeSpherePoint<3> = CubeSpherePoint::new(CubeFace::Front, 0, 2);
 
-        assert_eq!(RectangleSpherePoint::new(5, 0), point.up());
+        assert_eq!(start, start.right().right().right()
+                   .right().right().right()
+                   .right().right().right()
+                   .right().right().right());
+    }
+
+    #[test]
+    fn test_cube_point_up_inverse_middle() {
+        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 5, 3);
+
+        assert_eq!(start, start.up().down());
     }
     
     #[test]
-    fn test_rect_point_up_top_right() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 0);
+    fn test_cube_point_down_inverse_middle() {
+        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 5, 3);
 
-        assert_eq!(RectangleSpherePoint::new(9, 1), point.up());
+        assert_eq!(start, start.down().up());
     }
     
     #[test]
-    fn test_rect_point_up_bottom_left() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 9);
+    fn test_cube_point_left_inverse_middle() {
+        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 5, 3);
 
-        assert_eq!(RectangleSpherePoint::new(0, 8), point.up());
+        assert_eq!(start, start.left().right());
     }
     
     #[test]
-    fn test_rect_point_up_bottom_right() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 9);
+    fn test_cube_point_right_inverse_middle() {
+        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 5, 3);
 
-        assert_eq!(RectangleSpherePoint::new(4, 9), point.up());
+        assert_eq!(start, start.right().left());
     }
     
     #[test]
-    fn test_rect_point_down_middle() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(3, 4);
+    fn test_cube_point_up_inverse_edge() {
+        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 0);
 
-        assert_eq!(RectangleSpherePoint::new(3, 5), point.down());
+        assert_eq!(start, start.up().down());
     }
     
     #[test]
-    fn test_rect_point_down_top_left() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 0);
+    fn test_cube_point_down_inverse_edge() {
+        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 9);
 
-        assert_eq!(RectangleSpherePoint::new(0, 1), point.down());
+        assert_eq!(start, start.down().up());
     }
     
     #[test]
-    fn test_rect_point_down_top_right() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 0);
+    fn test_cube_point_left_inverse_edge() {
+        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 0);
 
-        assert_eq!(RectangleSpherePoint::new(4, 0), point.down());
+        assert_eq!(start, start.left().right());
     }
     
     #[test]
-    fn test_rect_point_down_bottom_left() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 9);
+    fn test_cube_point_right_inverse_edge() {
+        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 9, 0);
 
-        assert_eq!(RectangleSpherePoint::new(5, 9), point.down());
+        assert_eq!(start, start.right().left());
+    }
+
+    #[test]
+    fn test_cube_from_fn() {
+        let grid: CubeSphereGrid<u16, 100> = CubeSphereGrid::from_fn(|point| point.x + point.y);
+
+        assert_eq!(15, grid[CubeSpherePoint::new(CubeFace::Front, 5, 10)]);
     }
     
     #[test]
-    fn test_rect_point_down_bottom_right() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 9);
+    fn test_cube_from_neighbours() {
+        let grid: CubeSphereGrid<u16, 10> = CubeSphereGrid::from_fn(|point| point.x);
 
-        assert_eq!(RectangleSpherePoint::new(9, 8), point.down());
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert_eq!(25, grid2[CubeSpherePoint::new(CubeFace::Front, 5, 3)])
     }
+    
+    #[test]
+    fn test_cube_from_neighbours_diagonals() {
+        let grid: CubeSphereGrid<u16, 10> = CubeSphereGrid::from_fn(|point| point.x);
+
+        let grid2 = grid.map_neighbours_diagonals(|up_left, up, up_right, left, current, right, down_left, down, down_right| up_left + up + up_right + left + current + right + down_left + down + down_right);
 
+        assert_eq!(4 * 3 + 5 * 3 + 6 * 3, grid2[CubeSpherePoint::new(CubeFace::Front, 5, 3)])
+    }
+    
     #[test]
-    fn test_rect_point_left_middle() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(5, 5);
+    fn test_cube_point_latitude_0() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 0.0);
+        
+        println!("{:?}", point);
 
-        assert_eq!(RectangleSpherePoint::new(4, 5), point.left());
+        assert_relative_eq!(0.0, point.latitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_left_left() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 5);
+    fn test_cube_point_latitude_1() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.0, 0.0);
 
-        assert_eq!(RectangleSpherePoint::new(9, 5), point.left());
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
+
+        assert_relative_eq!(1.0, point.latitude(), epsilon = 0.01);
     }
-   
+    
     #[test]
-    fn test_rect_point_left_right() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 5);
+    fn test_cube_point_latitude_minus_1() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.0, 0.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(8, 5), point.left());
+        assert_relative_eq!(-1.0, point.latitude(), epsilon = 0.01);
     }
+    
+    #[test]
+    fn test_cube_point_latitude_half() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.5, 0.0);
+
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
+        assert_relative_eq!(0.5, point.latitude(), epsilon = 0.01);
+    }
+    
     #[test]
-    fn test_rect_point_right_middle() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(5, 5);
+    fn test_cube_point_latitude_minus_half() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-0.5, 0.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(6, 5), point.right());
+        assert_relative_eq!(-0.5, point.latitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_right_left() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(0, 5);
+    fn test_cube_point_latitude_0_far() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 3.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(1, 5), point.right());
+        assert_relative_eq!(0.0, point.latitude(), epsilon = 0.01);
     }
-   
+    
     #[test]
-    fn test_rect_point_right_right() {
-        let point: RectangleSpherePoint<10, 10> = RectangleSpherePoint::new(9, 5);
+    fn test_cube_point_latitude_1_far() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.0, 2.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(0, 5), point.right());
+        assert_relative_eq!(1.0, point.latitude(), epsilon = 0.1);
     }
+    
+    #[test]
+    fn test_cube_point_latitude_minus_1_far() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.0, 3.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
+        assert_relative_eq!(-1.0, point.latitude(), epsilon = 0.01);
+    }
+    
     #[test]
-    fn test_rect_point_from_geographic_equator() {
-        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(0.0, PI);
+    fn test_cube_point_latitude_half_far() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.5, 5.0);
 
-        assert_eq!(RectangleSpherePoint::new(50, 50), point);
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
+
+        assert_relative_eq!(0.5, point.latitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_from_geographic_north_pole() {
-        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(PI / 2.0, PI);
+    fn test_cube_point_latitude_minus_half_far() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-0.5, 3.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(50, 0), point);
+        assert_relative_eq!(-0.5, point.latitude(), epsilon = 0.01);
     }
-    
+   
     #[test]
-    fn test_rect_point_from_geographic_south_pole() {
-        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(-PI / 2.0, PI);
+    fn test_cube_point_longitude_0() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 0.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(50, 99), point);
+        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_from_geographic_equator_wrap_north() {
-        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(-PI, PI);
+    fn test_cube_point_longitude_1() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 1.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(50, 50), point);
+        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_from_geographic_equator_wrap_south() {
-        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(PI, PI);
+    fn test_cube_point_longitude_2() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 2.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(50, 50), point);
+        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_from_geographic_east() {
-        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(0.0, PI * 2.0);
+    fn test_cube_point_longitude_3() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 3.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(0, 50), point);
+        assert_relative_eq!(3.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_from_geographic_west() {
-        let point: RectangleSpherePoint<100, 100> = RectangleSpherePoint::from_geographic(0.0, 0.0);
+    fn test_cube_point_longitude_4() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 4.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(RectangleSpherePoint::new(0, 50), point);
+        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.01);
     }
-
+    
     #[test]
-    fn test_rect_point_up_loop() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 3);
+    fn test_cube_point_longitude_5() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 5.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.up().up().up().up().up().up().up().up().up().up());
+        assert_relative_eq!(5.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_down_loop() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+    fn test_cube_point_longitude_6() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 6.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.down().down().down().down().down().down().down().down().down().down());
+        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_left_loop() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 3);
+    fn test_cube_point_longitude_0_north() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 0.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.left().left().left().left().left().left().left().left().left().left());
+        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_right_loop() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 3);
+    fn test_cube_point_longitude_1_north() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 1.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.right().right().right().right().right().right().right().right().right().right());
+        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.01);
     }
-
+    
     #[test]
-    fn test_rect_point_up_inverse_middle() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+    fn test_cube_point_longitude_2_north() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 2.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.up().down());
+        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_down_inverse_middle() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+    fn test_cube_point_longitude_4_north() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 4.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.down().up());
+        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.001);
     }
     
     #[test]
-    fn test_rect_point_left_inverse_middle() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+    fn test_cube_point_longitude_6_north() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 6.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.left().right());
+        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_right_inverse_middle() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(5, 3);
+    fn test_cube_point_longitude_0_south() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 0.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.right().left());
+        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_up_inverse_edge() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 0);
+    fn test_cube_point_longitude_1_south() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 1.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.up().down());
+        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_down_inverse_edge() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 4);
+    fn test_cube_point_longitude_2_south() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 2.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.down().up());
+        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_left_inverse_edge() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(0, 0);
+    fn test_cube_point_longitude_4_south() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 4.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.left().right());
+        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.01);
     }
     
     #[test]
-    fn test_rect_point_right_inverse_edge() {
-        let start: RectangleSpherePoint<10, 5> = RectangleSpherePoint::new(9, 0);
+    fn test_cube_point_longitude_6_south() {
+        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 6.0);
+        
+        println!("{:?}", point);
+        println!("{:?}", point.position(1.0));
 
-        assert_eq!(start, start.right().left());
+        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.01);
     }
 
     #[test]
-    fn test_rect_from_fn() {
-        let grid: RectangleSphereGrid<u32, 200, 100> = RectangleSphereGrid::from_fn(|point| point.x + point.y);
+    fn test_cube_point_geographic_inverse() {
+        let grid: CubeSphereGrid<(), 16> = CubeSphereGrid::default();
 
-        assert_eq!(15, grid[RectangleSpherePoint::new(5, 10)]);
+        for point in grid.points() {
+            assert_eq!(point, point.at_geographic(point.latitude(), point.longitude()));
+        }
     }
 
     #[test]
-    fn test_rect_from_neighbours() {
-        let grid: RectangleSphereGrid<u32, 20, 10> = RectangleSphereGrid::from_fn(|point| point.x);
+    fn test_equiangular_plane_inverse() {
+        for i in -10..=10 {
+            let position = i as f64 / 10.0;
 
-        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+            assert_relative_eq!(position, Equiangular::from_plane(Equiangular::to_plane(position)), epsilon = 1e-12);
+            assert_relative_eq!(position, Gnomonic::from_plane(Gnomonic::to_plane(position)));
+        }
 
-        assert_eq!(25, grid2[RectangleSpherePoint::new(5, 3)])
+        assert_relative_eq!(1.0, Equiangular::to_plane(1.0), epsilon = 1e-12);
+        assert_relative_eq!(-1.0, Equiangular::to_plane(-1.0), epsilon = 1e-12);
     }
-    
-    #[test]
-    fn test_rect_from_neighbours_diagonals() {
-        let grid: RectangleSphereGrid<u32, 20, 10> = RectangleSphereGrid::from_fn(|point| point.x);
 
-        let grid2 = grid.map_neighbours_diagonals(|up_left, up, up_right, left, current, right, down_left, down, down_right| up_left + up + up_right + left + current + right + down_left + down + down_right);
+    #[test]
+    fn test_equiangular_point_geographic_inverse() {
+        let grid: EquiangularCubeSphereGrid<(), 16> = EquiangularCubeSphereGrid::default();
 
-        assert_eq!(4 * 3 + 5 * 3 + 6 * 3, grid2[RectangleSpherePoint::new(5, 3)])
+        for point in grid.points() {
+            assert_eq!(point, point.at_geographic(point.latitude(), point.longitude()));
+        }
     }
 
     #[test]
-    fn test_rect_point_latitude_0() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 0.0);
+    fn test_equiangular_point_from_geographic_north_pole() {
+        let point: CubeSpherePoint<100, Equiangular> = CubeSpherePoint::from_geographic(PI / 2.0, 0.0);
 
-        assert_relative_eq!(0.0, point.latitude());
+        assert_eq!(CubeSpherePoint::new(CubeFace::Top, 50, 50), point);
     }
-    
-    #[test]
-    fn test_rect_point_latitude_1() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.0, 0.0);
 
-        assert_relative_eq!(1.0, point.latitude(), epsilon = 0.001);
-    }
-    
     #[test]
-    fn test_rect_point_latitude_minus_1() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.0, 0.0);
+    fn test_equiangular_point_from_geographic_face_edge() {
+        // 45 degrees from the centre of the front face is its right edge.
+        let point: CubeSpherePoint<100, Equiangular> = CubeSpherePoint::from_geographic(0.0, PI / 4.0 - 0.001);
 
-        assert_relative_eq!(-1.0, point.latitude(), epsilon = 0.001);
-    }
-    
-    #[test]
-    fn test_rect_point_latitude_0_far() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 4.0);
+        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 99, 50), point);
 
-        assert_relative_eq!(0.0, point.latitude());
-    }
-    
-    #[test]
-    fn test_rect_point_latitude_1_far() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.0, 2.0);
+        // A quarter of the way to the edge is a quarter of the way across the face.
+        let point: CubeSpherePoint<100, Equiangular> = CubeSpherePoint::from_geographic(0.0, PI / 8.0 + 0.001);
 
-        assert_relative_eq!(1.0, point.latitude(), epsilon = 0.001);
+        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 75, 50), point);
     }
-    
-    #[test]
-    fn test_rect_point_latitude_minus_1_far() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.0, 3.0);
 
-        assert_relative_eq!(-1.0, point.latitude(), epsilon = 0.001);
-    }
-    
     #[test]
-    fn test_rect_point_longitude_0() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 0.0);
+    fn test_equiangular_equal_angle_spacing() {
+        // Every step along the equator across the front and right faces covers the same angle.
+        let mut point = CubeSpherePoint::<21, Equiangular>::new(CubeFace::Front, 0, 10);
 
-        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.001);
-    }
-    
-    #[test]
-    fn test_rect_point_longitude_1() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 1.0);
+        for _ in 0..41 {
+            let next = point.right();
 
-        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.001);
-    }
-    
-    #[test]
-    fn test_rect_point_longitude_2() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 2.0);
+            let (x1, y1, z1) = point.position(1.0);
+            let (x2, y2, z2) = next.position(1.0);
 
-        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.001);
-    }
-    
-    #[test]
-    fn test_rect_point_longitude_4() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 4.0);
+            let angle = (x1 * x2 + y1 * y2 + z1 * z2).acos();
 
-        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.001);
+            assert_relative_eq!(PI / 2.0 / 21.0, angle, epsilon = 1e-9);
+
+            point = next;
+        }
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_6() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(0.0, 6.0);
+    fn test_equiangular_more_uniform_than_gnomonic() {
+        fn spacing_ratio<P: CubeProjection>() -> f64 {
+            let grid: CubeSphereGrid<(), 32, P> = CubeSphereGrid::default();
 
-        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.001);
+            let distances: Vec<f64> = grid.points()
+                .filter(|point| point.x < 31)
+                .map(|point| {
+                    let (x1, y1, z1) = point.position(1.0);
+                    let (x2, y2, z2) = CubeSpherePoint::<32, P>::new(point.face, point.x + 1, point.y).position(1.0);
+
+                    ((x1 - x2).powi(2) + (y1 - y2).powi(2) + (z1 - z2).powi(2)).sqrt()
+                })
+                .collect();
+
+            let max = distances.iter().cloned().fold(f64::MIN, f64::max);
+            let min = distances.iter().cloned().fold(f64::MAX, f64::min);
+
+            max / min
+        }
+
+        assert!(spacing_ratio::<Equiangular>() < 1.5);
+        assert!(spacing_ratio::<Gnomonic>() > 2.0);
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_0_north() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 0.0);
+    fn test_equiangular_neighbours_match_gnomonic() {
+        let grid: CubeSphereGrid<(), 5> = CubeSphereGrid::default();
 
-        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.001);
+        for point in grid.points() {
+            let equiangular: CubeSpherePoint<5, Equiangular> = CubeSpherePoint::new(point.face, point.x, point.y);
+
+            assert_eq!(point.up().to_dyn(), equiangular.up().to_dyn());
+            assert_eq!(point.down().to_dyn(), equiangular.down().to_dyn());
+            assert_eq!(point.left().to_dyn(), equiangular.left().to_dyn());
+            assert_eq!(point.right().to_dyn(), equiangular.right().to_dyn());
+        }
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_1_north() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 1.0);
+    fn test_equiangular_from_neighbours() {
+        let grid: EquiangularCubeSphereGrid<u16, 10> = EquiangularCubeSphereGrid::from_fn(|point| point.x);
 
-        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.001);
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert_eq!(25, grid2[CubeSpherePoint::new(CubeFace::Front, 5, 3)])
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_2_north() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 2.0);
+    fn test_rect_ellipsoid_area_total() {
+        let grid: RectangleSphereGrid<(), 36, 18> = RectangleSphereGrid::default();
 
-        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.001);
+        let area: f64 = grid.points().map(|point| point.ellipsoid_area(&Ellipsoid::WGS84)).sum();
+
+        assert_relative_eq!(Ellipsoid::WGS84.area(), area, max_relative = 1e-12);
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_4_north() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 4.0);
+    fn test_rect_ellipsoid_area_poles_smaller() {
+        let equator: RectangleSpherePoint<36, 18> = RectangleSpherePoint::new(0, 9);
+        let pole: RectangleSpherePoint<36, 18> = RectangleSpherePoint::new(0, 0);
 
-        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.001);
+        assert!(pole.ellipsoid_area(&Ellipsoid::WGS84) < equator.ellipsoid_area(&Ellipsoid::WGS84) / 5.0);
+        assert_relative_eq!(
+            pole.ellipsoid_area(&Ellipsoid::WGS84),
+            RectangleSpherePoint::<36, 18>::new(0, 17).ellipsoid_area(&Ellipsoid::WGS84),
+            max_relative = 1e-12
+        );
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_6_north() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(-1.5, 6.0);
+    fn test_cube_ellipsoid_area_total() {
+        let grid: CubeSphereGrid<(), 8> = CubeSphereGrid::default();
 
-        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.001);
+        let area: f64 = grid.points().map(|point| point.ellipsoid_area(&Ellipsoid::WGS84)).sum();
+
+        assert_relative_eq!(Ellipsoid::WGS84.area(), area, max_relative = 1e-9);
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_0_south() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 0.0);
+    fn test_equiangular_ellipsoid_area_total() {
+        let grid: EquiangularCubeSphereGrid<(), 8> = EquiangularCubeSphereGrid::default();
 
-        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.001);
+        let area: f64 = grid.points().map(|point| point.ellipsoid_area(&Ellipsoid::WGS84)).sum();
+
+        assert_relative_eq!(Ellipsoid::WGS84.area(), area, max_relative = 1e-9);
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_1_south() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 1.0);
+    fn test_dyn_cube_ellipsoid_area_matches_static() {
+        let point: CubeSpherePoint<8> = CubeSpherePoint::new(CubeFace::Right, 1, 6);
 
-        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.001);
+        assert_eq!(point.ellipsoid_area(&Ellipsoid::WGS84), point.to_dyn().ellipsoid_area(&Ellipsoid::WGS84));
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_2_south() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 2.0);
+    fn test_cube_ellipsoid_area_sphere() {
+        // A face of a cube covers a sixth of a sphere.
+        let grid: CubeSphereGrid<(), 4> = CubeSphereGrid::default();
 
-        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.001);
+        let area: f64 = grid.points()
+            .filter(|point| point.face == CubeFace::Top)
+            .map(|point| point.ellipsoid_area(&Ellipsoid::sphere(1.0)))
+            .sum();
+
+        assert_relative_eq!(4.0 * PI / 6.0, area, max_relative = 1e-12);
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_4_south() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 4.0);
+    fn test_cube_point_ecef() {
+        let point: CubeSpherePoint<64> = CubeSpherePoint::from_geographic(0.7, 2.5);
 
-        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.001);
+        let (x, y, z) = point.ecef(&Ellipsoid::WGS84);
+        let (latitude, longitude, height) = Ellipsoid::WGS84.ecef_to_geodetic(x, y, z);
+
+        assert_relative_eq!(point.latitude(), latitude, epsilon = 1e-12);
+        assert_relative_eq!(point.longitude(), longitude, epsilon = 1e-12);
+        assert_relative_eq!(0.0, height, epsilon = 1e-6);
     }
-    
+
     #[test]
-    fn test_rect_point_longitude_6_south() {
-        let point: RectangleSpherePoint<1000000000, 500000000> = RectangleSpherePoint::from_geographic(1.5, 6.0);
+    fn test_cube_point_from_ecef() {
+        let grid: CubeSphereGrid<(), 16> = CubeSphereGrid::default();
 
-        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.001);
+        for point in grid.points() {
+            let (x, y, z) = point.ecef(&Ellipsoid::WGS84);
+
+            assert_eq!(point, CubeSpherePoint::from_ecef(x, y, z, &Ellipsoid::WGS84));
+        }
     }
 
     #[test]
-    fn test_cube_point_up_middle() {
-        let point: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 3, 4);
+    fn test_rect_point_from_ecef() {
+        let (x, y, z) = Ellipsoid::WGS84.geodetic_to_ecef(0.7, 2.5, 1000.0);
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 3, 3), point.up());
+        let point: RectangleSpherePoint<100, 50> = RectangleSpherePoint::from_ecef(x, y, z, &Ellipsoid::WGS84);
+
+        assert_eq!(RectangleSpherePoint::from_geographic(0.7, 2.5), point);
+        assert_eq!(point, point.at_ecef(x, y, z, &Ellipsoid::WGS84));
     }
-    
+
     #[test]
-    fn test_cube_point_up_top() {
-        let point: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 0, 0);
+    fn test_rect_point_ellipsoid_distance() {
+        let point1: RectangleSpherePoint<360, 180> = RectangleSpherePoint::new(0, 90);
+        let point2: RectangleSpherePoint<360, 180> = RectangleSpherePoint::new(90, 90);
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Top, 0, 4), point.up());
+        // A quarter of the equator.
+        assert_relative_eq!(6_378_137.0 * PI / 2.0, point1.ellipsoid_distance_to(&point2, &Ellipsoid::WGS84), epsilon = 1e-3);
     }
-    
+
     #[test]
-    fn test_cube_point_up_bottom() {
-        let point: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 0, 4);
+    fn test_rect_point_try_new() {
+        let point: RectangleSpherePoint<10, 6> = RectangleSpherePoint::try_new(7, 5).unwrap();
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 0, 3), point.up());
+        assert_eq!(RectangleSpherePoint::new(7, 5), point);
+        assert_eq!(7, point.x());
+        assert_eq!(5, point.y());
     }
-    
-    #[test]
-    fn test_cube_point_down_middle() {
-        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 3, 4);
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 3, 5), point.down());
+    #[test]
+    fn test_rect_point_try_new_out_of_range() {
+        assert_eq!(Err(PointError::XOutOfRange { x: 10, width: 10 }), RectangleSpherePoint::<10, 6>::try_new(10, 0));
+        assert_eq!(Err(PointError::YOutOfRange { y: 6, height: 6 }), RectangleSpherePoint::<10, 6>::try_new(0, 6));
     }
-    
+
     #[test]
-    fn test_cube_point_down_top() {
-        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 0);
+    fn test_dyn_rect_point_try_new() {
+        let point = DynRectangleSpherePoint::try_new(7, 5, 10, 6).unwrap();
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Front, 0, 1), point.down());
+        assert_eq!(RectangleSpherePoint::<10, 6>::new(7, 5).to_dyn(), point);
+        assert_eq!((7, 5), (point.x(), point.y()));
+        assert!(DynRectangleSpherePoint::try_new(7, 6, 10, 6).is_err());
     }
-    
+
     #[test]
-    fn test_cube_point_down_bottom() {
-        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 9);
+    fn test_cube_point_try_new() {
+        let point: CubeSpherePoint<8> = CubeSpherePoint::try_new(CubeFace::Left, 3, 7).unwrap();
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Bottom, 0, 0), point.down());
+        assert_eq!(CubeSpherePoint::new(CubeFace::Left, 3, 7), point);
+        assert_eq!(CubeFace::Left, point.face());
+        assert_eq!(3, point.x());
+        assert_eq!(7, point.y());
     }
 
     #[test]
-    fn test_cube_point_left_middle() {
-        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Left, 5, 5);
-
-        assert_eq!(CubeSpherePoint::new(CubeFace::Left, 4, 5), point.left());
+    fn test_cube_point_try_new_out_of_range() {
+        assert_eq!(Err(PointError::XOutOfRange { x: 8, width: 8 }), CubeSpherePoint::<8>::try_new(CubeFace::Top, 8, 0));
+        assert_eq!(Err(PointError::YOutOfRange { y: 9, height: 8 }), CubeSpherePoint::<8>::try_new(CubeFace::Top, 0, 9));
     }
-    
+
     #[test]
-    fn test_cube_point_left_left() {
-        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Left, 0, 5);
+    fn test_dyn_cube_point_try_new() {
+        let point = DynCubeSpherePoint::try_new(CubeFace::Back, 2, 4, 5).unwrap();
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Back, 0, 5), point.left());
+        assert_eq!(DynCubeSpherePoint::new(CubeFace::Back, 2, 4, 5), point);
+        assert_eq!((CubeFace::Back, 2, 4), (point.face(), point.x(), point.y()));
+        assert!(DynCubeSpherePoint::try_new(CubeFace::Back, 5, 4, 5).is_err());
     }
-   
+
     #[test]
-    fn test_cube_point_left_right() {
-        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Left, 9, 5);
+    fn test_cube_face_centres() {
+        for (face, latitude, longitude) in [
+            (CubeFace::Front, 0.0, 0.0),
+            (CubeFace::Back, 0.0, PI),
+            (CubeFace::Left, 0.0, 3.0 * PI / 2.0),
+            (CubeFace::Right, 0.0, PI / 2.0),
+            (CubeFace::Top, PI / 2.0, 0.0),
+            (CubeFace::Bottom, -PI / 2.0, 0.0),
+        ] {
+            assert_eq!(face, CubeSpherePoint::<9>::from_geographic(latitude, longitude).face());
+        }
+    }
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Left, 8, 5), point.left());
+    #[test]
+    fn test_point_error_display() {
+        assert_eq!("X coordinate 10 is outside a grid of width 8", PointError::XOutOfRange { x: 10, width: 8 }.to_string());
     }
 
     #[test]
-    fn test_cube_point_right_middle() {
-        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Right, 5, 5);
+    fn test_cube_clone_128() {
+        let grid: CubeSphereGrid<u64, 128> = CubeSphereGrid::default();
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Right, 6, 5), point.right());
+        assert_eq!(grid, black_box(grid.clone()));
     }
     
     #[test]
-    fn test_cube_point_right_left() {
-        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Right, 0, 5);
+    fn test_cube_clone_128_large_struct() {
+        let grid: CubeSphereGrid<[u8; 1024], 128> = CubeSphereGrid::from_fn(|_| [0b11101101; 1024]);
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Right, 1, 5), point.right());
+        assert_eq!(grid, black_box(grid.clone()));
     }
-   
+    
     #[test]
-    fn test_cube_point_right_right() {
-        let point: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Right, 9, 5);
+    fn test_cube_clone_4096() {
+        let grid: CubeSphereGrid<u64, 4096> = CubeSphereGrid::default();
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Back, 9, 5), point.right());
+        assert_eq!(grid, black_box(grid.clone()));
     }
 
     #[test]
-    fn test_cube_point_from_geographic_equator() {
-        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(0.0, PI);
+    fn test_dyn_rect_neighbours_match_static() {
+        let grid: RectangleSphereGrid<(), 10, 6> = RectangleSphereGrid::default();
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Back, 50, 50), point);
+        for point in grid.points() {
+            let dyn_point = point.to_dyn();
+
+            assert_eq!(point.up().to_dyn(), dyn_point.up());
+            assert_eq!(point.down().to_dyn(), dyn_point.down());
+            assert_eq!(point.left().to_dyn(), dyn_point.left());
+            assert_eq!(point.right().to_dyn(), dyn_point.right());
+        }
     }
-    
+
     #[test]
-    fn test_cube_point_from_geographic_north_pole() {
-        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(PI / 2.0, PI);
+    fn test_dyn_rect_points_match_static() {
+        let grid: RectangleSphereGrid<(), 10, 6> = RectangleSphereGrid::default();
+        let dyn_grid: DynRectangleSphereGrid<()> = DynRectangleSphereGrid::new(10, 6);
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Top, 50, 50), point);
+        assert!(grid.points().map(|point| point.to_dyn()).eq(dyn_grid.points()));
     }
-    
+
     #[test]
-    fn test_cube_point_from_geographic_south_pole() {
-        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(-PI / 2.0, PI);
+    fn test_dyn_rect_from_fn() {
+        let grid = DynRectangleSphereGrid::from_fn(200, 100, |point| point.x + point.y);
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Bottom, 50, 50), point);
+        assert_eq!(15, grid[DynRectangleSpherePoint::new(5, 10, 200, 100)]);
     }
-    
+
     #[test]
-    fn test_cube_point_from_geographic_east() {
-        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(0.0, -PI / 2.0);
+    fn test_dyn_rect_from_fn_par() {
+        let grid = DynRectangleSphereGrid::from_fn_par(200, 100, |point| point.x + point.y);
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Left, 50, 50), point);
+        assert_eq!(DynRectangleSphereGrid::from_fn(200, 100, |point| point.x + point.y), grid);
     }
-    
+
     #[test]
-    fn test_cube_point_from_geographic_west() {
-        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(0.0, PI / 2.0);
+    fn test_dyn_rect_from_neighbours() {
+        let grid = DynRectangleSphereGrid::from_fn(20, 10, |point| point.x);
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Right, 50, 50), point);
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert_eq!(25, grid2[DynRectangleSpherePoint::new(5, 3, 20, 10)])
     }
-    
+
     #[test]
-    fn test_cube_point_from_geographic_less_west() {
-        let point: CubeSpherePoint<100> = CubeSpherePoint::from_geographic(0.0, PI / 2.0 - 0.2);
+    fn test_dyn_rect_from_geographic_south_pole() {
+        let grid: DynRectangleSphereGrid<()> = DynRectangleSphereGrid::new(60, 30);
 
-        assert_eq!(CubeSpherePoint::new(CubeFace::Right, 39, 50), point);
+        assert_eq!(DynRectangleSpherePoint::new(30, 29, 60, 30), grid.point_at_geographic(-PI / 2.0, PI));
     }
 
-
     #[test]
-    fn test_cube_point_up_loop() {
-        let start: CubeSpherePoint<3> = CubeSpherePoint::new(CubeFace::Bottom, 1, 2);
+    fn test_dyn_rect_geographic_matches_static() {
+        let point: RectangleSpherePoint<100, 50> = RectangleSpherePoint::from_geographic(0.7, 2.5);
+        let grid: DynRectangleSphereGrid<()> = DynRectangleSphereGrid::new(100, 50);
 
-        assert_eq!(start, start.up().up().up()
-                   .up().up().up()
-                   .up().up().up()
-                   .up().up().up());
+        assert_eq!(point.to_dyn(), grid.point_at_geographic(0.7, 2.5));
+        assert_eq!(point.latitude(), point.to_dyn().latitude());
+        assert_eq!(point.longitude(), point.to_dyn().longitude());
     }
-    
+
     #[test]
-    fn test_cube_point_down_loop() {
-        let start: CubeSpherePoint<3> = CubeSpherePoint::new(CubeFace::Top, 0, 0);
+    #[should_panic]
+    fn test_dyn_rect_index_other_dimensions() {
+        let grid: DynRectangleSphereGrid<u8> = DynRectangleSphereGrid::new(10, 6);
+        let other: DynRectangleSphereGrid<u8> = DynRectangleSphereGrid::new(6, 10);
 
-        assert_eq!(start, start.down().down().down()
-                   .down().down().down()
-                   .down().down().down()
-                   .down().down().down());
+        let point = other.points().last().unwrap();
+
+        black_box(grid[point]);
     }
-    
+
     #[test]
-    fn test_cube_point_left_loop() {
-        let start: CubeSpherePoint<3> = CubeSpherePoint::new(CubeFace::Back, 1, 0);
+    #[should_panic]
+    fn test_dyn_cube_index_other_size() {
+        let mut grid: DynCubeSphereGrid<u8> = DynCubeSphereGrid::new(4);
+        let other: DynCubeSphereGrid<u8> = DynCubeSphereGrid::new(3);
 
-        assert_eq!(start, start.left().left().left()
-                   .left().left().left()
-                   .left().left().left()
-                   .left().left().left());
+        let point = other.points().next().unwrap();
+
+        grid[point] = 1;
     }
-    
+
     #[test]
-    fn test_cube_point_right_loop() {
-        let start: CubeSpherePoint<3> = CubeSpherePoint::new(CubeFace::Front, 0, 2);
+    fn test_dyn_cube_neighbours_match_static() {
+        let grid: CubeSphereGrid<(), 5> = CubeSphereGrid::default();
 
-        assert_eq!(start, start.right().right().right()
-                   .right().right().right()
-                   .right().right().right()
-                   .right().right().right());
+        for point in grid.points() {
+            let dyn_point = point.to_dyn();
+
+            assert_eq!(point.up().to_dyn(), dyn_point.up());
+            assert_eq!(point.down().to_dyn(), dyn_point.down());
+            assert_eq!(point.left().to_dyn(), dyn_point.left());
+            assert_eq!(point.right().to_dyn(), dyn_point.right());
+        }
     }
 
     #[test]
-    fn test_cube_point_up_inverse_middle() {
-        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 5, 3);
+    fn test_dyn_cube_points_match_static() {
+        let grid: CubeSphereGrid<(), 5> = CubeSphereGrid::default();
+        let dyn_grid: DynCubeSphereGrid<()> = DynCubeSphereGrid::new(5);
 
-        assert_eq!(start, start.up().down());
+        assert!(grid.points().map(|point| point.to_dyn()).eq(dyn_grid.points()));
     }
-    
+
     #[test]
-    fn test_cube_point_down_inverse_middle() {
-        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 5, 3);
+    fn test_dyn_cube_storage_matches_static_index() {
+        let grid = DynCubeSphereGrid::from_fn(5, |point| *point);
 
-        assert_eq!(start, start.down().up());
+        assert!((0..CubeSpherePoint::<5>::COUNT)
+            .map(|i| CubeSpherePoint::<5>::from_index(i).to_dyn())
+            .eq(grid.data.iter().copied()));
     }
-    
+
     #[test]
-    fn test_cube_point_left_inverse_middle() {
-        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 5, 3);
+    fn test_dyn_cube_from_fn() {
+        let grid = DynCubeSphereGrid::from_fn(100, |point| point.x + point.y);
 
-        assert_eq!(start, start.left().right());
+        assert_eq!(15, grid[DynCubeSpherePoint::new(CubeFace::Front, 5, 10, 100)]);
     }
-    
+
     #[test]
-    fn test_cube_point_right_inverse_middle() {
-        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 5, 3);
+    fn test_dyn_cube_set_from_fn_par() {
+        let mut grid = DynCubeSphereGrid::new(20);
 
-        assert_eq!(start, start.right().left());
+        grid.set_from_fn_par(|point| point.x * 2 + point.y);
+
+        assert_eq!(DynCubeSphereGrid::from_fn(20, |point| point.x * 2 + point.y), grid);
     }
-    
+
     #[test]
-    fn test_cube_point_up_inverse_edge() {
-        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 0);
+    fn test_dyn_cube_from_neighbours() {
+        let grid = DynCubeSphereGrid::from_fn(10, |point| point.x);
 
-        assert_eq!(start, start.up().down());
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert_eq!(25, grid2[DynCubeSpherePoint::new(CubeFace::Front, 5, 3, 10)])
     }
-    
+
     #[test]
-    fn test_cube_point_down_inverse_edge() {
-        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 9);
+    fn test_dyn_cube_from_geographic_north_pole() {
+        let grid: DynCubeSphereGrid<()> = DynCubeSphereGrid::new(100);
 
-        assert_eq!(start, start.down().up());
+        assert_eq!(DynCubeSpherePoint::new(CubeFace::Top, 50, 50, 100), grid.point_at_geographic(PI / 2.0, PI));
     }
-    
+
     #[test]
-    fn test_cube_point_left_inverse_edge() {
-        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 0, 0);
+    fn test_dyn_cube_geographic_matches_static() {
+        let point: CubeSpherePoint<64> = CubeSpherePoint::from_geographic(0.7, 2.5);
+        let grid: DynCubeSphereGrid<()> = DynCubeSphereGrid::new(64);
 
-        assert_eq!(start, start.left().right());
+        assert_eq!(point.to_dyn(), grid.point_at_geographic(0.7, 2.5));
+        assert_eq!(point, point.at_geographic(0.7, 2.5));
+        assert_eq!(point.to_dyn(), point.to_dyn().at_geographic(0.7, 2.5));
     }
-    
+
     #[test]
-    fn test_cube_point_right_inverse_edge() {
-        let start: CubeSpherePoint<10> = CubeSpherePoint::new(CubeFace::Front, 9, 0);
+    fn test_dyn_cube_into_iter() {
+        let grid = DynCubeSphereGrid::from_fn(4, |point| point.to_owned());
 
-        assert_eq!(start, start.right().left());
+        assert!(grid.into_iter().all(|(point, value)| point == value));
     }
 
     #[test]
-    fn test_cube_from_fn() {
-        let grid: CubeSphereGrid<u16, 100> = CubeSphereGrid::from_fn(|point| point.x + point.y);
+    fn test_rect_indexed_points() {
+        assert_eq!(60, RectangleSpherePoint::<10, 6>::COUNT);
 
-        assert_eq!(15, grid[CubeSpherePoint::new(CubeFace::Front, 5, 10)]);
+        assert_indexed_points(&RectangleSphereGrid::<(), 10, 6>::default());
     }
-    
+
     #[test]
-    fn test_cube_from_neighbours() {
-        let grid: CubeSphereGrid<u16, 10> = CubeSphereGrid::from_fn(|point| point.x);
+    fn test_cube_indexed_points() {
+        assert_eq!(150, CubeSpherePoint::<5>::COUNT);
 
-        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+        assert_indexed_points(&CubeSphereGrid::<(), 5>::default());
+    }
 
-        assert_eq!(25, grid2[CubeSpherePoint::new(CubeFace::Front, 5, 3)])
+    #[test]
+    fn test_equiangular_indexed_points() {
+        assert_indexed_points(&EquiangularCubeSphereGrid::<(), 4>::default());
     }
-    
+
     #[test]
-    fn test_cube_from_neighbours_diagonals() {
-        let grid: CubeSphereGrid<u16, 10> = CubeSphereGrid::from_fn(|point| point.x);
+    fn test_distance_to_self() {
+        let point: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Left, 1, 3);
 
-        let grid2 = grid.map_neighbours_diagonals(|up_left, up, up_right, left, current, right, down_left, down, down_right| up_left + up + up_right + left + current + right + down_left + down + down_right);
+        assert_relative_eq!(0.0, point.distance_to(&point, 2.0));
+    }
 
-        assert_eq!(4 * 3 + 5 * 3 + 6 * 3, grid2[CubeSpherePoint::new(CubeFace::Front, 5, 3)])
+    #[test]
+    fn test_distance_to_pole() {
+        let front: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 2, 2);
+        let top: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Top, 2, 2);
+
+        assert_relative_eq!(PI, front.distance_to(&top, 2.0), epsilon = 1e-9);
+        assert_relative_eq!(PI, top.distance_to(&front, 2.0), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_latitude_0() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 0.0);
-        
-        println!("{:?}", point);
+    fn test_distance_to_opposite() {
+        let front: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 2, 2);
+        let back: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Back, 2, 2);
 
-        assert_relative_eq!(0.0, point.latitude(), epsilon = 0.01);
+        assert_relative_eq!(PI, front.distance_to(&back, 1.0), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_latitude_1() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.0, 0.0);
+    fn test_distance_to_matches_position() {
+        let point1: RectangleSpherePoint<40, 20> = RectangleSpherePoint::from_geographic(0.3, 1.0);
+        let point2: RectangleSpherePoint<40, 20> = RectangleSpherePoint::from_geographic(-0.7, 4.0);
 
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+        let (x1, y1, z1) = point1.position(1.0);
+        let (x2, y2, z2) = point2.position(1.0);
 
-        assert_relative_eq!(1.0, point.latitude(), epsilon = 0.01);
+        assert_relative_eq!((x1 * x2 + y1 * y2 + z1 * z2).acos(), point1.distance_to(&point2, 1.0), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_latitude_minus_1() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.0, 0.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_initial_bearing_north() {
+        let front: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 2, 2);
+        let top: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Top, 2, 2);
 
-        assert_relative_eq!(-1.0, point.latitude(), epsilon = 0.01);
+        assert_relative_eq!(0.0, front.initial_bearing_to(&top), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_latitude_half() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.5, 0.0);
+    fn test_initial_bearing_east_and_west() {
+        let front: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 2, 2);
+        let right: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Right, 2, 2);
 
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+        assert_relative_eq!(PI / 2.0, front.initial_bearing_to(&right), epsilon = 1e-9);
+        assert_relative_eq!(PI * 1.5, right.initial_bearing_to(&front), epsilon = 1e-9);
+    }
 
-        assert_relative_eq!(0.5, point.latitude(), epsilon = 0.01);
+    #[test]
+    fn test_initial_bearing_south() {
+        let front: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 2, 2);
+        let bottom: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Bottom, 2, 2);
+
+        assert_relative_eq!(PI, front.initial_bearing_to(&bottom), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_latitude_minus_half() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-0.5, 0.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_destination_cube() {
+        let front: CubeSpherePoint<5> = CubeSpherePoint::new(CubeFace::Front, 2, 2);
 
-        assert_relative_eq!(-0.5, point.latitude(), epsilon = 0.01);
+        assert_eq!(CubeSpherePoint::new(CubeFace::Top, 2, 2), front.destination(0.0, PI, 2.0));
+        assert_eq!(CubeSpherePoint::new(CubeFace::Right, 2, 2), front.destination(PI / 2.0, PI, 2.0));
+        assert_eq!(CubeSpherePoint::new(CubeFace::Left, 2, 2), front.destination(PI * 1.5, PI, 2.0));
+        assert_eq!(CubeSpherePoint::new(CubeFace::Back, 2, 2), front.destination(0.0, PI * 2.0, 2.0));
     }
-    
+
     #[test]
-    fn test_cube_point_latitude_0_far() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 3.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_destination_zero_distance() {
+        let point: DynRectangleSpherePoint = DynRectangleSphereGrid::<()>::new(40, 20).points().nth(123).unwrap();
 
-        assert_relative_eq!(0.0, point.latitude(), epsilon = 0.01);
+        assert_eq!(point, point.destination(1.0, 0.0, 1.0));
     }
-    
+
     #[test]
-    fn test_cube_point_latitude_1_far() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.0, 2.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_destination_distance() {
+        let grid: RectangleSphereGrid<(), 400, 200> = RectangleSphereGrid::default();
 
-        assert_relative_eq!(1.0, point.latitude(), epsilon = 0.1);
+        for point in grid.points().step_by(997) {
+            let destination = point.destination(2.0, 1.5, 3.0);
+
+            // The destination is moved to a corner of its cell so it can be up to two cells out.
+            assert_relative_eq!(1.5, point.distance_to(&destination, 3.0), epsilon = 0.1);
+        }
     }
-    
+
+    /// Checks that a query finds the same points as checking every point on the grid.
+    fn assert_same_points<P: SpherePoint + std::hash::Hash + std::fmt::Debug>(
+        found: impl Iterator<Item = P>, all: impl Iterator<Item = P>, inside: impl Fn(&P) -> bool
+    ) {
+        let found: Vec<_> = found.collect();
+        let unique: HashSet<_> = found.iter().cloned().collect();
+
+        assert_eq!(found.len(), unique.len());
+        assert_eq!(all.filter(inside).collect::<HashSet<_>>(), unique);
+    }
+
     #[test]
-    fn test_cube_point_latitude_minus_1_far() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.0, 3.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_points_within_distance_cube() {
+        let grid: CubeSphereGrid<(), 20> = CubeSphereGrid::default();
 
-        assert_relative_eq!(-1.0, point.latitude(), epsilon = 0.01);
+        for (centre, distance) in [
+            (CubeSpherePoint::new(CubeFace::Front, 3, 7), 0.4),
+            (CubeSpherePoint::new(CubeFace::Top, 0, 0), 0.3),
+            (CubeSpherePoint::new(CubeFace::Bottom, 10, 10), 1.2),
+            (CubeSpherePoint::new(CubeFace::Back, 19, 0), 0.01),
+        ] {
+            assert_same_points(
+                grid.points_within_distance(&centre, distance * 2.0, 2.0),
+                grid.points(),
+                |point| centre.distance_to(point, 1.0) <= distance,
+            );
+        }
     }
-    
+
     #[test]
-    fn test_cube_point_latitude_half_far() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.5, 5.0);
+    fn test_points_within_distance_everything() {
+        let grid: CubeSphereGrid<(), 5> = CubeSphereGrid::default();
 
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+        let centre = CubeSpherePoint::new(CubeFace::Left, 1, 1);
 
-        assert_relative_eq!(0.5, point.latitude(), epsilon = 0.01);
+        assert_eq!(150, grid.points_within_distance(&centre, 4.0, 1.0).count());
     }
-    
+
     #[test]
-    fn test_cube_point_latitude_minus_half_far() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-0.5, 3.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_points_within_distance_rect() {
+        let grid: RectangleSphereGrid<(), 80, 40> = RectangleSphereGrid::default();
 
-        assert_relative_eq!(-0.5, point.latitude(), epsilon = 0.01);
+        for centre in [RectangleSpherePoint::new(79, 20), RectangleSpherePoint::new(10, 0), RectangleSpherePoint::new(40, 39)] {
+            assert_same_points(
+                grid.points_within_distance(&centre, 0.5, 1.0),
+                grid.points(),
+                |point| centre.distance_to(point, 1.0) <= 0.5,
+            );
+        }
     }
-   
+
     #[test]
-    fn test_cube_point_longitude_0() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 0.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_points_in_box_cube() {
+        let grid: CubeSphereGrid<(), 20> = CubeSphereGrid::default();
 
-        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.01);
+        assert_same_points(
+            grid.points_in_box(-0.2, 0.5, 1.0, 2.0),
+            grid.points(),
+            |point| (-0.2..=0.5).contains(&point.latitude()) && (1.0..=2.0).contains(&point.longitude()),
+        );
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_1() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 1.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_points_in_box_antimeridian() {
+        let grid: CubeSphereGrid<(), 20> = CubeSphereGrid::default();
 
-        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.01);
+        let found: Vec<_> = grid.points_in_box(-0.3, 0.3, PI * 2.0 - 0.4, 0.4).collect();
+
+        assert!(found.contains(&CubeSpherePoint::from_geographic(0.0, 0.0)));
+
+        assert_same_points(
+            found.into_iter(),
+            grid.points(),
+            |point| (-0.3..=0.3).contains(&point.latitude()) && (point.longitude() <= 0.4 || point.longitude() >= PI * 2.0 - 0.4),
+        );
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_2() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 2.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_points_in_box_north_pole() {
+        let grid: CubeSphereGrid<(), 21> = CubeSphereGrid::default();
 
-        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.01);
+        // The point at the pole is inside every range of longitudes.
+        let found: Vec<_> = grid.points_in_box(1.0, PI / 2.0, 2.0, 2.5).collect();
+
+        assert!(found.contains(&CubeSpherePoint::new(CubeFace::Top, 10, 10)));
+
+        assert_same_points(
+            found.into_iter(),
+            grid.points(),
+            |point| point.latitude() >= 1.0 && ((2.0..=2.5).contains(&point.longitude()) || point.latitude() > 1.57),
+        );
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_3() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 3.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_points_in_box_south_cap() {
+        let grid: RectangleSphereGrid<(), 80, 40> = RectangleSphereGrid::default();
 
-        assert_relative_eq!(3.0, point.longitude(), epsilon = 0.01);
+        assert_same_points(
+            grid.points_in_box(-PI / 2.0, -1.2, 0.0, PI * 2.0),
+            grid.points(),
+            |point| point.latitude() <= -1.2,
+        );
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_4() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 4.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_points_in_box_smaller_than_cell() {
+        let grid: DynCubeSphereGrid<()> = DynCubeSphereGrid::new(10);
 
-        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.01);
+        for (south, west) in [(0.1, 0.1), (0.75, 0.75), (-0.35, 3.1)] {
+            assert_same_points(
+                grid.points_in_box(south, south + 0.01, west, west + 0.01),
+                grid.points(),
+                |point| (south..=south + 0.01).contains(&point.latitude()) && (west..=west + 0.01).contains(&point.longitude()),
+            );
+        }
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_5() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 5.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_points_in_box_cube_thin() {
+        let grid: CubeSphereGrid<(), 40> = CubeSphereGrid::default();
 
-        assert_relative_eq!(5.0, point.longitude(), epsilon = 0.01);
+        // Thin boxes cross rows of cells on the cube faces at an angle.
+        let mut boxes = vec![(-0.390, -0.356, 3.390, 0.607)];
+        boxes.extend((0..200).map(|i| {
+            let i = i as f64;
+            let south = (i * 0.731).rem_euclid(2.8) - 1.4;
+            let west = (i * 1.913).rem_euclid(2.0 * PI);
+
+            (south, south + 0.01 + (i * 0.37).rem_euclid(0.05), west, (west + 0.1 + (i * 2.71).rem_euclid(4.0)).rem_euclid(2.0 * PI))
+        }));
+
+        for (south, north, west, east) in boxes {
+            assert_same_points(
+                grid.points_in_box(south, north, west, east),
+                grid.points(),
+                |point| (south..=north).contains(&point.latitude())
+                    && (point.longitude() - west).rem_euclid(PI * 2.0) <= (east - west).rem_euclid(PI * 2.0),
+            );
+        }
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_6() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(0.0, 6.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_points_in_box_octa() {
+        let grid: OctaSphereGrid<(), 12> = OctaSphereGrid::default();
 
-        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.01);
+        assert_same_points(
+            grid.points_in_box(-1.0, 0.2, 5.0, 1.0),
+            grid.points(),
+            |point| (-1.0..=0.2).contains(&point.latitude()) && (point.longitude() <= 1.0 || point.longitude() >= 5.0),
+        );
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_0_north() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 0.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_rect_areas_total() {
+        let areas: RectangleSphereGrid<f64, 40, 20> = RectangleSphereGrid::areas(2.0);
 
-        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.01);
+        assert_relative_eq!(16.0 * PI, areas.iter().map(|(_, area)| area).sum::<f64>(), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_1_north() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 1.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_dyn_rect_areas_total() {
+        let areas = DynRectangleSphereGrid::areas(30, 15, 1.0);
 
-        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.01);
+        assert_relative_eq!(4.0 * PI, areas.iter().map(|(_, area)| area).sum::<f64>(), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_2_north() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 2.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_rect_solid_angle_matches_ellipsoid_area() {
+        let point: RectangleSpherePoint<40, 20> = RectangleSpherePoint::new(7, 3);
 
-        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.01);
+        assert_relative_eq!(point.ellipsoid_area(&Ellipsoid::sphere(3.0)), point.area(3.0), max_relative = 1e-9);
+        assert_relative_eq!(point.area(1.0), point.solid_angle());
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_4_north() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 4.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_rect_corners() {
+        let point: RectangleSpherePoint<4, 2> = RectangleSpherePoint::new(1, 0);
 
-        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.001);
+        let [north_west, north_east, south_east, south_west] = point.corners(2.0);
+
+        assert_relative_eq!(2.0, north_west.1, epsilon = 1e-12);
+        assert_relative_eq!(2.0, north_east.1, epsilon = 1e-12);
+        assert_relative_eq!(0.0, south_east.0, epsilon = 1e-12);
+        assert_relative_eq!(-2.0, south_east.2, epsilon = 1e-12);
+        assert_relative_eq!(2.0, south_west.0, epsilon = 1e-12);
+        assert_relative_eq!(0.0, south_west.1, epsilon = 1e-12);
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_6_north() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(-1.5, 6.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_cube_areas_total() {
+        let areas: CubeSphereGrid<f64, 10> = CubeSphereGrid::areas(2.0);
 
-        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.01);
+        assert_relative_eq!(16.0 * PI, areas.iter().map(|(_, area)| area).sum::<f64>(), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_0_south() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 0.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_equiangular_areas_total() {
+        let areas: EquiangularCubeSphereGrid<f64, 7> = EquiangularCubeSphereGrid::areas(1.0);
 
-        assert_relative_eq!(0.0, point.longitude(), epsilon = 0.01);
+        assert_relative_eq!(4.0 * PI, areas.iter().map(|(_, area)| area).sum::<f64>(), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_1_south() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 1.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_dyn_cube_areas_total() {
+        let areas = DynCubeSphereGrid::areas(9, 1.0);
 
-        assert_relative_eq!(1.0, point.longitude(), epsilon = 0.01);
+        assert_relative_eq!(4.0 * PI, areas.iter().map(|(_, area)| area).sum::<f64>(), epsilon = 1e-9);
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_2_south() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 2.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_cube_solid_angle_face() {
+        let point: CubeSpherePoint<1> = CubeSpherePoint::new(CubeFace::Right, 0, 0);
 
-        assert_relative_eq!(2.0, point.longitude(), epsilon = 0.01);
+        assert_relative_eq!(2.0 * PI / 3.0, point.solid_angle(), epsilon = 1e-12);
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_4_south() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 4.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_cube_solid_angle_matches_ellipsoid_area() {
+        let grid: CubeSphereGrid<(), 6> = CubeSphereGrid::default();
 
-        assert_relative_eq!(4.0, point.longitude(), epsilon = 0.01);
+        for point in grid.points() {
+            assert_relative_eq!(point.ellipsoid_area(&Ellipsoid::sphere(1.0)), point.solid_angle(), max_relative = 1e-3);
+        }
     }
-    
+
     #[test]
-    fn test_cube_point_longitude_6_south() {
-        let point: CubeSpherePoint<32000> = CubeSpherePoint::from_geographic(1.5, 6.0);
-        
-        println!("{:?}", point);
-        println!("{:?}", point.position(1.0));
+    fn test_cube_corners_face() {
+        let point: CubeSpherePoint<1> = CubeSpherePoint::new(CubeFace::Front, 0, 0);
 
-        assert_relative_eq!(6.0, point.longitude(), epsilon = 0.01);
+        let length = 3.0_f64.sqrt();
+
+        for (x, y, z) in point.corners(length) {
+            assert_relative_eq!(1.0, x.abs(), epsilon = 1e-12);
+            assert_relative_eq!(1.0, y.abs(), epsilon = 1e-12);
+            assert_relative_eq!(1.0, z, epsilon = 1e-12);
+        }
+    }
+
+    /// Checks that the corners of a cell are in clockwise order when viewed from outside.
+    fn assert_clockwise(corners: [(f64, f64, f64); 4], (x, y, z): (f64, f64, f64)) {
+        let diagonal1 = (corners[2].0 - corners[0].0, corners[2].1 - corners[0].1, corners[2].2 - corners[0].2);
+        let diagonal2 = (corners[3].0 - corners[1].0, corners[3].1 - corners[1].1, corners[3].2 - corners[1].2);
+
+        let normal = (
+            diagonal1.1 * diagonal2.2 - diagonal1.2 * diagonal2.1,
+            diagonal1.2 * diagonal2.0 - diagonal1.0 * diagonal2.2,
+            diagonal1.0 * diagonal2.1 - diagonal1.1 * diagonal2.0,
+        );
+
+        assert!(normal.0 * x + normal.1 * y + normal.2 * z < 0.0);
     }
 
     #[test]
-    fn test_cube_clone_128() {
-        let grid: CubeSphereGrid<u64, 128> = CubeSphereGrid::default();
+    fn test_rect_corners_clockwise() {
+        let grid: RectangleSphereGrid<(), 8, 6> = RectangleSphereGrid::default();
 
-        assert_eq!(grid, black_box(grid.clone()));
+        for point in grid.points() {
+            let (latitude, longitude) = (point.latitude() - PI / 12.0, point.longitude() + PI / 8.0);
+
+            assert_clockwise(point.corners(1.0), (latitude.cos() * longitude.sin(), latitude.sin(), latitude.cos() * longitude.cos()));
+        }
     }
-    
+
     #[test]
-    fn test_cube_clone_128_large_struct() {
-        let grid: CubeSphereGrid<[u8; 1024], 128> = CubeSphereGrid::from_fn(|_| [0b11101101; 1024]);
+    fn test_cube_corners_clockwise() {
+        let grid: CubeSphereGrid<(), 3> = CubeSphereGrid::default();
 
-        assert_eq!(grid, black_box(grid.clone()));
+        for point in grid.points() {
+            assert_clockwise(point.corners(1.0), point.position(1.0));
+        }
     }
-    
+
     #[test]
-    fn test_cube_clone_4096() {
-        let grid: CubeSphereGrid<u64, 4096> = CubeSphereGrid::default();
+    fn test_cube_corners_shared() {
+        let point: EquiangularCubeSphereGrid<(), 5> = EquiangularCubeSphereGrid::default();
 
-        assert_eq!(grid, black_box(grid.clone()));
+        for point in point.points().filter(|point| point.x() < 4 && point.y() < 4 && point.face() != CubeFace::Back) {
+            let corners = point.corners(1.0);
+            let right = point.right().corners(1.0);
+            let down = point.down().corners(1.0);
+
+            assert_relative_eq!(corners[1].0, right[0].0, epsilon = 1e-12);
+            assert_relative_eq!(corners[2].1, right[3].1, epsilon = 1e-12);
+            assert_relative_eq!(corners[3].2, down[0].2, epsilon = 1e-12);
+        }
     }
-}
 
+    #[test]
+    fn test_dyn_cube_corners_surround_position() {
+        let grid: DynCubeSphereGrid<()> = DynCubeSphereGrid::new(4);
+
+        for point in grid.points() {
+            let (x, y, z) = point.position(1.0);
+
+            for (cx, cy, cz) in point.corners(1.0) {
+                assert!(x * cx + y * cy + z * cz > 0.9);
+            }
+        }
+    }
+}
@@ -0,0 +1,435 @@
+//! Conversion between cube sphere grids and cubemap images.
+//!
+//! A cubemap looks up a direction from the centre of the cube, using the same axes as
+//! `GridPoint::position`: +X is at longitude π/2, +Y is the north pole and +Z is at longitude 0.
+//! Each face image is oriented as in OpenGL, so the faces can be passed to graphics APIs and
+//! environment map tools directly.
+//! The cells are copied without resampling, so a grid with a projection other than `Gnomonic`
+//! gives a cubemap with the same spacing.
+
+use std::{error::Error, fmt::{self, Debug, Display, Formatter}};
+
+use crate::{storage::Storage, StaticSurfaceGrid, SurfaceGrid};
+
+use super::{CubeFace, CubeProjection, CubeSphereGrid, CubeSpherePoint, PointError};
+
+/// A face of a cubemap named by the axis that it faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CubemapFace {
+    /// The face looking towards longitude π/2.
+    PositiveX,
+    /// The face looking towards longitude 3π/2.
+    NegativeX,
+    /// The face looking towards the north pole.
+    PositiveY,
+    /// The face looking towards the south pole.
+    NegativeY,
+    /// The face looking towards longitude 0.
+    PositiveZ,
+    /// The face looking towards longitude π.
+    NegativeZ,
+}
+
+impl CubemapFace {
+    /// The faces in the order used by OpenGL.
+    pub const ALL: [CubemapFace; 6] = [
+        CubemapFace::PositiveX,
+        CubemapFace::NegativeX,
+        CubemapFace::PositiveY,
+        CubemapFace::NegativeY,
+        CubemapFace::PositiveZ,
+        CubemapFace::NegativeZ,
+    ];
+
+    /// Gets the face of a cube sphere grid that this face shows.
+    pub fn cube_face(&self) -> CubeFace {
+        match self {
+            Self::PositiveX => CubeFace::Right,
+            Self::NegativeX => CubeFace::Left,
+            Self::PositiveY => CubeFace::Top,
+            Self::NegativeY => CubeFace::Bottom,
+            Self::PositiveZ => CubeFace::Front,
+            Self::NegativeZ => CubeFace::Back,
+        }
+    }
+
+    /// Gets the cubemap face that shows a face of a cube sphere grid.
+    ///
+    /// - `face` - The face of the grid.
+    pub fn from_cube_face(face: CubeFace) -> Self {
+        match face {
+            CubeFace::Right => Self::PositiveX,
+            CubeFace::Left => Self::NegativeX,
+            CubeFace::Top => Self::PositiveY,
+            CubeFace::Bottom => Self::NegativeY,
+            CubeFace::Front => Self::PositiveZ,
+            CubeFace::Back => Self::NegativeZ,
+        }
+    }
+
+    /// Gets the position of this face in the order used by OpenGL.
+    fn index(&self) -> usize {
+        Self::ALL.iter()
+            .position(|face| face == self)
+            .expect("All faces are listed")
+    }
+}
+
+/// A way of arranging the six faces of a cubemap in a single image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CubemapLayout {
+    /// The faces are placed one below another in the order +X, −X, +Y, −Y, +Z, −Z, as uploaded
+    /// to OpenGL.
+    /// The image is one face wide and six faces tall.
+    #[default]
+    Stacked,
+    /// The faces are unfolded into a cross with +Y above and −Y below the row −X, +Z, +X, −Z.
+    /// The image is four faces wide and three faces tall, and the unused corners are filled with
+    /// a background value.
+    HorizontalCross,
+}
+
+impl CubemapLayout {
+    /// Gets the width and height of an image in this layout.
+    ///
+    /// - `size` - The size of each side of each face.
+    pub fn dimensions(&self, size: usize) -> (usize, usize) {
+        match self {
+            Self::Stacked => (size, 6 * size),
+            Self::HorizontalCross => (4 * size, 3 * size),
+        }
+    }
+
+    /// Gets the position of the top left corner of a face in an image in this layout measured in
+    /// faces.
+    ///
+    /// - `face` - The face to find.
+    fn offset(&self, face: CubemapFace) -> (usize, usize) {
+        match self {
+            Self::Stacked => (0, face.index()),
+            Self::HorizontalCross => match face {
+                CubemapFace::PositiveY => (1, 0),
+                CubemapFace::NegativeX => (0, 1),
+                CubemapFace::PositiveZ => (1, 1),
+                CubemapFace::PositiveX => (2, 1),
+                CubemapFace::NegativeZ => (3, 1),
+                CubemapFace::NegativeY => (1, 2),
+            },
+        }
+    }
+}
+
+/// An error from reading a grid from a cubemap with the wrong size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CubemapError {
+    /// The image does not have the dimensions of its layout for the grid.
+    DimensionMismatch {
+        /// The expected width and height.
+        expected: (usize, usize),
+        /// The width and height of the image.
+        found: (usize, usize),
+    },
+    /// The number of values does not match the dimensions of the image.
+    LengthMismatch {
+        /// The expected number of values.
+        expected: usize,
+        /// The number of values found.
+        found: usize,
+    },
+}
+
+impl Display for CubemapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DimensionMismatch { expected, found } => write!(
+                f, "Expected a cubemap of {}x{} but found {}x{}", expected.0, expected.1, found.0, found.1
+            ),
+            Self::LengthMismatch { expected, found } => write!(f, "Expected {} values but found {}", expected, found),
+        }
+    }
+}
+
+impl Error for CubemapError {}
+
+impl <const S: usize, P: CubeProjection> CubeSpherePoint<S, P> {
+    /// Gets the cubemap face and the pixel on that face at this point.
+    /// The pixel is given as its column and row from the top left corner of the face.
+    pub fn cubemap_pixel(&self) -> (CubemapFace, usize, usize) {
+        let (x, y) = (self.x as usize, self.y as usize);
+
+        let (x, y) = if self.face == CubeFace::Back {
+            // The back face is the only one that is turned around in a cubemap.
+            (S - 1 - x, S - 1 - y)
+        } else {
+            (x, y)
+        };
+
+        (CubemapFace::from_cube_face(self.face), x, y)
+    }
+
+    /// Gets the point at a pixel on a cubemap face.
+    /// Returns an error if the pixel is outside the face.
+    ///
+    /// - `face` - The cubemap face.
+    /// - `x` - The column of the pixel from the left of the face.
+    /// - `y` - The row of the pixel from the top of the face.
+    pub fn from_cubemap_pixel(face: CubemapFace, x: usize, y: usize) -> Result<Self, PointError> {
+        PointError::check(x, y, S, S)?;
+
+        let face = face.cube_face();
+
+        if face == CubeFace::Back {
+            Self::try_new(face, S - 1 - x, S - 1 - y)
+        } else {
+            Self::try_new(face, x, y)
+        }
+    }
+}
+
+impl <T: Clone + Debug, const S: usize, P: CubeProjection, D: Storage<T>> CubeSphereGrid<T, S, P, D> {
+    /// Gets the six faces of a cubemap holding the values of this grid.
+    /// The faces are in the order of `CubemapFace::ALL` and each is stored row by row.
+    pub fn to_cubemap_faces(&self) -> [Vec<T>; 6] {
+        CubemapFace::ALL.map(|face| {
+            (0..S * S)
+                .map(|i| self[CubeSpherePoint::from_cubemap_pixel(face, i % S, i / S).expect("The pixel is on the face")].clone())
+                .collect()
+        })
+    }
+
+    /// Creates a new grid from the six faces of a cubemap.
+    /// Returns an error if a face does not hold one value for each cell of a face.
+    ///
+    /// - `faces` - The faces in the order of `CubemapFace::ALL`, each stored row by row.
+    pub fn from_cubemap_faces<F: AsRef<[T]>>(faces: &[F; 6]) -> Result<Self, CubemapError> {
+        for face in faces {
+            let found = face.as_ref().len();
+
+            if found != S * S {
+                return Err(CubemapError::LengthMismatch { expected: S * S, found });
+            }
+        }
+
+        Ok(Self::from_fn(|point| {
+            let (face, x, y) = point.cubemap_pixel();
+
+            faces[face.index()].as_ref()[y * S + x].clone()
+        }))
+    }
+
+    /// Creates a cubemap image holding the values of this grid.
+    /// The image is stored row by row and has the dimensions given by `CubemapLayout::dimensions`.
+    ///
+    /// - `layout` - The arrangement of the faces.
+    /// - `background` - The value of pixels that are not on any face.
+    pub fn to_cubemap(&self, layout: CubemapLayout, background: T) -> Vec<T> {
+        let (width, height) = layout.dimensions(S);
+
+        let mut image = vec![background; width * height];
+
+        for (point, value) in self.iter() {
+            let (face, x, y) = point.cubemap_pixel();
+            let (column, row) = layout.offset(face);
+
+            image[(row * S + y) * width + column * S + x] = value.clone();
+        }
+
+        image
+    }
+
+    /// Creates a new grid from a cubemap image.
+    /// Returns an error if the image does not have the dimensions given by
+    /// `CubemapLayout::dimensions`.
+    ///
+    /// - `layout` - The arrangement of the faces.
+    /// - `width` - The width of the image.
+    /// - `height` - The height of the image.
+    /// - `image` - The values of the image stored row by row.
+    pub fn from_cubemap(layout: CubemapLayout, width: usize, height: usize, image: &[T]) -> Result<Self, CubemapError> {
+        let expected = layout.dimensions(S);
+
+        if expected != (width, height) {
+            return Err(CubemapError::DimensionMismatch { expected, found: (width, height) });
+        }
+
+        if image.len() != width * height {
+            return Err(CubemapError::LengthMismatch { expected: width * height, found: image.len() });
+        }
+
+        Ok(Self::from_fn(|point| {
+            let (face, x, y) = point.cubemap_pixel();
+            let (column, row) = layout.offset(face);
+
+            image[(row * S + y) * width + column * S + x].clone()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{sphere::{CubeFace, CubeSphereGrid, CubeSpherePoint, EquiangularCubeSphereGrid}, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    use super::{CubemapError, CubemapFace, CubemapLayout};
+
+    /// Gets the direction looked up by a position on a cubemap face following the OpenGL
+    /// specification.
+    fn cubemap_direction(face: CubemapFace, s: f64, t: f64) -> (f64, f64, f64) {
+        let sc = 2.0 * s - 1.0;
+        let tc = 2.0 * t - 1.0;
+
+        let (x, y, z) = match face {
+            CubemapFace::PositiveX => (1.0, -tc, -sc),
+            CubemapFace::NegativeX => (-1.0, -tc, sc),
+            CubemapFace::PositiveY => (sc, 1.0, tc),
+            CubemapFace::NegativeY => (sc, -1.0, -tc),
+            CubemapFace::PositiveZ => (sc, -tc, 1.0),
+            CubemapFace::NegativeZ => (-sc, -tc, -1.0),
+        };
+
+        let length = (x * x + y * y + z * z).sqrt();
+
+        (x / length, y / length, z / length)
+    }
+
+    #[test]
+    fn test_cubemap_pixel_direction() {
+        const S: usize = 6;
+
+        for point in CubeSphereGrid::<(), S>::default().points() {
+            let (face, x, y) = point.cubemap_pixel();
+
+            let (a, b, c) = cubemap_direction(face, (x as f64 + 0.5) / S as f64, (y as f64 + 0.5) / S as f64);
+            let (d, e, f) = point.position(1.0);
+
+            assert_relative_eq!(a, d, epsilon = 1e-9);
+            assert_relative_eq!(b, e, epsilon = 1e-9);
+            assert_relative_eq!(c, f, epsilon = 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_cubemap_pixel_round_trip() {
+        for point in CubeSphereGrid::<(), 5>::default().points() {
+            let (face, x, y) = point.cubemap_pixel();
+
+            assert_eq!(point, CubeSpherePoint::from_cubemap_pixel(face, x, y).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_cubemap_pixel_out_of_range() {
+        assert!(CubeSpherePoint::<4>::from_cubemap_pixel(CubemapFace::NegativeZ, 4, 0).is_err());
+        assert!(CubeSpherePoint::<4>::from_cubemap_pixel(CubemapFace::NegativeZ, 0, 4).is_err());
+    }
+
+    #[test]
+    fn test_cubemap_face_cube_face() {
+        for face in CubemapFace::ALL {
+            assert_eq!(face, CubemapFace::from_cube_face(face.cube_face()));
+        }
+
+        assert_eq!(CubeFace::Top, CubemapFace::PositiveY.cube_face());
+    }
+
+    #[test]
+    fn test_cubemap_faces_round_trip() {
+        let grid: CubeSphereGrid<usize, 4> = CubeSphereGrid::from_fn(|point| point.to_index());
+
+        let faces = grid.to_cubemap_faces();
+
+        assert!(faces.iter().all(|face| face.len() == 16));
+        assert_eq!(grid, CubeSphereGrid::from_cubemap_faces(&faces).unwrap());
+    }
+
+    #[test]
+    fn test_cubemap_faces_top_left() {
+        let grid: CubeSphereGrid<(CubeFace, usize, usize), 3> = CubeSphereGrid::from_fn(|point| (point.face(), point.x(), point.y()));
+
+        let faces = grid.to_cubemap_faces();
+
+        assert_eq!((CubeFace::Right, 0, 0), faces[0][0]);
+        assert_eq!((CubeFace::Front, 1, 0), faces[4][1]);
+        assert_eq!((CubeFace::Back, 2, 2), faces[5][0]);
+        assert_eq!((CubeFace::Back, 2, 1), faces[5][3]);
+    }
+
+    #[test]
+    fn test_cubemap_faces_wrong_length() {
+        let mut faces: [Vec<u8>; 6] = Default::default();
+        faces.iter_mut().for_each(|face| *face = vec![0; 9]);
+        faces[3].pop();
+
+        assert_eq!(
+            Err(CubemapError::LengthMismatch { expected: 9, found: 8 }),
+            CubeSphereGrid::<u8, 3>::from_cubemap_faces(&faces)
+        );
+    }
+
+    #[test]
+    fn test_cubemap_stacked_matches_faces() {
+        let grid: EquiangularCubeSphereGrid<u32, 3> = EquiangularCubeSphereGrid::from_fn(|point| point.to_index() as u32);
+
+        let image = grid.to_cubemap(CubemapLayout::Stacked, 0);
+
+        assert_eq!(grid.to_cubemap_faces().concat(), image);
+        assert_eq!(grid, EquiangularCubeSphereGrid::from_cubemap(CubemapLayout::Stacked, 3, 18, &image).unwrap());
+    }
+
+    #[test]
+    fn test_cubemap_cross_round_trip() {
+        let grid: CubeSphereGrid<i32, 5> = CubeSphereGrid::from_fn(|point| point.to_index() as i32);
+
+        let image = grid.to_cubemap(CubemapLayout::HorizontalCross, -1);
+
+        assert_eq!(20 * 15, image.len());
+        assert_eq!(6 * 25, image.iter().filter(|value| **value >= 0).count());
+        assert_eq!(grid, CubeSphereGrid::from_cubemap(CubemapLayout::HorizontalCross, 20, 15, &image).unwrap());
+    }
+
+    #[test]
+    fn test_cubemap_cross_continuous() {
+        const S: usize = 8;
+
+        let grid: CubeSphereGrid<(f64, f64, f64), S> = CubeSphereGrid::from_fn(|point| point.position(1.0));
+
+        let image = grid.to_cubemap(CubemapLayout::HorizontalCross, (0.0, 0.0, 0.0));
+        let (width, _) = CubemapLayout::HorizontalCross.dimensions(S);
+
+        // Neighbouring cells are no further apart than the cells at the centre of a face.
+        let close = |a: (f64, f64, f64), b: (f64, f64, f64)| {
+            let distance = ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2) + (a.2 - b.2).powi(2)).sqrt();
+
+            distance < 2.0 / S as f64
+        };
+
+        // Pixels next to each other along the middle row are next to each other on the sphere.
+        for y in S..2 * S {
+            for x in 0..width - 1 {
+                assert!(close(image[y * width + x], image[y * width + x + 1]));
+            }
+        }
+
+        // The same is true down the middle column.
+        for y in 0..3 * S - 1 {
+            for x in S..2 * S {
+                assert!(close(image[y * width + x], image[(y + 1) * width + x]));
+            }
+        }
+    }
+
+    #[test]
+    fn test_cubemap_wrong_dimensions() {
+        let image = vec![0.0; 12 * 16];
+
+        assert_eq!(
+            Err(CubemapError::DimensionMismatch { expected: (16, 12), found: (12, 16) }),
+            CubeSphereGrid::<f64, 4>::from_cubemap(CubemapLayout::HorizontalCross, 12, 16, &image)
+        );
+        assert_eq!(
+            Err(CubemapError::LengthMismatch { expected: 16 * 12, found: 12 * 16 - 1 }),
+            CubeSphereGrid::<f64, 4>::from_cubemap(CubemapLayout::HorizontalCross, 16, 12, &image[1..])
+        );
+    }
+}
@@ -0,0 +1,456 @@
+//! The geometry of the oblate ellipsoids used to model the shape of planets.
+
+use std::f64::consts::PI;
+
+/// An oblate ellipsoid of revolution around the north-south axis.
+///
+/// Latitudes used with an ellipsoid are geodetic latitudes, which are the angle between the
+/// equator and the normal to the surface.
+/// The latitudes and longitudes of sphere grid points are treated as geodetic coordinates, so
+/// geospatial data can be placed on any sphere grid without latitude errors.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ellipsoid {
+    /// The radius at the equator.
+    semi_major_axis: f64,
+    /// The relative difference between the equatorial and polar radii.
+    flattening: f64,
+}
+
+impl Ellipsoid {
+    /// The World Geodetic System 1984 ellipsoid in metres.
+    pub const WGS84: Self = Self {
+        semi_major_axis: 6_378_137.0,
+        flattening: 1.0 / 298.257_223_563,
+    };
+
+    /// Creates a new `Ellipsoid`.
+    ///
+    /// - `semi_major_axis` - The radius at the equator.
+    /// - `flattening` - The relative difference between the equatorial and polar radii.
+    pub const fn new(semi_major_axis: f64, flattening: f64) -> Self {
+        Self {
+            semi_major_axis,
+            flattening,
+        }
+    }
+
+    /// Creates a sphere with the specified radius.
+    ///
+    /// - `radius` - The radius of the sphere.
+    pub const fn sphere(radius: f64) -> Self {
+        Self::new(radius, 0.0)
+    }
+
+    /// Gets the radius at the equator.
+    pub fn semi_major_axis(&self) -> f64 {
+        self.semi_major_axis
+    }
+
+    /// Gets the radius at the poles.
+    pub fn semi_minor_axis(&self) -> f64 {
+        self.semi_major_axis * (1.0 - self.flattening)
+    }
+
+    /// Gets the relative difference between the equatorial and polar radii.
+    pub fn flattening(&self) -> f64 {
+        self.flattening
+    }
+
+    /// Gets the square of the first eccentricity.
+    pub fn eccentricity_squared(&self) -> f64 {
+        self.flattening * (2.0 - self.flattening)
+    }
+
+    /// Gets the radius of curvature in the prime vertical at the specified latitude.
+    ///
+    /// - `latitude` - The geodetic latitude in radians.
+    fn prime_vertical_radius(&self, latitude: f64) -> f64 {
+        self.semi_major_axis / (1.0 - self.eccentricity_squared() * latitude.sin().powi(2)).sqrt()
+    }
+
+    /// Converts geodetic coordinates into earth centred, earth fixed coordinates.
+    /// The X axis points to latitude 0 and longitude 0, the Y axis points to latitude 0 and
+    /// longitude π/2 and the Z axis points to the north pole.
+    ///
+    /// - `latitude` - The geodetic latitude in radians.
+    /// - `longitude` - The longitude in radians.
+    /// - `height` - The height above the surface of the ellipsoid.
+    pub fn geodetic_to_ecef(&self, latitude: f64, longitude: f64, height: f64) -> (f64, f64, f64) {
+        let radius = self.prime_vertical_radius(latitude);
+
+        let horizontal = (radius + height) * latitude.cos();
+
+        (
+            horizontal * longitude.cos(),
+            horizontal * longitude.sin(),
+            (radius * (1.0 - self.eccentricity_squared()) + height) * latitude.sin(),
+        )
+    }
+
+    /// Converts earth centred, earth fixed coordinates into geodetic coordinates.
+    /// Returns the geodetic latitude, the longitude in the range 0 to 2π and the height above the
+    /// surface of the ellipsoid.
+    ///
+    /// - `x` - The X coordinate.
+    /// - `y` - The Y coordinate.
+    /// - `z` - The Z coordinate.
+    pub fn ecef_to_geodetic(&self, x: f64, y: f64, z: f64) -> (f64, f64, f64) {
+        let eccentricity_squared = self.eccentricity_squared();
+
+        let distance = (x * x + y * y).sqrt();
+        let longitude = y.atan2(x).rem_euclid(2.0 * PI);
+
+        let mut latitude = z.atan2(distance * (1.0 - eccentricity_squared));
+
+        for _ in 0..10 {
+            let radius = self.prime_vertical_radius(latitude);
+            let next = (z + eccentricity_squared * radius * latitude.sin()).atan2(distance);
+
+            let done = (next - latitude).abs() < 1e-15;
+
+            latitude = next;
+
+            if done {
+                break;
+            }
+        }
+
+        // This form stays accurate at the poles where the horizontal distance vanishes.
+        let height = distance * latitude.cos() + z * latitude.sin()
+            - self.semi_major_axis * (1.0 - eccentricity_squared * latitude.sin().powi(2)).sqrt();
+
+        (latitude, longitude, height)
+    }
+
+    /// Gets the length of the shortest path along the surface between two points.
+    /// Nearly antipodal points, for which the geodesic cannot be found, use the distance on a
+    /// sphere with the mean radius of the ellipsoid.
+    ///
+    /// - `latitude1` - The geodetic latitude of the first point in radians.
+    /// - `longitude1` - The longitude of the first point in radians.
+    /// - `latitude2` - The geodetic latitude of the second point in radians.
+    /// - `longitude2` - The longitude of the second point in radians.
+    pub fn distance(&self, latitude1: f64, longitude1: f64, latitude2: f64, longitude2: f64) -> f64 {
+        // Vincenty's inverse formula.
+        let a = self.semi_major_axis;
+        let b = self.semi_minor_axis();
+        let f = self.flattening;
+
+        let reduced1 = ((1.0 - f) * latitude1.sin()).atan2(latitude1.cos());
+        let reduced2 = ((1.0 - f) * latitude2.sin()).atan2(latitude2.cos());
+
+        let (sin_u1, cos_u1) = reduced1.sin_cos();
+        let (sin_u2, cos_u2) = reduced2.sin_cos();
+
+        let difference = longitude2 - longitude1;
+        let mut lambda = difference;
+
+        for _ in 0..200 {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+
+            let sin_sigma = ((cos_u2 * sin_lambda).powi(2)
+                + (cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda).powi(2)).sqrt();
+
+            if sin_sigma == 0.0 {
+                return 0.0;
+            }
+
+            let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            let sigma = sin_sigma.atan2(cos_sigma);
+
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+
+            let cos_2sigma_m = if cos2_alpha == 0.0 {
+                // Both points lie on the equator.
+                0.0
+            } else {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+            };
+
+            let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+
+            let previous = lambda;
+            lambda = difference + (1.0 - c) * f * sin_alpha
+                * (sigma + c * sin_sigma * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+
+            if (lambda - previous).abs() < 1e-12 {
+                let u2 = cos2_alpha * (a * a - b * b) / (b * b);
+
+                let big_a = 1.0 + u2 / 16384.0 * (4096.0 + u2 * (-768.0 + u2 * (320.0 - 175.0 * u2)));
+                let big_b = u2 / 1024.0 * (256.0 + u2 * (-128.0 + u2 * (74.0 - 47.0 * u2)));
+
+                let delta_sigma = big_b * sin_sigma * (cos_2sigma_m + big_b / 4.0
+                    * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)
+                       - big_b / 6.0 * cos_2sigma_m * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                       * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)));
+
+                return b * big_a * (sigma - delta_sigma);
+            }
+        }
+
+        let angle = (latitude1.sin() * latitude2.sin()
+            + latitude1.cos() * latitude2.cos() * difference.cos()).clamp(-1.0, 1.0).acos();
+
+        angle * (2.0 * a + b) / 3.0
+    }
+
+    /// Gets `q`, the function of latitude that is proportional to the area between the equator
+    /// and that latitude.
+    ///
+    /// - `latitude` - The geodetic latitude in radians.
+    fn area_function(&self, latitude: f64) -> f64 {
+        let eccentricity_squared = self.eccentricity_squared();
+        let sin = latitude.sin();
+
+        if eccentricity_squared == 0.0 {
+            return 2.0 * sin;
+        }
+
+        let eccentricity = eccentricity_squared.sqrt();
+
+        (1.0 - eccentricity_squared) * (sin / (1.0 - eccentricity_squared * sin * sin)
+            - ((1.0 - eccentricity * sin) / (1.0 + eccentricity * sin)).ln() / (2.0 * eccentricity))
+    }
+
+    /// Gets the radius of the sphere with the same surface area as this ellipsoid.
+    pub fn authalic_radius(&self) -> f64 {
+        self.semi_major_axis * (self.area_function(PI / 2.0) / 2.0).sqrt()
+    }
+
+    /// Gets the authalic latitude for a geodetic latitude.
+    /// Mapping every point to its authalic latitude on a sphere with the authalic radius
+    /// preserves area.
+    ///
+    /// - `latitude` - The geodetic latitude in radians.
+    pub fn authalic_latitude(&self, latitude: f64) -> f64 {
+        (self.area_function(latitude) / self.area_function(PI / 2.0)).clamp(-1.0, 1.0).asin()
+    }
+
+    /// Gets the surface area of this ellipsoid.
+    pub fn area(&self) -> f64 {
+        2.0 * PI * self.semi_major_axis * self.semi_major_axis * self.area_function(PI / 2.0)
+    }
+
+    /// Gets the area of the region between two latitudes and spanning a range of longitudes.
+    ///
+    /// - `south` - The geodetic latitude of the southern edge in radians.
+    /// - `north` - The geodetic latitude of the northern edge in radians.
+    /// - `longitude_span` - The range of longitudes covered in radians.
+    pub fn quadrangle_area(&self, south: f64, north: f64, longitude_span: f64) -> f64 {
+        longitude_span * self.semi_major_axis * self.semi_major_axis
+            * (self.area_function(north) - self.area_function(south)) / 2.0
+    }
+
+    /// Gets the area of a convex polygon with edges short enough that they are close to straight
+    /// on the authalic sphere.
+    ///
+    /// - `corners` - The geodetic latitude and longitude of each corner in order.
+    pub(crate) fn polygon_area(&self, corners: &[(f64, f64)]) -> f64 {
+        let points: Vec<_> = corners.iter()
+            .map(|&(latitude, longitude)| {
+                let latitude = self.authalic_latitude(latitude);
+
+                (latitude.cos() * longitude.cos(), latitude.cos() * longitude.sin(), latitude.sin())
+            })
+            .collect();
+
+        let radius = self.authalic_radius();
+
+        (1..points.len().saturating_sub(1))
+            .map(|i| triangle_solid_angle(points[0], points[i], points[i + 1]))
+            .sum::<f64>() * radius * radius
+    }
+}
+
+impl Default for Ellipsoid {
+    fn default() -> Self {
+        Self::WGS84
+    }
+}
+
+/// Gets the solid angle of the triangle on the unit sphere with the specified corners.
+///
+/// - `a` - The first corner.
+/// - `b` - The second corner.
+/// - `c` - The third corner.
+fn triangle_solid_angle(a: (f64, f64, f64), b: (f64, f64, f64), c: (f64, f64, f64)) -> f64 {
+    let dot = |u: (f64, f64, f64), v: (f64, f64, f64)| u.0 * v.0 + u.1 * v.1 + u.2 * v.2;
+
+    let cross = (
+        b.1 * c.2 - b.2 * c.1,
+        b.2 * c.0 - b.0 * c.2,
+        b.0 * c.1 - b.1 * c.0,
+    );
+
+    2.0 * dot(a, cross).abs().atan2(1.0 + dot(a, b) + dot(b, c) + dot(c, a))
+}
+
+#[cfg(test)]
+mod test {
+    use std::f64::consts::PI;
+
+    use approx::assert_relative_eq;
+
+    use super::Ellipsoid;
+
+    #[test]
+    fn test_wgs84_axes() {
+        let ellipsoid = Ellipsoid::default();
+
+        assert_relative_eq!(6_378_137.0, ellipsoid.semi_major_axis());
+        assert_relative_eq!(6_356_752.314_245, ellipsoid.semi_minor_axis(), epsilon = 1e-6);
+        assert_relative_eq!(0.006_694_379_990_14, ellipsoid.eccentricity_squared(), epsilon = 1e-14);
+    }
+
+    #[test]
+    fn test_ecef_equator() {
+        let (x, y, z) = Ellipsoid::WGS84.geodetic_to_ecef(0.0, 0.0, 0.0);
+
+        assert_relative_eq!(6_378_137.0, x, epsilon = 1e-6);
+        assert_relative_eq!(0.0, y, epsilon = 1e-6);
+        assert_relative_eq!(0.0, z, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_ecef_east() {
+        let (x, y, z) = Ellipsoid::WGS84.geodetic_to_ecef(0.0, PI / 2.0, 100.0);
+
+        assert_relative_eq!(0.0, x, epsilon = 1e-6);
+        assert_relative_eq!(6_378_237.0, y, epsilon = 1e-6);
+        assert_relative_eq!(0.0, z, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_ecef_north_pole() {
+        let ellipsoid = Ellipsoid::WGS84;
+
+        let (x, y, z) = ellipsoid.geodetic_to_ecef(PI / 2.0, 0.0, 0.0);
+
+        assert_relative_eq!(0.0, x, epsilon = 1e-6);
+        assert_relative_eq!(0.0, y, epsilon = 1e-6);
+        assert_relative_eq!(ellipsoid.semi_minor_axis(), z, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_ecef_inverse() {
+        let ellipsoid = Ellipsoid::WGS84;
+
+        for (latitude, longitude, height) in [
+            (0.0, 0.0, 0.0),
+            (0.7, 2.5, 1000.0),
+            (-1.2, 4.0, -50.0),
+            (1.57, 0.1, 8848.0),
+            (-PI / 2.0, 0.0, 10.0),
+        ] {
+            let (x, y, z) = ellipsoid.geodetic_to_ecef(latitude, longitude, height);
+            let (latitude2, longitude2, height2) = ellipsoid.ecef_to_geodetic(x, y, z);
+
+            assert_relative_eq!(latitude, latitude2, epsilon = 1e-12);
+            assert_relative_eq!(height, height2, epsilon = 1e-6);
+
+            if latitude.abs() < PI / 2.0 {
+                assert_relative_eq!(longitude, longitude2, epsilon = 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ecef_to_geodetic_known() {
+        // Geodetic latitude is larger than geocentric latitude in the northern hemisphere.
+        let ellipsoid = Ellipsoid::WGS84;
+
+        let (x, y, z) = ellipsoid.geodetic_to_ecef(PI / 4.0, 0.0, 0.0);
+
+        assert!(z.atan2((x * x + y * y).sqrt()) < PI / 4.0 - 0.003);
+        assert_relative_eq!(PI / 4.0, ellipsoid.ecef_to_geodetic(x, y, z).0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_distance_vincenty() {
+        // Flinders Peak to Buninyong from Vincenty's paper.
+        let degrees = PI / 180.0;
+
+        let distance = Ellipsoid::WGS84.distance(
+            -(37.0 + 57.0 / 60.0 + 3.72030 / 3600.0) * degrees,
+            (144.0 + 25.0 / 60.0 + 29.52440 / 3600.0) * degrees,
+            -(37.0 + 39.0 / 60.0 + 10.15610 / 3600.0) * degrees,
+            (143.0 + 55.0 / 60.0 + 35.38390 / 3600.0) * degrees,
+        );
+
+        assert_relative_eq!(54_972.271, distance, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_distance_quarter_meridian() {
+        // The length of a quarter of a meridian of WGS84.
+        let distance = Ellipsoid::WGS84.distance(0.0, 0.0, PI / 2.0, 0.0);
+
+        assert_relative_eq!(10_001_965.729, distance, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_distance_equator() {
+        let distance = Ellipsoid::WGS84.distance(0.0, 0.0, 0.0, 1.0);
+
+        assert_relative_eq!(6_378_137.0, distance, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_distance_same_point() {
+        assert_eq!(0.0, Ellipsoid::WGS84.distance(0.3, 0.4, 0.3, 0.4));
+    }
+
+    #[test]
+    fn test_distance_antipodal() {
+        let distance = Ellipsoid::WGS84.distance(0.0, 0.0, 0.0, PI);
+
+        assert!(distance > 19_900_000.0 && distance < 20_100_000.0);
+    }
+
+    #[test]
+    fn test_distance_sphere() {
+        let distance = Ellipsoid::sphere(2.0).distance(0.1, 0.2, 0.8, 1.9);
+
+        let angle = (0.1f64.sin() * 0.8f64.sin() + 0.1f64.cos() * 0.8f64.cos() * 1.7f64.cos()).acos();
+
+        assert_relative_eq!(2.0 * angle, distance, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_area_wgs84() {
+        assert_relative_eq!(510_065_621.724e6, Ellipsoid::WGS84.area(), max_relative = 1e-9);
+    }
+
+    #[test]
+    fn test_area_sphere() {
+        assert_relative_eq!(4.0 * PI * 9.0, Ellipsoid::sphere(3.0).area(), epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_authalic_radius() {
+        assert_relative_eq!(6_371_007.181, Ellipsoid::WGS84.authalic_radius(), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_authalic_latitude_sphere() {
+        assert_relative_eq!(0.6, Ellipsoid::sphere(1.0).authalic_latitude(0.6), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_quadrangle_area_hemisphere() {
+        let ellipsoid = Ellipsoid::WGS84;
+
+        assert_relative_eq!(ellipsoid.area() / 2.0, ellipsoid.quadrangle_area(0.0, PI / 2.0, 2.0 * PI), max_relative = 1e-12);
+    }
+
+    #[test]
+    fn test_polygon_area_octant() {
+        let ellipsoid = Ellipsoid::WGS84;
+
+        // One eighth of the ellipsoid bounded by the equator and two meridians.
+        let area = ellipsoid.polygon_area(&[(0.0, 0.0), (0.0, PI / 2.0), (PI / 2.0, 0.0)]);
+
+        assert_relative_eq!(ellipsoid.area() / 8.0, area, max_relative = 1e-12);
+    }
+}
@@ -0,0 +1,712 @@
+//! The HEALPix equal area grid.
+
+use std::{f64::consts::PI, fmt::Debug, hash::Hash, marker::PhantomData, ops::{Index, IndexMut}, vec};
+
+use rayon::prelude::*;
+
+use crate::{GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+use super::{SpherePoint, StaticSpherePoint};
+
+/// The ring of the southern corner of each base face in units of `N`.
+const FACE_RINGS: [u32; 12] = [2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4];
+
+/// The longitude of the southern corner of each base face in units of pi/4.
+const FACE_LONGITUDES: [u32; 12] = [1, 3, 5, 7, 0, 2, 4, 6, 1, 3, 5, 7];
+
+/// The base face reached by leaving a face towards the south west, south east, north east or
+/// north west, indexed by the current face.
+const NEIGHBOUR_FACES: [[u8; 12]; 4] = [
+    [4, 5, 6, 7, 11, 8, 9, 10, 11, 8, 9, 10],
+    [5, 6, 7, 4, 8, 9, 10, 11, 9, 10, 11, 8],
+    [1, 2, 3, 0, 0, 1, 2, 3, 5, 6, 7, 4],
+    [3, 0, 1, 2, 3, 0, 1, 2, 4, 5, 6, 7],
+];
+
+/// How the coordinates change when leaving a face towards the south west, south east, north east
+/// or north west, indexed by the row of the current face.
+/// Bit 1 mirrors X, bit 2 mirrors Y and bit 4 swaps X and Y.
+const NEIGHBOUR_SWAPS: [[u8; 3]; 4] = [
+    [0, 0, 5],
+    [0, 0, 6],
+    [5, 0, 0],
+    [6, 0, 0],
+];
+
+/// The order in which the cells of a `HealpixSphereGrid` are stored and iterated.
+pub trait HealpixOrdering: Debug + Clone + Copy + PartialEq + Eq + Hash + Default + Send + Sync {
+    /// Gets the index of a point in this ordering.
+    ///
+    /// - `point` - The point to get the index of.
+    fn index<const N: usize>(point: &HealpixSpherePoint<N, Self>) -> usize;
+
+    /// Gets the point at an index in this ordering.
+    ///
+    /// - `index` - The index of the point. This must be less than `12 * N * N`.
+    fn point<const N: usize>(index: usize) -> HealpixSpherePoint<N, Self>;
+}
+
+/// The nested ordering where each base face is stored as a quadtree.
+/// This requires `N` to be a power of two, so using it with any other size fails to compile.
+/// Use `RingOrdering` for other sizes.
+///
+/// ```compile_fail
+/// use surface_grid::{sphere::HealpixSphereGrid, StaticSurfaceGrid};
+///
+/// let grid: HealpixSphereGrid<u8, 12> = HealpixSphereGrid::from_fn(|_| 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct NestedOrdering;
+
+impl HealpixOrdering for NestedOrdering {
+    fn index<const N: usize>(point: &HealpixSpherePoint<N, Self>) -> usize {
+        point.nested_index()
+    }
+
+    fn point<const N: usize>(index: usize) -> HealpixSpherePoint<N, Self> {
+        HealpixSpherePoint::from_nested_index(index)
+            .expect("Index must be within the grid")
+    }
+}
+
+/// The ring ordering where cells are stored in rings of equal latitude from north to south.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RingOrdering;
+
+impl HealpixOrdering for RingOrdering {
+    fn index<const N: usize>(point: &HealpixSpherePoint<N, Self>) -> usize {
+        point.ring_index()
+    }
+
+    fn point<const N: usize>(index: usize) -> HealpixSpherePoint<N, Self> {
+        HealpixSpherePoint::from_ring_index(index)
+            .expect("Index must be within the grid")
+    }
+}
+
+/// A grid wrapped around a sphere using the HEALPix tessellation.
+///
+/// The sphere is split into 12 base faces which are each split into `N` by `N` cells, giving
+/// `12 * N * N` cells that all have exactly the same area.
+/// The cells lie on rings of equal latitude which makes the grid well suited to spherical
+/// harmonics and to matching astronomy data products.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+/// - `O` - The order in which the cells are stored and iterated, either `NestedOrdering` or
+///   `RingOrdering`.
+///
+/// # Constant Parameters
+/// - `N` - The number of cells along each side of each base face, known as `nside`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HealpixSphereGrid<T, const N: usize, O: HealpixOrdering = NestedOrdering> {
+    /// The data held in this grid in the order given by `O`.
+    data: Vec<T>,
+    ordering: PhantomData<O>,
+}
+
+impl <T: Default, const N: usize, O: HealpixOrdering> Default for HealpixSphereGrid<T, N, O> {
+    fn default() -> Self {
+        Self::from_fn(|_| T::default())
+    }
+}
+
+impl <T, const N: usize, O: HealpixOrdering> SurfaceGrid<T> for HealpixSphereGrid<T, N, O> {
+    type Point = HealpixSpherePoint<N, O>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        for (i, value) in self.data.iter_mut().enumerate() {
+            *value = f(&O::point(i));
+        }
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        self.data.par_iter_mut()
+            .enumerate()
+            .for_each(|(i, value)| *value = f(&O::point(i)));
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        self.data.iter()
+            .enumerate()
+            .map(|(i, value)| (O::point(i), value))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        self.data.par_iter()
+            .enumerate()
+            .map(|(i, value)| (O::point(i), value))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        (0..12 * N * N).map(O::point)
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        (0..12 * N * N).into_par_iter().map(O::point)
+    }
+}
+
+impl <T, const N: usize, O: HealpixOrdering> StaticSurfaceGrid<T> for HealpixSphereGrid<T, N, O> {
+    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
+        Self {
+            data: (0..12 * N * N).map(|i| f(&O::point(i))).collect(),
+            ordering: PhantomData,
+        }
+    }
+
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self {
+            data: (0..12 * N * N).into_par_iter().map(|i| f(&O::point(i))).collect(),
+            ordering: PhantomData,
+        }
+    }
+}
+
+impl <T, const N: usize, O: HealpixOrdering> Index<HealpixSpherePoint<N, O>> for HealpixSphereGrid<T, N, O> {
+    type Output = T;
+
+    fn index(&self, index: HealpixSpherePoint<N, O>) -> &Self::Output {
+        &self.data[O::index(&index)]
+    }
+}
+
+impl <T, const N: usize, O: HealpixOrdering> IndexMut<HealpixSpherePoint<N, O>> for HealpixSphereGrid<T, N, O> {
+    fn index_mut(&mut self, index: HealpixSpherePoint<N, O>) -> &mut Self::Output {
+        &mut self.data[O::index(&index)]
+    }
+}
+
+impl <T, const N: usize, O: HealpixOrdering> IntoIterator for HealpixSphereGrid<T, N, O> {
+    type Item = (HealpixSpherePoint<N, O>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let data: Vec<_> = self.data.into_iter()
+            .enumerate()
+            .map(|(i, value)| (O::point(i), value))
+            .collect();
+
+        data.into_iter()
+    }
+}
+
+/// A point on a `HealpixSphereGrid`.
+///
+/// Each cell is a diamond with corners pointing north, east, south and west.
+/// Moving `up` goes to the neighbour to the north west, `right` to the north east, `down` to the
+/// south east and `left` to the south west.
+/// As with `CubeSpherePoint`, the directions can rotate when moving from one base face to another.
+///
+/// # Type Parameters
+/// - `O` - The ordering of the grid that the point is on, which sets its index.
+///
+/// # Constant Parameters
+/// - `N` - The number of cells along each side of each base face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HealpixSpherePoint<const N: usize, O: HealpixOrdering = NestedOrdering> {
+    /// The base face of the point.
+    face: u8,
+    /// The position towards the north east in the base face.
+    x: u32,
+    /// The position towards the north west in the base face.
+    y: u32,
+    ordering: PhantomData<O>,
+}
+
+impl <const N: usize, O: HealpixOrdering> HealpixSpherePoint<N, O> {
+    fn new(face: u8, x: u32, y: u32) -> Self {
+        Self {
+            face: face.min(11),
+            x: x.min(N as u32 - 1),
+            y: y.min(N as u32 - 1),
+            ordering: PhantomData,
+        }
+    }
+
+    /// Gets the same cell as a point on a grid with a different ordering.
+    pub fn with_ordering<Q: HealpixOrdering>(&self) -> HealpixSpherePoint<N, Q> {
+        HealpixSpherePoint::new(self.face, self.x, self.y)
+    }
+
+    /// Gets the index of this point in the nested ordering.
+    /// This requires `N` to be a power of two, which is checked when the program is compiled.
+    pub fn nested_index(&self) -> usize {
+        const { assert!(N.is_power_of_two(), "The nested ordering requires N to be a power of two") };
+
+        let mut index = 0;
+        for bit in 0..u32::BITS - 1 - (N as u32).leading_zeros() {
+            index |= ((self.x as usize >> bit) & 1) << (2 * bit);
+            index |= ((self.y as usize >> bit) & 1) << (2 * bit + 1);
+        }
+
+        self.face as usize * N * N + index
+    }
+
+    /// Gets the point with the specified index in the nested ordering.
+    /// Returns `None` if the index is outside the grid.
+    /// This requires `N` to be a power of two, which is checked when the program is compiled.
+    ///
+    /// - `index` - The index of the point.
+    pub fn from_nested_index(index: usize) -> Option<Self> {
+        const { assert!(N.is_power_of_two(), "The nested ordering requires N to be a power of two") };
+
+        if index >= 12 * N * N {
+            return None;
+        }
+
+        let face = index / (N * N);
+        let index = index % (N * N);
+
+        let mut x = 0;
+        let mut y = 0;
+        for bit in 0..u32::BITS - 1 - (N as u32).leading_zeros() {
+            x |= ((index >> (2 * bit)) & 1) << bit;
+            y |= ((index >> (2 * bit + 1)) & 1) << bit;
+        }
+
+        Some(Self::new(face as u8, x as u32, y as u32))
+    }
+
+    /// Gets the index of this point in the ring ordering.
+    pub fn ring_index(&self) -> usize {
+        let (ring, position) = self.ring_position();
+        let n = N as u32;
+
+        let index = if ring < n {
+            2 * ring * (ring - 1) + position - 1
+        } else if ring > 3 * n {
+            let south = 4 * n - ring;
+
+            12 * n * n - 2 * south * (south + 1) + position - 1
+        } else {
+            2 * n * (n - 1) + (ring - n) * 4 * n + position - 1
+        };
+
+        index as usize
+    }
+
+    /// Gets the point with the specified index in the ring ordering.
+    /// Returns `None` if the index is outside the grid.
+    ///
+    /// - `index` - The index of the point.
+    pub fn from_ring_index(index: usize) -> Option<Self> {
+        let n = N as u64;
+        let index = index as u64;
+        let cap = 2 * n * (n - 1);
+        let total = 12 * n * n;
+
+        if index >= total {
+            return None;
+        }
+
+        let (ring, position) = if index < cap {
+            let ring = integer_sqrt(1 + 2 * index).div_ceil(2);
+
+            (ring, index + 1 - 2 * ring * (ring - 1))
+        } else if index < total - cap {
+            let offset = index - cap;
+
+            (offset / (4 * n) + n, offset % (4 * n) + 1)
+        } else {
+            let offset = total - index;
+            let south = integer_sqrt(2 * offset - 1).div_ceil(2);
+
+            (4 * n - south, 4 * south + 1 - (offset - 2 * south * (south - 1)))
+        };
+
+        let (height, longitude) = ring_centre(ring as u32, position as u32, N as u32);
+
+        Some(Self::from_height(height, longitude))
+    }
+
+    /// Gets the ring of this point counting from 1 at the north pole, and the position of the
+    /// point in the ring counting from 1 at longitude 0.
+    fn ring_position(&self) -> (u32, u32) {
+        let n = N as u32;
+        let face = self.face as usize;
+
+        let ring = FACE_RINGS[face] * n - self.x - self.y - 1;
+
+        let (cells, shift) = if ring < n {
+            (ring, 0)
+        } else if ring > 3 * n {
+            (4 * n - ring, 0)
+        } else {
+            (n, (ring - n) & 1)
+        };
+
+        let position = (FACE_LONGITUDES[face] * cells + self.x + 1 + shift) as i64 - self.y as i64;
+        let position = (position / 2 - 1).rem_euclid(4 * cells as i64) + 1;
+
+        (ring, position as u32)
+    }
+
+    /// Gets the cell containing the point with the specified height above the equatorial plane
+    /// and longitude.
+    ///
+    /// - `height` - The sine of the latitude.
+    /// - `longitude` - The longitude in radians.
+    fn from_height(height: f64, longitude: f64) -> Self {
+        let n = N as f64;
+        let size = N as i64;
+        let quarter = (longitude * 2.0 / PI).rem_euclid(4.0);
+
+        if height.abs() <= 2.0 / 3.0 {
+            let centre = n * (0.5 + quarter);
+            let offset = n * height * 0.75;
+
+            let ascending = (centre - offset) as i64;
+            let descending = (centre + offset) as i64;
+
+            let ascending_face = ascending / size;
+            let descending_face = descending / size;
+
+            let face = if ascending_face == descending_face {
+                ascending_face | 4
+            } else if ascending_face < descending_face {
+                ascending_face
+            } else {
+                descending_face + 8
+            };
+
+            Self::new(face as u8, descending.rem_euclid(size) as u32, (size - ascending.rem_euclid(size) - 1) as u32)
+        } else {
+            let column = (quarter as i64).min(3);
+            let along = quarter - column as f64;
+            let distance = n * (3.0 * (1.0 - height.abs())).sqrt();
+
+            let ascending = ((along * distance) as i64).min(size - 1);
+            let descending = (((1.0 - along) * distance) as i64).min(size - 1);
+
+            if height >= 0.0 {
+                Self::new(column as u8, (size - descending - 1) as u32, (size - ascending - 1) as u32)
+            } else {
+                Self::new(column as u8 + 8, ascending as u32, descending as u32)
+            }
+        }
+    }
+
+    /// Gets the neighbouring point in the specified direction.
+    ///
+    /// - `dx` - The change in the X position which must be -1, 0 or 1.
+    /// - `dy` - The change in the Y position which must be -1, 0 or 1 when `dx` is 0, otherwise
+    ///   it must be 0.
+    fn step(&self, dx: i64, dy: i64) -> Self {
+        let size = N as i64;
+        let x = self.x as i64 + dx;
+        let y = self.y as i64 + dy;
+
+        let direction = if x < 0 {
+            0
+        } else if y < 0 {
+            1
+        } else if x >= size {
+            2
+        } else if y >= size {
+            3
+        } else {
+            return Self::new(self.face, x as u32, y as u32);
+        };
+
+        let face = NEIGHBOUR_FACES[direction][self.face as usize];
+        let swap = NEIGHBOUR_SWAPS[direction][self.face as usize / 4];
+
+        let mut x = x.rem_euclid(size);
+        let mut y = y.rem_euclid(size);
+
+        if swap & 1 != 0 {
+            x = size - x - 1;
+        }
+
+        if swap & 2 != 0 {
+            y = size - y - 1;
+        }
+
+        if swap & 4 != 0 {
+            (x, y) = (y, x);
+        }
+
+        Self::new(face, x as u32, y as u32)
+    }
+}
+
+impl <const N: usize, O: HealpixOrdering> GridPoint for HealpixSpherePoint<N, O> {
+    fn up(&self) -> Self {
+        self.step(0, 1)
+    }
+
+    fn down(&self) -> Self {
+        self.step(0, -1)
+    }
+
+    fn left(&self) -> Self {
+        self.step(-1, 0)
+    }
+
+    fn right(&self) -> Self {
+        self.step(1, 0)
+    }
+
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        let latitude = self.latitude();
+        let longitude = self.longitude();
+
+        let radius = latitude.cos() * scale;
+
+        (radius * longitude.sin(), latitude.sin() * scale, radius * longitude.cos())
+    }
+}
+
+impl <const N: usize, O: HealpixOrdering> SpherePoint for HealpixSpherePoint<N, O> {
+    fn at_geographic(&self, latitude: f64, longitude: f64) -> Self {
+        Self::from_geographic(latitude, longitude)
+    }
+
+    fn latitude(&self) -> f64 {
+        let (ring, position) = self.ring_position();
+        let (height, _) = ring_centre(ring, position, N as u32);
+
+        height.asin()
+    }
+
+    fn longitude(&self) -> f64 {
+        let (ring, position) = self.ring_position();
+        let (_, longitude) = ring_centre(ring, position, N as u32);
+
+        longitude
+    }
+}
+
+impl <const N: usize, O: HealpixOrdering> IndexedPoint for HealpixSpherePoint<N, O> {
+    const COUNT: usize = 12 * N * N;
+
+    fn to_index(&self) -> usize {
+        O::index(self)
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        O::point(index)
+    }
+}
+
+impl <const N: usize, O: HealpixOrdering> StaticSpherePoint for HealpixSpherePoint<N, O> {
+    fn from_geographic(latitude: f64, longitude: f64) -> Self {
+        Self::from_height(latitude.sin(), longitude)
+    }
+}
+
+/// Gets the sine of the latitude and the longitude of the centre of a cell from its ring and its
+/// position in the ring.
+///
+/// - `ring` - The ring counting from 1 at the north pole.
+/// - `position` - The position in the ring counting from 1.
+/// - `n` - The number of cells along each side of each base face.
+fn ring_centre(ring: u32, position: u32, n: u32) -> (f64, f64) {
+    let size = n as f64;
+
+    if ring < n {
+        let ring = ring as f64;
+
+        (1.0 - ring * ring / (3.0 * size * size), (position as f64 - 0.5) * PI / (2.0 * ring))
+    } else if ring > 3 * n {
+        let ring = (4 * n - ring) as f64;
+
+        (ring * ring / (3.0 * size * size) - 1.0, (position as f64 - 0.5) * PI / (2.0 * ring))
+    } else {
+        let shift = if (ring - n) & 1 == 1 { 1.0 } else { 0.5 };
+
+        ((2.0 * size - ring as f64) * 2.0 / (3.0 * size), (position as f64 - shift) * PI / (2.0 * size))
+    }
+}
+
+/// Gets the integer square root of a number rounded down.
+///
+/// - `value` - The number to get the square root of.
+fn integer_sqrt(value: u64) -> u64 {
+    let mut root = (value as f64).sqrt() as u64;
+
+    while root * root > value {
+        root -= 1;
+    }
+
+    while (root + 1) * (root + 1) <= value {
+        root += 1;
+    }
+
+    root
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::{HashMap, HashSet}, f64::consts::PI};
+
+    use approx::assert_relative_eq;
+
+    use crate::{test::assert_indexed_points, GridPoint, SurfaceGrid, StaticSurfaceGrid, sphere::{SpherePoint, StaticSpherePoint}};
+
+    use super::{HealpixSphereGrid, HealpixSpherePoint, NestedOrdering, RingOrdering};
+
+    #[test]
+    fn test_healpix_nested_round_trip() {
+        for i in 0..12 * 4 * 4 {
+            let point: HealpixSpherePoint<4> = HealpixSpherePoint::from_nested_index(i).unwrap();
+
+            assert_eq!(i, point.nested_index());
+        }
+    }
+
+    #[test]
+    fn test_healpix_ring_round_trip() {
+        for i in 0..12 * 5 * 5 {
+            let point: HealpixSpherePoint<5> = HealpixSpherePoint::from_ring_index(i).unwrap();
+
+            assert_eq!(i, point.ring_index());
+        }
+    }
+
+    #[test]
+    fn test_healpix_out_of_range() {
+        assert_eq!(None, HealpixSpherePoint::<4>::from_nested_index(12 * 4 * 4));
+        assert_eq!(None, HealpixSpherePoint::<4>::from_ring_index(12 * 4 * 4));
+    }
+
+    #[test]
+    fn test_healpix_ring_order_by_latitude() {
+        let grid: HealpixSphereGrid<(), 4, RingOrdering> = HealpixSphereGrid::default();
+
+        let latitudes: Vec<_> = grid.points().map(|point| point.latitude()).collect();
+
+        assert!(latitudes.windows(2).all(|pair| pair[0] >= pair[1]));
+    }
+
+    #[test]
+    fn test_healpix_nside_one() {
+        let point: HealpixSpherePoint<1> = HealpixSpherePoint::from_nested_index(0).unwrap();
+
+        assert_relative_eq!((2.0_f64 / 3.0).asin(), point.latitude());
+        assert_relative_eq!(PI / 4.0, point.longitude());
+        assert_eq!(0, point.ring_index());
+    }
+
+    #[test]
+    fn test_healpix_from_geographic_round_trip() {
+        for i in 0..12 * 8 * 8 {
+            let point: HealpixSpherePoint<8> = HealpixSpherePoint::from_nested_index(i).unwrap();
+
+            assert_eq!(point, HealpixSpherePoint::from_geographic(point.latitude(), point.longitude()));
+        }
+    }
+
+    #[test]
+    fn test_healpix_equal_area() {
+        let mut counts = HashMap::new();
+
+        let steps = 600;
+        for i in 0..steps {
+            let height = -1.0 + (i as f64 + 0.5) * 2.0 / steps as f64;
+
+            for j in 0..steps {
+                let longitude = (j as f64 + 0.5) * 2.0 * PI / steps as f64;
+
+                let point: HealpixSpherePoint<2> = HealpixSpherePoint::from_geographic(height.asin(), longitude);
+
+                *counts.entry(point).or_insert(0) += 1;
+            }
+        }
+
+        let expected = (steps * steps) as f64 / 48.0;
+
+        assert_eq!(48, counts.len());
+        for count in counts.values() {
+            assert!((*count as f64 - expected).abs() < expected * 0.02);
+        }
+    }
+
+    #[test]
+    fn test_healpix_neighbours_symmetric() {
+        for i in 0..12 * 4 * 4 {
+            let point: HealpixSpherePoint<4> = HealpixSpherePoint::from_nested_index(i).unwrap();
+
+            for neighbour in [point.up(), point.down(), point.left(), point.right()] {
+                assert!([neighbour.up(), neighbour.down(), neighbour.left(), neighbour.right()].contains(&point),
+                    "{:?} is not a neighbour of {:?}", point, neighbour);
+            }
+        }
+    }
+
+    #[test]
+    fn test_healpix_neighbours_adjacent() {
+        for i in 0..12 * 4 * 4 {
+            let point: HealpixSpherePoint<4> = HealpixSpherePoint::from_nested_index(i).unwrap();
+            let (x, y, z) = point.position(1.0);
+
+            let neighbours: HashSet<_> = [point.up(), point.down(), point.left(), point.right()].into_iter().collect();
+            assert_eq!(4, neighbours.len());
+
+            for neighbour in neighbours {
+                let (nx, ny, nz) = neighbour.position(1.0);
+                let angle = (x * nx + y * ny + z * nz).clamp(-1.0, 1.0).acos();
+
+                assert!(angle < 0.4, "{:?} to {:?} is {}", point, neighbour, angle);
+            }
+        }
+    }
+
+    #[test]
+    fn test_healpix_directions() {
+        let point: HealpixSpherePoint<4> = HealpixSpherePoint::from_geographic(0.1, 1.0);
+
+        assert!(point.up().latitude() > point.latitude());
+        assert!(point.up().longitude() < point.longitude());
+        assert!(point.right().latitude() > point.latitude());
+        assert!(point.right().longitude() > point.longitude());
+    }
+
+    #[test]
+    fn test_healpix_orderings_agree() {
+        let nested: HealpixSphereGrid<usize, 4, NestedOrdering> = HealpixSphereGrid::from_fn(|point| point.ring_index());
+        let ring: HealpixSphereGrid<usize, 4, RingOrdering> = HealpixSphereGrid::from_fn_par(|point| point.ring_index());
+
+        for (point, value) in nested.iter() {
+            assert_eq!(*value, ring[point.with_ordering()]);
+        }
+
+        assert!(ring.iter().all(|(point, value)| point.ring_index() == *value));
+        assert!(ring.into_iter().enumerate().all(|(i, (_, value))| i == value));
+    }
+
+    #[test]
+    fn test_healpix_ring_any_size() {
+        // Only the nested ordering needs a power of two.
+        let grid: HealpixSphereGrid<usize, 12, RingOrdering> = HealpixSphereGrid::from_fn(|point| point.ring_index());
+
+        assert!(grid.into_iter().enumerate().all(|(i, (_, value))| i == value));
+    }
+
+    #[test]
+    fn test_healpix_from_neighbours() {
+        let grid: HealpixSphereGrid<u32, 4> = HealpixSphereGrid::from_fn(|_| 1);
+
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert!(grid2.iter().all(|(_, value)| *value == 5));
+    }
+
+    #[test]
+    fn test_healpix_indexed_points() {
+        assert_indexed_points(&HealpixSphereGrid::<(), 4, NestedOrdering>::default());
+        assert_indexed_points(&HealpixSphereGrid::<(), 3, RingOrdering>::default());
+    }
+}
@@ -0,0 +1,756 @@
+//! A geodesic grid made by subdividing an icosahedron.
+
+use std::{f64::consts::PI, iter, ops::{Index, IndexMut}, vec};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use static_array::HeapArray2D;
+
+use crate::{GridPoint, IndexedPoint, PolygonPoint, SurfaceGrid, StaticSurfaceGrid};
+
+use super::{SpherePoint, StaticSpherePoint};
+
+/// The diamond value used for the north pole.
+const NORTH: u8 = 10;
+/// The diamond value used for the south pole.
+const SOUTH: u8 = 11;
+
+/// The number of vertices of an icosahedron.
+const VERTICES: usize = 12;
+
+/// The weights of a lattice point over the vertices of the icosahedron.
+/// Only the vertices of a single face can have a non-zero weight and the weights sum to `N`.
+type Weights = [u32; VERTICES];
+
+/// A hexagonal grid wrapped around a sphere.
+///
+/// The grid is made by splitting each edge of an icosahedron into `N` parts and projecting the
+/// resulting triangular lattice onto the sphere.
+/// Each lattice point is the centre of a hexagonal cell, except for the 12 vertices of the
+/// icosahedron which are the centres of pentagonal cells.
+/// This gives `10 * N * N + 2` cells with far less distortion than a `CubeSphereGrid`.
+///
+/// The points of this grid implement `PolygonPoint` which gives the 6 or 5 neighbours of each
+/// cell.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+///
+/// # Constant Parameters
+/// - `N` - The number of parts that each edge of the icosahedron is split into.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IcosaSphereGrid<T, const N: usize> {
+    north: T,
+    diamonds: [HeapArray2D<T, N, N>; 10],
+    south: T,
+}
+
+impl <T, const N: usize> SurfaceGrid<T> for IcosaSphereGrid<T, N> {
+    type Point = IcosaSpherePoint<N>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        for point in IcosaSpherePoint::all() {
+            self[point] = f(&point);
+        }
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        self.north = f(&IcosaSpherePoint::new(NORTH, 0, 0));
+
+        for (diamond, data) in self.diamonds.iter_mut().enumerate() {
+            data.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+                for (x, value) in subarray.iter_mut().enumerate() {
+                    *value = f(&IcosaSpherePoint::new(diamond as u8, x as u32, y as u32));
+                }
+            });
+        }
+
+        self.south = f(&IcosaSpherePoint::new(SOUTH, 0, 0));
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        self.points()
+            .map(|point| (point, &self[point]))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        self.par_points()
+            .map(|point| (point, &self[point]))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        IcosaSpherePoint::all()
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        IcosaSpherePoint::all().par_bridge()
+    }
+}
+
+impl <T, const N: usize> StaticSurfaceGrid<T> for IcosaSphereGrid<T, N> {
+    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
+        let north = f(&IcosaSpherePoint::new(NORTH, 0, 0));
+        let diamonds = std::array::from_fn(|diamond| HeapArray2D::from_fn(|y, x| f(&IcosaSpherePoint::new(diamond as u8, x as u32, y as u32))));
+        let south = f(&IcosaSpherePoint::new(SOUTH, 0, 0));
+
+        Self {
+            north,
+            diamonds,
+            south,
+        }
+    }
+
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self {
+            north: f(&IcosaSpherePoint::new(NORTH, 0, 0)),
+            diamonds: std::array::from_fn(|diamond| HeapArray2D::from_fn_par(|y, x| f(&IcosaSpherePoint::new(diamond as u8, x as u32, y as u32)))),
+            south: f(&IcosaSpherePoint::new(SOUTH, 0, 0)),
+        }
+    }
+}
+
+impl <T, const N: usize> Index<IcosaSpherePoint<N>> for IcosaSphereGrid<T, N> {
+    type Output = T;
+
+    fn index(&self, index: IcosaSpherePoint<N>) -> &Self::Output {
+        match index.diamond {
+            NORTH => &self.north,
+            SOUTH => &self.south,
+            diamond => &self.diamonds[diamond as usize][index.y as usize][index.x as usize],
+        }
+    }
+}
+
+impl <T, const N: usize> IndexMut<IcosaSpherePoint<N>> for IcosaSphereGrid<T, N> {
+    fn index_mut(&mut self, index: IcosaSpherePoint<N>) -> &mut Self::Output {
+        match index.diamond {
+            NORTH => &mut self.north,
+            SOUTH => &mut self.south,
+            diamond => &mut self.diamonds[diamond as usize][index.y as usize][index.x as usize],
+        }
+    }
+}
+
+impl <T, const N: usize> IntoIterator for IcosaSphereGrid<T, N> {
+    type Item = (IcosaSpherePoint<N>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let mut data = vec![(IcosaSpherePoint::new(NORTH, 0, 0), self.north)];
+
+        data.extend(self.diamonds.into_iter()
+                    .enumerate()
+                    .flat_map(|(diamond, data)| data.into_iter()
+                              .enumerate()
+                              .flat_map(move |(y, subarray)| subarray.into_iter()
+                                        .enumerate()
+                                        .map(move |(x, value)| (IcosaSpherePoint::new(diamond as u8, x as u32, y as u32), value))
+                                        )));
+
+        data.push((IcosaSpherePoint::new(SOUTH, 0, 0), self.south));
+
+        data.into_iter()
+    }
+}
+
+/// A point on an `IcosaSphereGrid`.
+///
+/// Use `PolygonPoint::neighbours` to get the 6 neighbours of a hexagonal cell or the 5 neighbours
+/// of a pentagonal cell.
+/// The `GridPoint` directions pick the neighbour closest to north, south, west and east, so
+/// moving `up` and then `down` may not return to the same point.
+///
+/// # Constant Parameters
+/// - `N` - The number of parts that each edge of the icosahedron is split into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IcosaSpherePoint<const N: usize> {
+    /// The diamond of two icosahedron faces that the point is on, or one of the poles.
+    diamond: u8,
+    /// The X position in the diamond.
+    x: u32,
+    /// The Y position in the diamond.
+    y: u32,
+}
+
+impl <const N: usize> IcosaSpherePoint<N> {
+    fn new(diamond: u8, x: u32, y: u32) -> Self {
+        match diamond {
+            NORTH | SOUTH => Self {
+                diamond,
+                x: 0,
+                y: 0,
+            },
+            _ => Self {
+                diamond: diamond.min(9),
+                x: x.min(N as u32 - 1),
+                y: y.min(N as u32 - 1),
+            },
+        }
+    }
+
+    /// Iterates over every point on the sphere.
+    /// The north pole comes first, followed by each diamond in row order and then the south pole.
+    fn all() -> impl Iterator<Item = Self> {
+        iter::once(Self::new(NORTH, 0, 0))
+            .chain((0..10).cartesian_product(0..N).cartesian_product(0..N)
+                   .map(|((diamond, y), x)| Self::new(diamond, x as u32, y as u32)))
+            .chain(iter::once(Self::new(SOUTH, 0, 0)))
+    }
+
+    /// Checks whether the cell is one of the 12 pentagons at the vertices of the icosahedron.
+    pub fn is_pentagon(&self) -> bool {
+        self.diamond == NORTH || self.diamond == SOUTH || (self.x == 0 && self.y == 0)
+    }
+
+    /// Gets the weights of this point over the vertices of the icosahedron.
+    fn weights(&self) -> Weights {
+        let mut weights = [0; VERTICES];
+
+        match self.diamond {
+            NORTH => weights[0] = N as u32,
+            SOUTH => weights[VERTICES - 1] = N as u32,
+            diamond => {
+                let (origin, first, second, opposite) = diamond_corners(diamond);
+
+                if self.x >= self.y {
+                    weights[origin] = N as u32 - self.x;
+                    weights[first] = self.x - self.y;
+                } else {
+                    weights[origin] = N as u32 - self.y;
+                    weights[second] = self.y - self.x;
+                }
+
+                weights[opposite] += self.x.min(self.y);
+            },
+        }
+
+        weights
+    }
+
+    /// Gets the point with the specified weights over the vertices of the icosahedron.
+    fn from_weights(weights: &Weights) -> Self {
+        if weights[0] == N as u32 {
+            return Self::new(NORTH, 0, 0);
+        }
+
+        if weights[VERTICES - 1] == N as u32 {
+            return Self::new(SOUTH, 0, 0);
+        }
+
+        for diamond in 0..10 {
+            let (origin, first, second, opposite) = diamond_corners(diamond);
+
+            let inside = |vertices: [usize; 3]| (0..VERTICES)
+                .all(|vertex| weights[vertex] == 0 || vertices.contains(&vertex));
+
+            let (x, y) = if inside([origin, first, opposite]) {
+                (weights[first] + weights[opposite], weights[opposite])
+            } else if inside([origin, second, opposite]) {
+                (weights[opposite], weights[second] + weights[opposite])
+            } else {
+                continue;
+            };
+
+            if x < N as u32 && y < N as u32 {
+                return Self::new(diamond, x, y);
+            }
+        }
+
+        unreachable!("Every lattice point belongs to a diamond or a pole")
+    }
+
+    /// Gets the position of the point on the unit sphere.
+    fn unit_position(&self) -> (f64, f64, f64) {
+        let weights = self.weights();
+
+        let (x, y, z) = (0..VERTICES)
+            .filter(|&vertex| weights[vertex] > 0)
+            .map(|vertex| {
+                let (x, y, z) = vertex_position(vertex);
+                let weight = weights[vertex] as f64;
+
+                (x * weight, y * weight, z * weight)
+            })
+            .fold((0.0, 0.0, 0.0), |(x1, y1, z1), (x2, y2, z2)| (x1 + x2, y1 + y2, z1 + z2));
+
+        let length = (x * x + y * y + z * z).sqrt();
+
+        (x / length, y / length, z / length)
+    }
+
+    /// Gets the nearest cell to the specified geographic coordinates.
+    ///
+    /// - `latitude` - The latitude of the point in the range -pi/2 to pi/2.
+    /// - `longitude` - The longitude of the point in the range 0 to 2pi.
+    fn nearest(latitude: f64, longitude: f64) -> Self {
+        let target = (latitude.cos() * longitude.sin(), latitude.sin(), latitude.cos() * longitude.cos());
+
+        let (face, coordinates) = faces().into_iter()
+            .map(|face| (face, barycentric(target, face)))
+            .max_by(|(_, a), (_, b)| {
+                let a = a[0].min(a[1]).min(a[2]);
+                let b = b[0].min(b[1]).min(b[2]);
+
+                a.total_cmp(&b)
+            })
+            .unwrap();
+
+        let mut weights = [0; VERTICES];
+        let scaled = coordinates.map(|coordinate| coordinate.max(0.0) * N as f64);
+        let mut remaining = N as u32;
+        for (vertex, value) in face.into_iter().zip(scaled).skip(1) {
+            let weight = (value.round() as u32).min(remaining);
+
+            weights[vertex] = weight;
+            remaining -= weight;
+        }
+        weights[face[0]] = remaining;
+
+        let mut point = Self::from_weights(&weights);
+        let closeness = |point: &Self| {
+            let (x, y, z) = point.unit_position();
+
+            x * target.0 + y * target.1 + z * target.2
+        };
+
+        loop {
+            let best = point.neighbours()
+                .into_iter()
+                .max_by(|a, b| closeness(a).total_cmp(&closeness(b)))
+                .unwrap();
+
+            if closeness(&best) <= closeness(&point) {
+                return point;
+            }
+
+            point = best;
+        }
+    }
+
+    /// Gets the unit vectors pointing east and north along the surface at this point.
+    fn tangents(&self) -> ((f64, f64, f64), (f64, f64, f64)) {
+        let latitude = self.latitude();
+        let longitude = self.longitude();
+
+        let east = (longitude.cos(), 0.0, -longitude.sin());
+        let north = (-latitude.sin() * longitude.sin(), latitude.cos(), -latitude.sin() * longitude.cos());
+
+        (east, north)
+    }
+
+    /// Gets the neighbour that is furthest along the specified direction.
+    ///
+    /// - `direction` - The direction along the surface.
+    fn furthest_along(&self, direction: (f64, f64, f64)) -> Self {
+        let (x, y, z) = self.unit_position();
+
+        let distance = |point: &Self| {
+            let (nx, ny, nz) = point.unit_position();
+
+            (nx - x) * direction.0 + (ny - y) * direction.1 + (nz - z) * direction.2
+        };
+
+        self.neighbours()
+            .into_iter()
+            .max_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap()
+    }
+}
+
+impl <const N: usize> GridPoint for IcosaSpherePoint<N> {
+    fn up(&self) -> Self {
+        let (_, north) = self.tangents();
+
+        self.furthest_along(north)
+    }
+
+    fn down(&self) -> Self {
+        let (_, (x, y, z)) = self.tangents();
+
+        self.furthest_along((-x, -y, -z))
+    }
+
+    fn left(&self) -> Self {
+        let ((x, y, z), _) = self.tangents();
+
+        self.furthest_along((-x, -y, -z))
+    }
+
+    fn right(&self) -> Self {
+        let (east, _) = self.tangents();
+
+        self.furthest_along(east)
+    }
+
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        let (x, y, z) = self.unit_position();
+
+        (x * scale, y * scale, z * scale)
+    }
+}
+
+impl <const N: usize> IndexedPoint for IcosaSpherePoint<N> {
+    const COUNT: usize = 10 * N * N + 2;
+
+    fn to_index(&self) -> usize {
+        match self.diamond {
+            NORTH => 0,
+            SOUTH => Self::COUNT - 1,
+            diamond => 1 + (diamond as usize * N + self.y as usize) * N + self.x as usize,
+        }
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        if index == 0 {
+            Self::new(NORTH, 0, 0)
+        } else if index == Self::COUNT - 1 {
+            Self::new(SOUTH, 0, 0)
+        } else {
+            let index = index - 1;
+
+            Self::new((index / (N * N)) as u8, (index % N) as u32, (index / N % N) as u32)
+        }
+    }
+}
+
+impl <const N: usize> PolygonPoint for IcosaSpherePoint<N> {
+    fn neighbours(&self) -> Vec<Self> {
+        let weights = self.weights();
+
+        let mut neighbours = Vec::with_capacity(6);
+
+        for face in faces() {
+            if (0..VERTICES).any(|vertex| weights[vertex] > 0 && !face.contains(&vertex)) {
+                continue;
+            }
+
+            for (from, to) in face.into_iter().cartesian_product(face) {
+                if from == to || weights[from] == 0 {
+                    continue;
+                }
+
+                let mut next = weights;
+                next[from] -= 1;
+                next[to] += 1;
+
+                let point = Self::from_weights(&next);
+
+                if !neighbours.contains(&point) {
+                    neighbours.push(point);
+                }
+            }
+        }
+
+        let (x, y, z) = self.unit_position();
+        let (east, north) = self.tangents();
+
+        let angle = |point: &Self| {
+            let (nx, ny, nz) = point.unit_position();
+            let (dx, dy, dz) = (nx - x, ny - y, nz - z);
+
+            let along_east = dx * east.0 + dy * east.1 + dz * east.2;
+            let along_north = dx * north.0 + dy * north.1 + dz * north.2;
+
+            (-along_east).atan2(along_north).rem_euclid(2.0 * PI)
+        };
+
+        neighbours.sort_by(|a, b| angle(a).total_cmp(&angle(b)));
+
+        neighbours
+    }
+}
+
+impl <const N: usize> SpherePoint for IcosaSpherePoint<N> {
+    fn at_geographic(&self, latitude: f64, longitude: f64) -> Self {
+        Self::from_geographic(latitude, longitude)
+    }
+
+    fn latitude(&self) -> f64 {
+        let (_, y, _) = self.unit_position();
+
+        y.clamp(-1.0, 1.0).asin()
+    }
+
+    fn longitude(&self) -> f64 {
+        let (x, _, z) = self.unit_position();
+
+        x.atan2(z).rem_euclid(2.0 * PI)
+    }
+}
+
+impl <const N: usize> StaticSpherePoint for IcosaSpherePoint<N> {
+    fn from_geographic(latitude: f64, longitude: f64) -> Self {
+        Self::nearest(latitude, longitude)
+    }
+}
+
+/// Gets the vertex on the upper ring of the icosahedron with the specified index.
+fn upper(index: u8) -> usize {
+    1 + index as usize % 5
+}
+
+/// Gets the vertex on the lower ring of the icosahedron with the specified index.
+fn lower(index: u8) -> usize {
+    6 + index as usize % 5
+}
+
+/// Gets the corners of a diamond.
+/// The corners are returned as the origin, the corner along the X axis, the corner along the Y
+/// axis and the opposite corner.
+/// Each diamond is split into two faces along the line from the origin to the opposite corner.
+///
+/// - `diamond` - The diamond to get the corners of.
+fn diamond_corners(diamond: u8) -> (usize, usize, usize, usize) {
+    if diamond < 5 {
+        (upper(diamond), 0, lower(diamond), upper(diamond + 1))
+    } else {
+        let index = diamond - 5;
+
+        (lower(index), upper(index + 1), VERTICES - 1, lower(index + 1))
+    }
+}
+
+/// Gets the faces of the icosahedron as the vertices of each face.
+fn faces() -> [[usize; 3]; 20] {
+    let mut faces = [[0; 3]; 20];
+
+    for diamond in 0..10 {
+        let (origin, first, second, opposite) = diamond_corners(diamond);
+
+        faces[diamond as usize * 2] = [origin, first, opposite];
+        faces[diamond as usize * 2 + 1] = [origin, second, opposite];
+    }
+
+    faces
+}
+
+/// Gets the position of a vertex of the icosahedron on the unit sphere.
+///
+/// - `vertex` - The vertex to get the position of.
+fn vertex_position(vertex: usize) -> (f64, f64, f64) {
+    let ring_latitude = 0.5_f64.atan();
+
+    let (latitude, longitude) = match vertex {
+        0 => (PI / 2.0, 0.0),
+        1..=5 => (ring_latitude, (vertex - 1) as f64 * PI * 2.0 / 5.0),
+        6..=10 => (-ring_latitude, (vertex - 6) as f64 * PI * 2.0 / 5.0 + PI / 5.0),
+        _ => (-PI / 2.0, 0.0),
+    };
+
+    (latitude.cos() * longitude.sin(), latitude.sin(), latitude.cos() * longitude.cos())
+}
+
+/// Gets the barycentric coordinates of the point where a ray from the origin through `target`
+/// meets the plane of a face.
+///
+/// - `target` - The direction of the ray.
+/// - `face` - The face to intersect.
+fn barycentric(target: (f64, f64, f64), face: [usize; 3]) -> [f64; 3] {
+    let [a, b, c] = face.map(vertex_position);
+
+    let determinant = |a: (f64, f64, f64), b: (f64, f64, f64), c: (f64, f64, f64)| {
+        a.0 * (b.1 * c.2 - b.2 * c.1) - a.1 * (b.0 * c.2 - b.2 * c.0) + a.2 * (b.0 * c.1 - b.1 * c.0)
+    };
+
+    let coordinates = [
+        determinant(target, b, c),
+        determinant(a, target, c),
+        determinant(a, b, target),
+    ];
+    let total: f64 = coordinates.iter().sum();
+
+    coordinates.map(|coordinate| coordinate / total)
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashSet, f64::consts::PI};
+
+    use approx::assert_relative_eq;
+
+    use crate::{test::assert_indexed_points, GridPoint, IndexedPoint, PolygonPoint, SurfaceGrid, StaticSurfaceGrid, sphere::{SpherePoint, StaticSpherePoint}};
+
+    use super::{IcosaSphereGrid, IcosaSpherePoint, NORTH, SOUTH};
+
+    #[test]
+    fn test_icosa_points_count() {
+        let grid: IcosaSphereGrid<u32, 4> = IcosaSphereGrid::default();
+
+        let points: HashSet<_> = grid.points().collect();
+
+        assert_eq!(10 * 4 * 4 + 2, points.len());
+    }
+
+    #[test]
+    fn test_icosa_pentagons() {
+        let pentagons = IcosaSpherePoint::<4>::all()
+            .filter(|point| point.is_pentagon())
+            .count();
+
+        assert_eq!(12, pentagons);
+    }
+
+    #[test]
+    fn test_icosa_neighbours_count() {
+        for point in IcosaSpherePoint::<4>::all() {
+            let expected = if point.is_pentagon() { 5 } else { 6 };
+
+            assert_eq!(expected, point.neighbours().len(), "{:?}", point);
+        }
+    }
+
+    #[test]
+    fn test_icosa_neighbours_symmetric() {
+        for point in IcosaSpherePoint::<3>::all() {
+            for neighbour in point.neighbours() {
+                assert!(neighbour.neighbours().contains(&point), "{:?} is not a neighbour of {:?}", point, neighbour);
+            }
+        }
+    }
+
+    #[test]
+    fn test_icosa_neighbours_adjacent() {
+        let spacing = 2.0_f64.atan() / 4.0;
+
+        for point in IcosaSpherePoint::<4>::all() {
+            let (x, y, z) = point.position(1.0);
+
+            for neighbour in point.neighbours() {
+                let (nx, ny, nz) = neighbour.position(1.0);
+                let angle = (x * nx + y * ny + z * nz).clamp(-1.0, 1.0).acos();
+
+                assert!(angle > spacing * 0.5 && angle < spacing * 2.0, "{:?} to {:?} is {}", point, neighbour, angle);
+            }
+        }
+    }
+
+    #[test]
+    fn test_icosa_neighbours_anticlockwise() {
+        let point: IcosaSpherePoint<4> = IcosaSpherePoint::new(2, 1, 2);
+        let (x, y, z) = point.position(1.0);
+
+        let neighbours = point.neighbours();
+
+        for (a, b) in neighbours.iter().zip(neighbours.iter().cycle().skip(1)) {
+            let (ax, ay, az) = a.position(1.0);
+            let (bx, by, bz) = b.position(1.0);
+
+            let (ax, ay, az) = (ax - x, ay - y, az - z);
+            let (bx, by, bz) = (bx - x, by - y, bz - z);
+
+            let cross = (ay * bz - az * by, az * bx - ax * bz, ax * by - ay * bx);
+
+            assert!(cross.0 * x + cross.1 * y + cross.2 * z > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_icosa_from_geographic_round_trip() {
+        for point in IcosaSpherePoint::<4>::all() {
+            assert_eq!(point, IcosaSpherePoint::from_geographic(point.latitude(), point.longitude()));
+        }
+    }
+
+    #[test]
+    fn test_icosa_from_geographic_poles() {
+        assert_eq!(IcosaSpherePoint::<4>::new(NORTH, 0, 0), IcosaSpherePoint::from_geographic(PI / 2.0, 1.0));
+        assert_eq!(IcosaSpherePoint::<4>::new(SOUTH, 0, 0), IcosaSpherePoint::from_geographic(-PI / 2.0, 1.0));
+    }
+
+    #[test]
+    fn test_icosa_from_geographic_nearest() {
+        let latitude = 0.3;
+        let longitude = 2.0;
+
+        let point: IcosaSpherePoint<5> = IcosaSpherePoint::from_geographic(latitude, longitude);
+        let target = (latitude.cos() * longitude.sin(), latitude.sin(), latitude.cos() * longitude.cos());
+
+        let distance = |point: &IcosaSpherePoint<5>| {
+            let (x, y, z) = point.position(1.0);
+
+            (x - target.0).powi(2) + (y - target.1).powi(2) + (z - target.2).powi(2)
+        };
+
+        let nearest = IcosaSpherePoint::<5>::all()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+            .unwrap();
+
+        assert_eq!(nearest, point);
+    }
+
+    #[test]
+    fn test_icosa_north_pole_position() {
+        let point: IcosaSpherePoint<4> = IcosaSpherePoint::new(NORTH, 0, 0);
+
+        let (x, y, z) = point.position(2.0);
+
+        assert_relative_eq!(0.0, x);
+        assert_relative_eq!(2.0, y);
+        assert_relative_eq!(0.0, z);
+    }
+
+    #[test]
+    fn test_icosa_up_goes_north() {
+        let point: IcosaSpherePoint<4> = IcosaSpherePoint::from_geographic(0.2, 1.0);
+
+        assert!(point.up().latitude() > point.latitude());
+        assert!(point.down().latitude() < point.latitude());
+    }
+
+    #[test]
+    fn test_icosa_right_goes_east() {
+        let point: IcosaSpherePoint<4> = IcosaSpherePoint::from_geographic(0.2, 1.0);
+
+        assert!(point.right().longitude() > point.longitude());
+        assert!(point.left().longitude() < point.longitude());
+    }
+
+    #[test]
+    fn test_icosa_map_polygon_neighbours() {
+        let grid: IcosaSphereGrid<u32, 4> = IcosaSphereGrid::from_fn(|_| 1);
+
+        let grid2 = grid.map_polygon_neighbours(|_, neighbours| neighbours.iter().copied().sum());
+
+        for (point, value) in grid2.iter() {
+            assert_eq!(if point.is_pentagon() { 5 } else { 6 }, *value);
+        }
+    }
+
+    #[test]
+    fn test_icosa_map_polygon_neighbours_par() {
+        let grid: IcosaSphereGrid<u32, 4> = IcosaSphereGrid::from_fn(|point| point.x + point.y);
+
+        let grid2 = grid.map_polygon_neighbours(|current, neighbours| current + neighbours.iter().copied().sum::<u32>());
+        let grid3 = grid.map_polygon_neighbours_par(|current, neighbours| current + neighbours.iter().copied().sum::<u32>());
+
+        assert_eq!(grid2, grid3);
+    }
+
+    #[test]
+    fn test_icosa_into_iter_matches_points() {
+        let grid: IcosaSphereGrid<u32, 3> = IcosaSphereGrid::from_fn(|point| point.x);
+
+        let points: Vec<_> = grid.points().collect();
+        let values: Vec<_> = grid.into_iter().map(|(point, _)| point).collect();
+
+        assert_eq!(points, values);
+    }
+
+    #[test]
+    fn test_icosa_indexed_points() {
+        assert_eq!(92, IcosaSpherePoint::<3>::COUNT);
+
+        assert_indexed_points(&IcosaSphereGrid::<(), 3>::default());
+    }
+}
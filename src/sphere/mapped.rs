@@ -0,0 +1,269 @@
+//! Rectangle and cube sphere grids stored in snapshot files that are mapped into memory.
+
+use std::{fmt::Debug, io, marker::PhantomData, path::Path};
+
+use crate::{snapshot::{GridKind, SnapshotError}, storage::{MapMode, MmapStorage, Plain}, IndexedPoint};
+
+use super::{CubeProjection, CubeSphereGrid, CubeSpherePoint, Gnomonic, RectangleSphereGrid, RectangleSpherePoint};
+
+/// A `RectangleSphereGrid` stored in a snapshot file that is mapped into memory.
+pub type MappedRectangleSphereGrid<T, const W: usize, const H: usize> = RectangleSphereGrid<T, W, H, MmapStorage<T>>;
+
+/// A `CubeSphereGrid` stored in a snapshot file that is mapped into memory.
+pub type MappedCubeSphereGrid<T, const S: usize, P = Gnomonic> = CubeSphereGrid<T, S, P, MmapStorage<T>>;
+
+impl <T: Plain, const W: usize, const H: usize> RectangleSphereGrid<T, W, H, MmapStorage<T>> {
+    /// Creates a snapshot file and maps it into memory as a grid by calling the specified
+    /// function for each point in the grid.
+    /// Any existing file is replaced.
+    ///
+    /// # Safety
+    /// The file must not be truncated or modified by other processes while it is mapped.
+    ///
+    /// - `path` - The path of the file.
+    /// - `f` - The function to apply.
+    pub unsafe fn create_mapped<Q: AsRef<Path>, F: FnMut(&RectangleSpherePoint<W, H>) -> T>(path: Q, mut f: F) -> io::Result<Self> {
+        let mut data = MmapStorage::create(path.as_ref(), GridKind::Rectangle, W, H, W * H)?;
+
+        for (i, value) in data.iter_mut().enumerate() {
+            *value = f(&RectangleSpherePoint::from_index(i));
+        }
+
+        Ok(Self {
+            data,
+            value: PhantomData,
+        })
+    }
+
+    /// Maps a snapshot file of a grid with the same dimensions into memory.
+    ///
+    /// # Safety
+    /// The file must not be truncated or modified by other processes while it is mapped.
+    ///
+    /// - `path` - The path of the file.
+    /// - `mode` - Whether changes to the grid are written to the file.
+    pub unsafe fn open_mapped<Q: AsRef<Path>>(path: Q, mode: MapMode) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            data: MmapStorage::open(path.as_ref(), mode, GridKind::Rectangle, W, H, W * H)?,
+            value: PhantomData,
+        })
+    }
+
+    /// Writes any changes to this grid to its file.
+    pub fn flush(&self) -> io::Result<()> {
+        self.data.flush()
+    }
+}
+
+impl <T: Plain + Debug, const S: usize, P: CubeProjection> CubeSphereGrid<T, S, P, MmapStorage<T>> {
+    /// Creates a snapshot file and maps it into memory as a grid by calling the specified
+    /// function for each point in the grid.
+    /// Any existing file is replaced.
+    ///
+    /// # Safety
+    /// The file must not be truncated or modified by other processes while it is mapped.
+    ///
+    /// - `path` - The path of the file.
+    /// - `f` - The function to apply.
+    pub unsafe fn create_mapped<Q: AsRef<Path>, F: FnMut(&CubeSpherePoint<S, P>) -> T>(path: Q, mut f: F) -> io::Result<Self> {
+        let mut data = MmapStorage::create(path.as_ref(), GridKind::Cube, S, S, 6 * S * S)?;
+
+        for (i, value) in data.iter_mut().enumerate() {
+            *value = f(&CubeSpherePoint::from_index(i));
+        }
+
+        Ok(Self {
+            data,
+            value: PhantomData,
+            projection: PhantomData,
+        })
+    }
+
+    /// Maps a snapshot file of a grid with the same size into memory.
+    ///
+    /// # Safety
+    /// The file must not be truncated or modified by other processes while it is mapped.
+    ///
+    /// - `path` - The path of the file.
+    /// - `mode` - Whether changes to the grid are written to the file.
+    pub unsafe fn open_mapped<Q: AsRef<Path>>(path: Q, mode: MapMode) -> Result<Self, SnapshotError> {
+        Ok(Self {
+            data: MmapStorage::open(path.as_ref(), mode, GridKind::Cube, S, S, 6 * S * S)?,
+            value: PhantomData,
+            projection: PhantomData,
+        })
+    }
+
+    /// Writes any changes to this grid to its file.
+    pub fn flush(&self) -> io::Result<()> {
+        self.data.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs, path::PathBuf};
+
+    use crate::{snapshot::{Snapshot, SnapshotError}, sphere::{CubeSphereGrid, CubeSpherePoint, CubeFace, RectangleSphereGrid}, storage::MapMode, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    use super::{MappedCubeSphereGrid, MappedRectangleSphereGrid};
+
+    /// Gets a path for a temporary file that is removed when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str) -> Self {
+            Self(env::temp_dir().join(format!("surface-grid-{}-{}.snapshot", std::process::id(), name)))
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn test_mapped_rect_create_open() {
+        let file = TempFile::new("rect-create-open");
+
+        let grid: MappedRectangleSphereGrid<f32, 8, 4> = unsafe {
+            MappedRectangleSphereGrid::create_mapped(&file.0, |point| point.to_index() as f32)
+        }.unwrap();
+
+        grid.flush().unwrap();
+        drop(grid);
+
+        let grid: MappedRectangleSphereGrid<f32, 8, 4> = unsafe {
+            MappedRectangleSphereGrid::open_mapped(&file.0, MapMode::Shared)
+        }.unwrap();
+
+        for (point, value) in grid.iter() {
+            assert_eq!(point.to_index() as f32, *value);
+        }
+    }
+
+    #[test]
+    fn test_mapped_cube_shared_changes_persist() {
+        let file = TempFile::new("cube-shared");
+
+        let mut grid: MappedCubeSphereGrid<u32, 4> = unsafe {
+            MappedCubeSphereGrid::create_mapped(&file.0, |_| 0)
+        }.unwrap();
+
+        let point = CubeSpherePoint::try_new(CubeFace::Back, 1, 2).unwrap();
+        grid[point] = 42;
+
+        let source: CubeSphereGrid<u32, 4> = CubeSphereGrid::from_fn(|point| grid[*point]);
+        grid.set_from_neighbours(&source, |current, up, _, _, _| current + up);
+        grid.flush().unwrap();
+        drop(grid);
+
+        let grid: MappedCubeSphereGrid<u32, 4> = unsafe {
+            MappedCubeSphereGrid::open_mapped(&file.0, MapMode::Shared)
+        }.unwrap();
+
+        assert_eq!(42, grid[point]);
+        assert_eq!(42, grid[point.down()]);
+    }
+
+    #[test]
+    fn test_mapped_private_changes_discarded() {
+        let file = TempFile::new("rect-private");
+
+        drop(unsafe { MappedRectangleSphereGrid::<u8, 4, 4>::create_mapped(&file.0, |_| 1) }.unwrap());
+
+        let mut first: MappedRectangleSphereGrid<u8, 4, 4> = unsafe {
+            MappedRectangleSphereGrid::open_mapped(&file.0, MapMode::Private)
+        }.unwrap();
+        let second: MappedRectangleSphereGrid<u8, 4, 4> = unsafe {
+            MappedRectangleSphereGrid::open_mapped(&file.0, MapMode::Private)
+        }.unwrap();
+
+        first.set_from_fn(|_| 9);
+        first.flush().unwrap();
+
+        assert!(second.iter().all(|(_, value)| *value == 1));
+        drop(first);
+
+        let grid: MappedRectangleSphereGrid<u8, 4, 4> = unsafe {
+            MappedRectangleSphereGrid::open_mapped(&file.0, MapMode::Private)
+        }.unwrap();
+
+        assert!(grid.iter().all(|(_, value)| *value == 1));
+    }
+
+    #[test]
+    fn test_mapped_snapshot_compatible() {
+        let file = TempFile::new("cube-snapshot");
+
+        let grid: CubeSphereGrid<[u16; 2], 5> = CubeSphereGrid::from_fn(|point| [point.x() as u16, point.to_index() as u16]);
+
+        let mut bytes = Vec::new();
+        grid.write_snapshot(&mut bytes).unwrap();
+        fs::write(&file.0, &bytes).unwrap();
+
+        let mapped: MappedCubeSphereGrid<[u16; 2], 5> = unsafe {
+            MappedCubeSphereGrid::open_mapped(&file.0, MapMode::Private)
+        }.unwrap();
+
+        assert!(grid.iter().eq(mapped.iter()));
+
+        let result = CubeSphereGrid::<[u16; 2], 5>::read_snapshot(&fs::read(&file.0).unwrap()[..]).unwrap();
+
+        assert_eq!(grid, result);
+    }
+
+    #[test]
+    fn test_mapped_wrong_dimensions() {
+        let file = TempFile::new("rect-wrong");
+
+        drop(unsafe { MappedRectangleSphereGrid::<u8, 4, 2>::create_mapped(&file.0, |_| 1) }.unwrap());
+
+        assert!(matches!(
+            unsafe { MappedRectangleSphereGrid::<u8, 2, 4>::open_mapped(&file.0, MapMode::Private) },
+            Err(SnapshotError::DimensionMismatch { width: 4, height: 2 })
+        ));
+        assert!(matches!(
+            unsafe { MappedRectangleSphereGrid::<u16, 4, 2>::open_mapped(&file.0, MapMode::Private) },
+            Err(SnapshotError::ElementSizeMismatch { expected: 2, found: 1 })
+        ));
+        assert!(matches!(
+            unsafe { MappedCubeSphereGrid::<u8, 4>::open_mapped(&file.0, MapMode::Private) },
+            Err(SnapshotError::KindMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_mapped_truncated() {
+        let file = TempFile::new("rect-truncated");
+
+        drop(unsafe { MappedRectangleSphereGrid::<u32, 4, 2>::create_mapped(&file.0, |_| 1) }.unwrap());
+
+        let bytes = fs::read(&file.0).unwrap();
+        fs::write(&file.0, &bytes[..bytes.len() - 1]).unwrap();
+
+        assert!(matches!(
+            unsafe { MappedRectangleSphereGrid::<u32, 4, 2>::open_mapped(&file.0, MapMode::Private) },
+            Err(SnapshotError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_mapped_same_size_from_fn() {
+        let file = TempFile::new("rect-same-size");
+
+        let grid: MappedRectangleSphereGrid<i64, 6, 3> = unsafe {
+            MappedRectangleSphereGrid::create_mapped(&file.0, |point| point.to_index() as i64)
+        }.unwrap();
+
+        // New grids made from a mapped grid are held in anonymous memory.
+        let doubled = grid.map_neighbours(|current, _, _, _, _| current * 2);
+
+        assert!(doubled.iter().all(|(point, value)| *value == point.to_index() as i64 * 2));
+        assert_eq!(
+            RectangleSphereGrid::<i64, 6, 3>::from_fn(|point| point.to_index() as i64 * 2).into_iter().collect::<Vec<_>>(),
+            doubled.into_iter().collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,568 @@
+//! An equal area grid made by unfolding an octahedron.
+
+use std::{f64::consts::PI, ops::{Index, IndexMut}, vec};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use static_array::HeapArray2D;
+
+use crate::{GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+use super::{SpherePoint, StaticSpherePoint};
+
+/// A grid wrapped around a sphere by splitting each face of an octahedron into triangles of
+/// cells.
+///
+/// The northern and southern faces that meet at each edge of the equator of the octahedron form a
+/// diamond from pole to pole, and each of the 4 diamonds is split into `N` by `N` diamond shaped
+/// cells.
+/// This gives `4 * N * N` cells that all have exactly the same area.
+/// The cell centres lie on rings of equal latitude with 4 cells in the ring around each pole and 4
+/// more in each ring towards the equator, in the same way as octahedral reduced Gaussian grids.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+///
+/// # Constant Parameters
+/// - `N` - The number of cells along each side of each diamond.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct OctaSphereGrid<T, const N: usize> {
+    diamonds: [HeapArray2D<T, N, N>; 4],
+}
+
+impl <T, const N: usize> SurfaceGrid<T> for OctaSphereGrid<T, N> {
+    type Point = OctaSpherePoint<N>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        for point in OctaSpherePoint::all() {
+            self[point] = f(&point);
+        }
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        for (diamond, data) in self.diamonds.iter_mut().enumerate() {
+            data.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+                for (x, value) in subarray.iter_mut().enumerate() {
+                    *value = f(&OctaSpherePoint::new(diamond as u8, x as u32, y as u32));
+                }
+            });
+        }
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        self.points()
+            .map(|point| (point, &self[point]))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        self.par_points()
+            .map(|point| (point, &self[point]))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        OctaSpherePoint::all()
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        OctaSpherePoint::all().par_bridge()
+    }
+}
+
+impl <T, const N: usize> StaticSurfaceGrid<T> for OctaSphereGrid<T, N> {
+    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
+        Self {
+            diamonds: std::array::from_fn(|diamond| HeapArray2D::from_fn(|y, x| f(&OctaSpherePoint::new(diamond as u8, x as u32, y as u32)))),
+        }
+    }
+
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self {
+            diamonds: std::array::from_fn(|diamond| HeapArray2D::from_fn_par(|y, x| f(&OctaSpherePoint::new(diamond as u8, x as u32, y as u32)))),
+        }
+    }
+}
+
+impl <T, const N: usize> Index<OctaSpherePoint<N>> for OctaSphereGrid<T, N> {
+    type Output = T;
+
+    fn index(&self, index: OctaSpherePoint<N>) -> &Self::Output {
+        &self.diamonds[index.diamond as usize][index.y as usize][index.x as usize]
+    }
+}
+
+impl <T, const N: usize> IndexMut<OctaSpherePoint<N>> for OctaSphereGrid<T, N> {
+    fn index_mut(&mut self, index: OctaSpherePoint<N>) -> &mut Self::Output {
+        &mut self.diamonds[index.diamond as usize][index.y as usize][index.x as usize]
+    }
+}
+
+impl <T, const N: usize> IntoIterator for OctaSphereGrid<T, N> {
+    type Item = (OctaSpherePoint<N>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let data: Vec<_> = self.diamonds.into_iter()
+            .enumerate()
+            .flat_map(|(diamond, data)| data.into_iter()
+                      .enumerate()
+                      .flat_map(move |(y, subarray)| subarray.into_iter()
+                                .enumerate()
+                                .map(move |(x, value)| (OctaSpherePoint::new(diamond as u8, x as u32, y as u32), value))
+                                ))
+            .collect();
+
+        data.into_iter()
+    }
+}
+
+/// A point on an `OctaSphereGrid`.
+///
+/// Each cell is a diamond with corners pointing north, east, south and west.
+/// Moving `up` goes to the neighbour to the north west, `right` to the north east, `down` to the
+/// south east and `left` to the south west.
+/// As with `CubeSpherePoint`, the directions can rotate when moving from one diamond to another.
+/// The 4 cells touching the vertices of the octahedron on the equator share two of their sides
+/// with the same neighbour.
+///
+/// # Constant Parameters
+/// - `N` - The number of cells along each side of each diamond.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OctaSpherePoint<const N: usize> {
+    /// The diamond of two octahedron faces that the point is on.
+    diamond: u8,
+    /// The position from the north pole towards the western corner of the diamond.
+    x: u32,
+    /// The position from the north pole towards the eastern corner of the diamond.
+    y: u32,
+}
+
+impl <const N: usize> OctaSpherePoint<N> {
+    fn new(diamond: u8, x: u32, y: u32) -> Self {
+        Self {
+            diamond: diamond % 4,
+            x: x.min(N as u32 - 1),
+            y: y.min(N as u32 - 1),
+        }
+    }
+
+    /// Iterates over every point on the sphere in the order that the grid stores them.
+    fn all() -> impl Iterator<Item = Self> {
+        (0..4).cartesian_product(0..N).cartesian_product(0..N)
+            .map(|((diamond, y), x)| Self::new(diamond, x as u32, y as u32))
+    }
+
+    /// Gets the index of this point when the cells are ordered in rings of equal latitude from
+    /// north to south, with each ring starting at longitude 0.
+    pub fn ring_index(&self) -> usize {
+        let ring = (self.x + self.y) as usize;
+        let first = ring.saturating_sub(N - 1);
+
+        ring_start::<N>(ring) + self.diamond as usize * ring_length::<N>(ring) + self.y as usize - first
+    }
+
+    /// Gets the point with the specified index in the ring ordering.
+    /// Returns `None` if the index is outside the grid.
+    ///
+    /// - `index` - The index of the point.
+    pub fn from_ring_index(index: usize) -> Option<Self> {
+        if index >= 4 * N * N {
+            return None;
+        }
+
+        // Binary search for the last ring starting at or before the index.
+        let mut low = 0;
+        let mut high = 2 * N - 1;
+        while high - low > 1 {
+            let middle = (low + high) / 2;
+
+            if ring_start::<N>(middle) <= index {
+                low = middle;
+            } else {
+                high = middle;
+            }
+        }
+
+        let ring = low;
+        let length = ring_length::<N>(ring);
+        let offset = index - ring_start::<N>(ring);
+
+        let y = ring.saturating_sub(N - 1) + offset % length;
+
+        Some(Self::new((offset / length) as u8, (ring - y) as u32, y as u32))
+    }
+
+    /// Gets the sine of the latitude and the longitude of a position in the diamond of this
+    /// point.
+    ///
+    /// - `s` - The position towards the western corner of the diamond from 0 to 1.
+    /// - `t` - The position towards the eastern corner of the diamond from 0 to 1.
+    fn height_longitude(&self, s: f64, t: f64) -> (f64, f64) {
+        // The distance from the nearest pole decides the latitude and the position across the
+        // face decides the longitude, which keeps the area of every cell the same.
+        let (height, along) = if s + t <= 1.0 {
+            let distance = s + t;
+
+            (1.0 - distance * distance, t / distance)
+        } else {
+            let distance = 2.0 - s - t;
+
+            (distance * distance - 1.0, (1.0 - s) / distance)
+        };
+
+        (height, (self.diamond as f64 + along) * PI / 2.0)
+    }
+
+    /// Gets the sine of the latitude and the longitude of the centre of this cell.
+    fn centre(&self) -> (f64, f64) {
+        let size = N as f64;
+
+        self.height_longitude((self.x as f64 + 0.5) / size, (self.y as f64 + 0.5) / size)
+    }
+
+    /// Gets the neighbouring point in the specified direction.
+    ///
+    /// - `dx` - The change in the X position which must be -1, 0 or 1.
+    /// - `dy` - The change in the Y position which must be -1, 0 or 1 when `dx` is 0, otherwise
+    ///   it must be 0.
+    fn step(&self, dx: i64, dy: i64) -> Self {
+        let size = N as i64;
+        let x = self.x as i64 + dx;
+        let y = self.y as i64 + dy;
+
+        // Each diamond shares its northern edges with the diamonds to either side of it, and its
+        // southern edges with the same diamonds.
+        let east = (self.diamond + 1) % 4;
+        let west = (self.diamond + 3) % 4;
+
+        if x < 0 {
+            Self::new(east, self.y, 0)
+        } else if y < 0 {
+            Self::new(west, 0, self.x)
+        } else if x >= size {
+            Self::new(west, self.y, N as u32 - 1)
+        } else if y >= size {
+            Self::new(east, N as u32 - 1, self.x)
+        } else {
+            Self::new(self.diamond, x as u32, y as u32)
+        }
+    }
+}
+
+impl <const N: usize> GridPoint for OctaSpherePoint<N> {
+    fn up(&self) -> Self {
+        self.step(0, -1)
+    }
+
+    fn down(&self) -> Self {
+        self.step(0, 1)
+    }
+
+    fn left(&self) -> Self {
+        self.step(1, 0)
+    }
+
+    fn right(&self) -> Self {
+        self.step(-1, 0)
+    }
+
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        let (height, longitude) = self.centre();
+
+        let radius = (1.0 - height * height).max(0.0).sqrt() * scale;
+
+        (radius * longitude.sin(), height * scale, radius * longitude.cos())
+    }
+}
+
+impl <const N: usize> IndexedPoint for OctaSpherePoint<N> {
+    const COUNT: usize = 4 * N * N;
+
+    fn to_index(&self) -> usize {
+        (self.diamond as usize * N + self.y as usize) * N + self.x as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        Self::new((index / (N * N)) as u8, (index % N) as u32, (index / N % N) as u32)
+    }
+}
+
+impl <const N: usize> SpherePoint for OctaSpherePoint<N> {
+    fn at_geographic(&self, latitude: f64, longitude: f64) -> Self {
+        Self::from_geographic(latitude, longitude)
+    }
+
+    fn latitude(&self) -> f64 {
+        let (height, _) = self.centre();
+
+        height.asin()
+    }
+
+    fn longitude(&self) -> f64 {
+        let (_, longitude) = self.centre();
+
+        longitude
+    }
+}
+
+impl <const N: usize> StaticSpherePoint for OctaSpherePoint<N> {
+    fn from_geographic(latitude: f64, longitude: f64) -> Self {
+        let quarter = (longitude * 2.0 / PI).rem_euclid(4.0);
+        let diamond = (quarter as u8).min(3);
+        let along = quarter - diamond as f64;
+
+        let height = latitude.sin();
+
+        let (s, t) = if height >= 0.0 {
+            let distance = (1.0 - height).sqrt();
+
+            (distance * (1.0 - along), distance * along)
+        } else {
+            let distance = (1.0 + height).sqrt();
+
+            (1.0 - distance * along, 1.0 - distance * (1.0 - along))
+        };
+
+        let size = N as f64;
+
+        Self::new(diamond, (s * size).max(0.0) as u32, (t * size).max(0.0) as u32)
+    }
+}
+
+/// Gets the number of cells in a ring in each diamond.
+///
+/// - `ring` - The ring counting from 0 at the north pole.
+fn ring_length<const N: usize>(ring: usize) -> usize {
+    (ring + 1).min(2 * N - 1 - ring)
+}
+
+/// Gets the ring index of the first cell in a ring.
+///
+/// - `ring` - The ring counting from 0 at the north pole.
+fn ring_start<const N: usize>(ring: usize) -> usize {
+    if ring < N {
+        2 * ring * (ring + 1)
+    } else {
+        let south = 2 * N - 1 - ring;
+
+        4 * N * N - 2 * south * (south + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::{HashMap, HashSet}, f64::consts::PI};
+
+    use approx::assert_relative_eq;
+
+    use crate::{test::assert_indexed_points, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid, sphere::{SpherePoint, StaticSpherePoint}};
+
+    use super::{OctaSphereGrid, OctaSpherePoint};
+
+    #[test]
+    fn test_octa_point_count() {
+        let grid: OctaSphereGrid<(), 5> = OctaSphereGrid::default();
+
+        assert_eq!(100, grid.points().count());
+        assert_eq!(100, grid.points().collect::<HashSet<_>>().len());
+    }
+
+    #[test]
+    fn test_octa_ring_round_trip() {
+        for i in 0..4 * 5 * 5 {
+            let point: OctaSpherePoint<5> = OctaSpherePoint::from_ring_index(i).unwrap();
+
+            assert_eq!(i, point.ring_index());
+        }
+    }
+
+    #[test]
+    fn test_octa_out_of_range() {
+        assert_eq!(None, OctaSpherePoint::<5>::from_ring_index(100));
+    }
+
+    #[test]
+    fn test_octa_ring_order_by_latitude() {
+        let points: Vec<OctaSpherePoint<6>> = (0..4 * 6 * 6)
+            .map(|i| OctaSpherePoint::from_ring_index(i).unwrap())
+            .collect();
+
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+
+            if (a.latitude() - b.latitude()).abs() < 1e-12 {
+                assert!(a.longitude() < b.longitude());
+            } else {
+                assert!(a.latitude() > b.latitude());
+            }
+        }
+    }
+
+    #[test]
+    fn test_octa_ring_lengths() {
+        let mut rings: Vec<(f64, usize)> = Vec::new();
+
+        for i in 0..4 * 4 * 4 {
+            let point: OctaSpherePoint<4> = OctaSpherePoint::from_ring_index(i).unwrap();
+
+            match rings.last_mut() {
+                Some((latitude, count)) if (*latitude - point.latitude()).abs() < 1e-12 => *count += 1,
+                _ => rings.push((point.latitude(), 1)),
+            }
+        }
+
+        assert_eq!(vec![4, 8, 12, 16, 12, 8, 4], rings.iter().map(|(_, count)| *count).collect::<Vec<_>>());
+        assert_relative_eq!(0.0, rings[3].0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_octa_from_geographic_round_trip() {
+        let grid: OctaSphereGrid<(), 7> = OctaSphereGrid::default();
+
+        for point in grid.points() {
+            assert_eq!(point, point.at_geographic(point.latitude(), point.longitude()));
+        }
+    }
+
+    #[test]
+    fn test_octa_from_geographic_poles() {
+        let north: OctaSpherePoint<4> = OctaSpherePoint::from_geographic(PI / 2.0, 0.3);
+        let south: OctaSpherePoint<4> = OctaSpherePoint::from_geographic(-PI / 2.0, 0.3);
+
+        assert_eq!(OctaSpherePoint::new(0, 0, 0), north);
+        assert_eq!(OctaSpherePoint::new(0, 3, 3), south);
+    }
+
+    #[test]
+    fn test_octa_equal_area() {
+        let mut counts = HashMap::new();
+
+        let steps = 600;
+        for i in 0..steps {
+            let height = -1.0 + (i as f64 + 0.5) * 2.0 / steps as f64;
+
+            for j in 0..steps {
+                let longitude = (j as f64 + 0.5) * 2.0 * PI / steps as f64;
+
+                let point: OctaSpherePoint<3> = OctaSpherePoint::from_geographic(height.asin(), longitude);
+
+                *counts.entry(point).or_insert(0) += 1;
+            }
+        }
+
+        let expected = (steps * steps) as f64 / 36.0;
+
+        assert_eq!(36, counts.len());
+        for count in counts.values() {
+            assert!((*count as f64 - expected).abs() < expected * 0.02);
+        }
+    }
+
+    #[test]
+    fn test_octa_neighbours_symmetric() {
+        let grid: OctaSphereGrid<(), 4> = OctaSphereGrid::default();
+
+        for point in grid.points() {
+            for neighbour in [point.up(), point.down(), point.left(), point.right()] {
+                assert!([neighbour.up(), neighbour.down(), neighbour.left(), neighbour.right()].contains(&point),
+                    "{:?} is not a neighbour of {:?}", point, neighbour);
+            }
+        }
+    }
+
+    #[test]
+    fn test_octa_neighbours_adjacent() {
+        let grid: OctaSphereGrid<(), 4> = OctaSphereGrid::default();
+
+        for point in grid.points() {
+            let (x, y, z) = point.position(1.0);
+
+            let neighbours: HashSet<_> = [point.up(), point.down(), point.left(), point.right()].into_iter().collect();
+
+            for neighbour in neighbours {
+                assert_ne!(point, neighbour);
+
+                let (nx, ny, nz) = neighbour.position(1.0);
+                let angle = (x * nx + y * ny + z * nz).clamp(-1.0, 1.0).acos();
+
+                assert!(angle < 0.6, "{:?} to {:?} is {}", point, neighbour, angle);
+            }
+        }
+    }
+
+    #[test]
+    fn test_octa_equator_vertex() {
+        let point: OctaSpherePoint<4> = OctaSpherePoint::new(1, 3, 0);
+
+        assert_eq!(OctaSpherePoint::new(0, 0, 3), point.up());
+        assert_eq!(OctaSpherePoint::new(0, 0, 3), point.left());
+    }
+
+    #[test]
+    fn test_octa_north_pole() {
+        let point: OctaSpherePoint<4> = OctaSpherePoint::new(0, 0, 0);
+
+        assert_eq!(OctaSpherePoint::new(1, 0, 0), point.right());
+        assert_eq!(OctaSpherePoint::new(3, 0, 0), point.up());
+        assert_eq!(OctaSpherePoint::new(0, 1, 0), point.left());
+        assert_eq!(OctaSpherePoint::new(0, 0, 1), point.down());
+    }
+
+    #[test]
+    fn test_octa_directions() {
+        let point: OctaSpherePoint<8> = OctaSpherePoint::from_geographic(0.3, 1.0);
+
+        assert!(point.up().latitude() > point.latitude());
+        assert!(point.up().longitude() < point.longitude());
+        assert!(point.right().latitude() > point.latitude());
+        assert!(point.right().longitude() > point.longitude());
+        assert!(point.down().latitude() < point.latitude());
+        assert!(point.down().longitude() > point.longitude());
+        assert!(point.left().latitude() < point.latitude());
+        assert!(point.left().longitude() < point.longitude());
+    }
+
+    #[test]
+    fn test_octa_position_matches_geographic() {
+        let point: OctaSpherePoint<8> = OctaSpherePoint::from_geographic(-0.4, 4.0);
+
+        let (x, y, z) = point.position(2.0);
+
+        assert_relative_eq!(2.0 * point.latitude().sin(), y, epsilon = 1e-12);
+        assert_relative_eq!(point.longitude(), x.atan2(z).rem_euclid(2.0 * PI), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_octa_from_fn_par() {
+        let grid: OctaSphereGrid<usize, 6> = OctaSphereGrid::from_fn_par(|point| point.ring_index());
+
+        assert_eq!(OctaSphereGrid::from_fn(|point: &OctaSpherePoint<6>| point.ring_index()), grid);
+        assert!(grid.into_iter().all(|(point, value)| point.ring_index() == value));
+    }
+
+    #[test]
+    fn test_octa_from_neighbours() {
+        let grid: OctaSphereGrid<u32, 4> = OctaSphereGrid::from_fn(|_| 1);
+
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert!(grid2.iter().all(|(_, value)| *value == 5));
+    }
+
+    #[test]
+    fn test_octa_indexed_points() {
+        assert_eq!(36, OctaSpherePoint::<3>::COUNT);
+
+        assert_indexed_points(&OctaSphereGrid::<(), 3>::default());
+    }
+}
@@ -0,0 +1,260 @@
+//! Serialization of sphere grids and points with serde.
+//!
+//! Grids are stored with their dimensions followed by the value of every point in the order
+//! given by `IndexedPoint`.
+//! The dimensions and coordinates are checked against the const generics when deserializing.
+
+use std::{fmt::Debug, marker::PhantomData};
+
+use serde::{de::Error, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{storage::Storage, IndexedPoint, StaticSurfaceGrid};
+
+use super::{CubeFace, CubeProjection, CubeSphereGrid, CubeSpherePoint, RectangleSphereGrid, RectangleSpherePoint};
+
+/// The values of every point on a grid in index order.
+struct Values<'a, T, P, G>(&'a G, PhantomData<(T, P)>);
+
+impl <'a, T: Serialize, P: IndexedPoint, G: std::ops::Index<P, Output = T>> Serialize for Values<'a, T, P, G> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq((0..P::COUNT).map(|i| &self.0[P::from_index(i)]))
+    }
+}
+
+/// Takes the values read for a grid so that each one can be moved into its point.
+///
+/// - `data` - The values in index order.
+/// - `count` - The number of points on the grid.
+fn take_values<T, E: Error>(data: Vec<T>, count: usize) -> Result<Vec<Option<T>>, E> {
+    if data.len() != count {
+        return Err(E::invalid_length(data.len(), &count.to_string().as_str()));
+    }
+
+    Ok(data.into_iter().map(Some).collect())
+}
+
+/// The serialized form of a `RectangleSphereGrid`.
+#[derive(Deserialize)]
+#[serde(rename = "RectangleSphereGrid")]
+struct RectangleSphereGridData<T> {
+    width: usize,
+    height: usize,
+    data: Vec<T>,
+}
+
+impl <T: Serialize, const W: usize, const H: usize, D: Storage<T>> Serialize for RectangleSphereGrid<T, W, H, D> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("RectangleSphereGrid", 3)?;
+
+        state.serialize_field("width", &W)?;
+        state.serialize_field("height", &H)?;
+        state.serialize_field("data", &Values::<T, RectangleSpherePoint<W, H>, _>(self, PhantomData))?;
+
+        state.end()
+    }
+}
+
+impl <'de, T: Deserialize<'de>, const W: usize, const H: usize, D: Storage<T>> Deserialize<'de> for RectangleSphereGrid<T, W, H, D> {
+    fn deserialize<Z: Deserializer<'de>>(deserializer: Z) -> Result<Self, Z::Error> {
+        let grid = RectangleSphereGridData::<T>::deserialize(deserializer)?;
+
+        if grid.width != W || grid.height != H {
+            return Err(Z::Error::custom(format!(
+                "expected a grid of {}x{} but found {}x{}", W, H, grid.width, grid.height
+            )));
+        }
+
+        let mut data = take_values(grid.data, W * H)?;
+
+        Ok(Self::from_fn(|point| data[point.to_index()].take().expect("Each point is only visited once")))
+    }
+}
+
+/// The serialized form of a `CubeSphereGrid`.
+#[derive(Deserialize)]
+#[serde(rename = "CubeSphereGrid")]
+struct CubeSphereGridData<T> {
+    size: usize,
+    data: Vec<T>,
+}
+
+impl <T: Serialize, const S: usize, P: CubeProjection, D: Storage<T>> Serialize for CubeSphereGrid<T, S, P, D> {
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        let mut state = serializer.serialize_struct("CubeSphereGrid", 2)?;
+
+        state.serialize_field("size", &S)?;
+        state.serialize_field("data", &Values::<T, CubeSpherePoint<S, P>, _>(self, PhantomData))?;
+
+        state.end()
+    }
+}
+
+impl <'de, T: Deserialize<'de> + Debug, const S: usize, P: CubeProjection, D: Storage<T>> Deserialize<'de> for CubeSphereGrid<T, S, P, D> {
+    fn deserialize<Z: Deserializer<'de>>(deserializer: Z) -> Result<Self, Z::Error> {
+        let grid = CubeSphereGridData::<T>::deserialize(deserializer)?;
+
+        if grid.size != S {
+            return Err(Z::Error::custom(format!("expected a grid of size {} but found {}", S, grid.size)));
+        }
+
+        let mut data = take_values(grid.data, 6 * S * S)?;
+
+        Ok(Self::from_fn(|point| data[point.to_index()].take().expect("Each point is only visited once")))
+    }
+}
+
+/// The serialized form of a `RectangleSpherePoint`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "RectangleSpherePoint")]
+struct RectangleSpherePointData {
+    x: usize,
+    y: usize,
+}
+
+impl <const W: usize, const H: usize> Serialize for RectangleSpherePoint<W, H> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RectangleSpherePointData {
+            x: self.x as usize,
+            y: self.y as usize,
+        }.serialize(serializer)
+    }
+}
+
+impl <'de, const W: usize, const H: usize> Deserialize<'de> for RectangleSpherePoint<W, H> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let point = RectangleSpherePointData::deserialize(deserializer)?;
+
+        Self::try_new(point.x, point.y).map_err(D::Error::custom)
+    }
+}
+
+/// The serialized form of a `CubeSpherePoint`.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "CubeSpherePoint")]
+struct CubeSpherePointData {
+    face: CubeFace,
+    x: usize,
+    y: usize,
+}
+
+impl <const S: usize, P: CubeProjection> Serialize for CubeSpherePoint<S, P> {
+    fn serialize<Z: Serializer>(&self, serializer: Z) -> Result<Z::Ok, Z::Error> {
+        CubeSpherePointData {
+            face: self.face,
+            x: self.x as usize,
+            y: self.y as usize,
+        }.serialize(serializer)
+    }
+}
+
+impl <'de, const S: usize, P: CubeProjection> Deserialize<'de> for CubeSpherePoint<S, P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let point = CubeSpherePointData::deserialize(deserializer)?;
+
+        Self::try_new(point.face, point.x, point.y).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{sphere::{CubeFace, CubeSphereGrid, CubeSpherePoint, EquiangularCubeSphereGrid, RectangleSphereGrid, RectangleSpherePoint}, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    #[test]
+    fn test_serde_rect_grid_round_trip() {
+        let grid: RectangleSphereGrid<usize, 5, 3> = RectangleSphereGrid::from_fn(|point| point.to_index());
+
+        let json = serde_json::to_string(&grid).unwrap();
+
+        assert_eq!(r#"{"width":5,"height":3,"data":[0,1,2,3,4,5,6,7,8,9,10,11,12,13,14]}"#, json);
+        assert_eq!(grid, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_serde_rect_grid_wrong_size() {
+        let json = r#"{"width":5,"height":3,"data":[0,1,2,3,4,5,6,7,8,9,10,11,12,13,14]}"#;
+
+        assert!(serde_json::from_str::<RectangleSphereGrid<usize, 3, 5>>(json).is_err());
+        assert!(serde_json::from_str::<RectangleSphereGrid<usize, 5, 4>>(json).is_err());
+    }
+
+    #[test]
+    fn test_serde_rect_grid_wrong_length() {
+        let json = r#"{"width":2,"height":2,"data":[0,1,2]}"#;
+
+        assert!(serde_json::from_str::<RectangleSphereGrid<usize, 2, 2>>(json).is_err());
+    }
+
+    #[test]
+    fn test_serde_cube_grid_round_trip() {
+        let grid: CubeSphereGrid<String, 3> = CubeSphereGrid::from_fn(|point| format!("{:?}", point));
+
+        let json = serde_json::to_string(&grid).unwrap();
+        let result: CubeSphereGrid<String, 3> = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(grid, result);
+
+        for (point, value) in result.iter() {
+            assert_eq!(format!("{:?}", point), *value);
+        }
+    }
+
+    #[test]
+    fn test_serde_cube_grid_index_order() {
+        let grid: EquiangularCubeSphereGrid<usize, 2> = EquiangularCubeSphereGrid::from_fn(|point| point.to_index());
+
+        let json = serde_json::to_value(&grid).unwrap();
+
+        assert_eq!(2, json["size"]);
+        assert_eq!((0..24).collect::<Vec<_>>(), serde_json::from_value::<Vec<usize>>(json["data"].clone()).unwrap());
+    }
+
+    #[test]
+    fn test_serde_cube_grid_wrong_size() {
+        let grid: CubeSphereGrid<u8, 3> = CubeSphereGrid::default();
+
+        let json = serde_json::to_string(&grid).unwrap();
+
+        let error = serde_json::from_str::<CubeSphereGrid<u8, 4>>(&json).unwrap_err();
+
+        assert!(error.to_string().contains("expected a grid of size 4 but found 3"));
+    }
+
+    #[test]
+    fn test_serde_rect_point_round_trip() {
+        let point: RectangleSpherePoint<10, 6> = RectangleSpherePoint::try_new(7, 2).unwrap();
+
+        let json = serde_json::to_string(&point).unwrap();
+
+        assert_eq!(r#"{"x":7,"y":2}"#, json);
+        assert_eq!(point, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_serde_rect_point_out_of_range() {
+        let error = serde_json::from_str::<RectangleSpherePoint<10, 6>>(r#"{"x":3,"y":6}"#).unwrap_err();
+
+        assert!(error.to_string().contains("Y coordinate 6 is outside a grid of height 6"));
+    }
+
+    #[test]
+    fn test_serde_cube_point_round_trip() {
+        let point: CubeSpherePoint<8> = CubeSpherePoint::try_new(CubeFace::Back, 1, 5).unwrap();
+
+        let json = serde_json::to_string(&point).unwrap();
+
+        assert_eq!(r#"{"face":"Back","x":1,"y":5}"#, json);
+        assert_eq!(point, serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_serde_cube_point_out_of_range() {
+        assert!(serde_json::from_str::<CubeSpherePoint<8>>(r#"{"face":"Top","x":8,"y":0}"#).is_err());
+        assert!(serde_json::from_str::<CubeSpherePoint<8>>(r#"{"face":"Side","x":0,"y":0}"#).is_err());
+    }
+
+    #[test]
+    fn test_serde_cube_face() {
+        assert_eq!(r#""Bottom""#, serde_json::to_string(&CubeFace::Bottom).unwrap());
+        assert_eq!(CubeFace::Left, serde_json::from_str(r#""Left""#).unwrap());
+    }
+}
@@ -0,0 +1,149 @@
+//! Snapshots of rectangle and cube sphere grids.
+
+use std::{fmt::Debug, marker::PhantomData};
+
+use crate::{snapshot::{GridKind, Pod, Snapshot, SnapshotError}, storage::Storage, IndexedPoint};
+
+use super::{CubeProjection, CubeSphereGrid, CubeSpherePoint, DynCubeSphereGrid, DynRectangleSphereGrid, RectangleSphereGrid, RectangleSpherePoint};
+
+impl <T: Pod, const W: usize, const H: usize, D: Storage<T>> Snapshot<T> for RectangleSphereGrid<T, W, H, D> {
+    const KIND: GridKind = GridKind::Rectangle;
+
+    fn snapshot_dimensions(&self) -> (usize, usize) {
+        (W, H)
+    }
+
+    fn snapshot_len(width: u64, height: u64) -> Result<usize, SnapshotError> {
+        if width != W as u64 || height != H as u64 {
+            return Err(SnapshotError::DimensionMismatch { width, height });
+        }
+
+        Ok(RectangleSpherePoint::<W, H>::COUNT)
+    }
+
+    fn from_snapshot_values(_: u64, _: u64, values: Vec<T>) -> Self {
+        Self {
+            data: D::from_vec(values),
+            value: PhantomData,
+        }
+    }
+}
+
+impl <T: Pod + Debug, const S: usize, P: CubeProjection + 'static, D: Storage<T>> Snapshot<T> for CubeSphereGrid<T, S, P, D> {
+    const KIND: GridKind = GridKind::Cube;
+
+    fn snapshot_dimensions(&self) -> (usize, usize) {
+        (S, S)
+    }
+
+    fn snapshot_len(width: u64, height: u64) -> Result<usize, SnapshotError> {
+        if width != S as u64 || height != S as u64 {
+            return Err(SnapshotError::DimensionMismatch { width, height });
+        }
+
+        Ok(CubeSpherePoint::<S, P>::COUNT)
+    }
+
+    fn from_snapshot_values(_: u64, _: u64, values: Vec<T>) -> Self {
+        Self {
+            data: D::from_vec(values),
+            value: PhantomData,
+            projection: PhantomData,
+        }
+    }
+}
+
+impl <T: Pod> Snapshot<T> for DynRectangleSphereGrid<T> {
+    const KIND: GridKind = GridKind::Rectangle;
+
+    fn snapshot_dimensions(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    fn snapshot_len(width: u64, height: u64) -> Result<usize, SnapshotError> {
+        if width == 0 || height == 0 || width > u32::MAX as u64 || height > u32::MAX as u64 {
+            return Err(SnapshotError::DimensionMismatch { width, height });
+        }
+
+        (width as usize).checked_mul(height as usize)
+            .ok_or(SnapshotError::DimensionMismatch { width, height })
+    }
+
+    fn from_snapshot_values(width: u64, height: u64, values: Vec<T>) -> Self {
+        Self {
+            width: width as usize,
+            height: height as usize,
+            data: values,
+        }
+    }
+}
+
+impl <T: Pod> Snapshot<T> for DynCubeSphereGrid<T> {
+    const KIND: GridKind = GridKind::Cube;
+
+    fn snapshot_dimensions(&self) -> (usize, usize) {
+        (self.size, self.size)
+    }
+
+    fn snapshot_len(width: u64, height: u64) -> Result<usize, SnapshotError> {
+        if width != height || width == 0 || width > u16::MAX as u64 {
+            return Err(SnapshotError::DimensionMismatch { width, height });
+        }
+
+        (width as usize).checked_mul(width as usize)
+            .and_then(|face| face.checked_mul(6))
+            .ok_or(SnapshotError::DimensionMismatch { width, height })
+    }
+
+    fn from_snapshot_values(width: u64, _: u64, values: Vec<T>) -> Self {
+        Self {
+            size: width as usize,
+            data: values,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{snapshot::Snapshot, sphere::{CubeSphereGrid, DynCubeSphereGrid, DynRectangleSphereGrid, RectangleSphereGrid}, SurfaceGrid, StaticSurfaceGrid};
+
+    #[test]
+    fn test_snapshot_values_move_into_storage() {
+        let values = vec![1.5; 200];
+        let pointer = values.as_ptr();
+        let grid: RectangleSphereGrid<f64, 20, 10> = Snapshot::from_snapshot_values(20, 10, values);
+
+        assert_eq!(pointer, grid.data.as_ptr());
+
+        let values = vec![1.5; 6 * 4 * 4];
+        let pointer = values.as_ptr();
+        let grid: CubeSphereGrid<f64, 4> = Snapshot::from_snapshot_values(4, 4, values);
+
+        assert_eq!(pointer, grid.data.as_ptr());
+
+        let values = vec![1.5; 200];
+        let pointer = values.as_ptr();
+        let grid: DynRectangleSphereGrid<f64> = Snapshot::from_snapshot_values(20, 10, values);
+
+        assert_eq!(pointer, grid.data.as_ptr());
+
+        let values = vec![1.5; 6 * 4 * 4];
+        let pointer = values.as_ptr();
+        let grid: DynCubeSphereGrid<f64> = Snapshot::from_snapshot_values(4, 4, values);
+
+        assert_eq!(pointer, grid.data.as_ptr());
+    }
+
+    #[test]
+    fn test_read_snapshot_fills_storage_exactly() {
+        let grid: CubeSphereGrid<u64, 300> = CubeSphereGrid::from_fn(|point| point.x() as u64);
+
+        let mut bytes = Vec::new();
+        grid.write_snapshot(&mut bytes).unwrap();
+
+        let read: CubeSphereGrid<u64, 300> = Snapshot::read_snapshot(&bytes[..]).unwrap();
+
+        assert_eq!(6 * 300 * 300, read.data.capacity());
+        assert!(grid.iter().eq(read.iter()));
+    }
+}
@@ -0,0 +1,130 @@
+//! A module for the storage that holds the values of grids.
+//!
+//! Rectangle and cube sphere grids store their values in a `Storage` in the order given by
+//! `IndexedPoint`.
+//! By default this is a `Vec`, but with the `mmap` feature enabled grids can also be stored in a
+//! file that is mapped into memory with `MmapStorage`.
+
+use std::ops::{Deref, DerefMut};
+
+use rayon::prelude::*;
+
+use crate::snapshot::Pod;
+
+#[cfg(feature = "mmap")]
+mod mmap;
+
+#[cfg(feature = "mmap")]
+pub use mmap::{MapMode, MmapStorage};
+
+/// A contiguous block of values held by a grid.
+///
+/// # Type Parameters
+/// - `T` - The type of the values.
+pub trait Storage<T> : Deref<Target = [T]> + DerefMut {
+    /// Creates new storage by calling the specified function with the index of each value.
+    ///
+    /// - `len` - The number of values.
+    /// - `f` - The function to apply.
+    fn from_fn<F: FnMut(usize) -> T>(len: usize, f: F) -> Self;
+
+    /// Creates new storage by calling the specified function in parallel with the index of each
+    /// value.
+    ///
+    /// - `len` - The number of values.
+    /// - `f` - The function to apply.
+    fn from_fn_par<F: Fn(usize) -> T + Send + Sync>(len: usize, f: F) -> Self where T: Send + Sync;
+
+    /// Creates new storage holding the specified values.
+    ///
+    /// - `values` - The values to store.
+    fn from_vec(values: Vec<T>) -> Self;
+
+    /// Moves the values out of this storage.
+    fn into_vec(self) -> Vec<T>;
+}
+
+impl <T> Storage<T> for Vec<T> {
+    fn from_fn<F: FnMut(usize) -> T>(len: usize, f: F) -> Self {
+        (0..len).map(f).collect()
+    }
+
+    fn from_fn_par<F: Fn(usize) -> T + Send + Sync>(len: usize, f: F) -> Self where T: Send + Sync {
+        (0..len).into_par_iter().map(f).collect()
+    }
+
+    fn from_vec(values: Vec<T>) -> Self {
+        values
+    }
+
+    fn into_vec(self) -> Vec<T> {
+        self
+    }
+}
+
+/// A value whose bytes in memory are exactly the bytes written by `Pod` in the native
+/// endianness.
+///
+/// # Safety
+/// Implementing types must have no padding, must be valid for every possible pattern of bytes and
+/// must have a size of `Pod::SIZE` and an alignment of at most 16.
+pub unsafe trait Plain: Pod {}
+
+/// Implements `Plain` for number types.
+macro_rules! impl_plain {
+    ($($t:ty),*) => {
+        $(
+            unsafe impl Plain for $t {}
+        )*
+    };
+}
+
+impl_plain!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+unsafe impl <T: Plain, const N: usize> Plain for [T; N] where [T; N]: Default {}
+
+#[cfg(test)]
+mod test {
+    use super::Storage;
+
+    #[test]
+    fn test_vec_storage_from_fn() {
+        let storage: Vec<usize> = Storage::from_fn(5, |i| i * 2);
+
+        assert_eq!(vec![0, 2, 4, 6, 8], storage);
+        assert_eq!(storage, <Vec<usize> as Storage<usize>>::from_fn_par(5, |i| i * 2));
+    }
+
+    #[test]
+    fn test_vec_storage_from_vec_keeps_allocation() {
+        let values = vec![1u32, 2, 3];
+        let pointer = values.as_ptr();
+
+        let storage: Vec<u32> = Storage::from_vec(values);
+
+        assert_eq!(pointer, storage.as_ptr());
+
+        let values = storage.into_vec();
+
+        assert_eq!(pointer, values.as_ptr());
+    }
+
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn test_mmap_storage_anonymous() {
+        use super::MmapStorage;
+
+        let mut storage: MmapStorage<[f32; 3]> = Storage::from_fn(7, |i| [i as f32; 3]);
+        storage[3][1] = -1.0;
+
+        assert_eq!(7, storage.len());
+        assert_eq!([3.0, -1.0, 3.0], storage[3]);
+        assert_eq!(storage, MmapStorage::from_fn_par(7, |i| if i == 3 { [3.0, -1.0, 3.0] } else { [i as f32; 3] }));
+        assert_eq!(6.0, storage.into_vec()[6][2]);
+        assert_eq!(&[1, 2, 3], &*MmapStorage::from_vec(vec![1u16, 2, 3]));
+
+        let empty: MmapStorage<u8> = Storage::from_fn(0, |_| 0);
+
+        assert!(empty.is_empty());
+    }
+}
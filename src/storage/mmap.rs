@@ -0,0 +1,221 @@
+//! Storage in files that are mapped into memory.
+
+use std::{fmt::{self, Debug, Formatter}, fs::OpenOptions, io, marker::PhantomData, mem, ops::{Deref, DerefMut}, path::Path, slice};
+
+use memmap2::{MmapMut, MmapOptions};
+use rayon::prelude::*;
+
+use crate::snapshot::{Endianness, GridKind, Header, SnapshotError, HEADER_SIZE};
+
+use super::{Plain, Storage};
+
+/// The way that a file is mapped into memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MapMode {
+    /// Changes are written to the file and seen by every other process that maps it.
+    #[default]
+    Shared,
+    /// The file is opened read only and changes are kept in memory private to this mapping.
+    /// Any number of processes can map the same file in this way at once.
+    Private,
+}
+
+/// Storage for the values of a grid in a snapshot file that is mapped into memory.
+///
+/// The operating system loads the parts of the file that are used on demand, so grids larger than
+/// the available memory can be opened.
+/// Storage created by `Storage::from_fn` is not backed by a file.
+///
+/// # Type Parameters
+/// - `T` - The type of the values.
+pub struct MmapStorage<T: Plain> {
+    /// The mapped memory.
+    map: MmapMut,
+    /// The offset of the first value in bytes.
+    offset: usize,
+    /// The number of values.
+    len: usize,
+    /// The type of the values.
+    value: PhantomData<T>,
+}
+
+impl <T: Plain> MmapStorage<T> {
+    /// Wraps mapped memory holding values.
+    ///
+    /// - `map` - The mapped memory.
+    /// - `offset` - The offset of the first value in bytes.
+    /// - `len` - The number of values.
+    fn from_map(map: MmapMut, offset: usize, len: usize) -> Self {
+        assert!(map.len() >= offset + len * T::SIZE, "The mapping is too small for its values");
+        assert_eq!(0, map[offset..].as_ptr().align_offset(mem::align_of::<T>()), "The values are not aligned");
+
+        Self {
+            map,
+            offset,
+            len,
+            value: PhantomData,
+        }
+    }
+
+    /// Creates storage in memory that is not backed by a file.
+    ///
+    /// - `len` - The number of values.
+    fn anonymous(len: usize) -> io::Result<Self> {
+        let size = len.checked_mul(T::SIZE)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "The grid is too large to map"))?;
+
+        // Empty mappings are not allowed.
+        Ok(Self::from_map(MmapMut::map_anon(size.max(1))?, 0, len))
+    }
+
+    /// Creates a snapshot file filled with zeros and maps it into memory.
+    ///
+    /// # Safety
+    /// The file must not be truncated or modified by other processes while it is mapped.
+    ///
+    /// - `path` - The path of the file.
+    /// - `kind` - The kind of grid.
+    /// - `width` - The width of the grid.
+    /// - `height` - The height of the grid.
+    /// - `len` - The number of values.
+    pub(crate) unsafe fn create(path: &Path, kind: GridKind, width: usize, height: usize, len: usize) -> io::Result<Self> {
+        let header = Header {
+            kind,
+            endianness: Endianness::NATIVE,
+            element_size: T::SIZE,
+            width: width as u64,
+            height: height as u64,
+        };
+
+        let size = len.checked_mul(T::SIZE)
+            .and_then(|size| size.checked_add(HEADER_SIZE))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "The grid is too large to map"))?;
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+
+        file.set_len(size as u64)?;
+
+        let mut map = MmapOptions::new().map_mut(&file)?;
+        map[..HEADER_SIZE].copy_from_slice(&header.to_bytes()?);
+
+        Ok(Self::from_map(map, HEADER_SIZE, len))
+    }
+
+    /// Maps an existing snapshot file into memory.
+    ///
+    /// # Safety
+    /// The file must not be truncated or modified by other processes while it is mapped.
+    ///
+    /// - `path` - The path of the file.
+    /// - `mode` - The way that the file is mapped.
+    /// - `kind` - The kind of grid.
+    /// - `width` - The width of the grid.
+    /// - `height` - The height of the grid.
+    /// - `len` - The number of values.
+    pub(crate) unsafe fn open(
+                path: &Path, mode: MapMode, kind: GridKind, width: usize, height: usize, len: usize
+            ) -> Result<Self, SnapshotError> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(mode == MapMode::Shared)
+            .open(path)?;
+
+        let map = match mode {
+            MapMode::Shared => MmapOptions::new().map_mut(&file)?,
+            MapMode::Private => MmapOptions::new().map_copy(&file)?,
+        };
+
+        let truncated = || io::Error::new(io::ErrorKind::UnexpectedEof, "The snapshot is shorter than its grid");
+
+        let bytes = map.get(..HEADER_SIZE).ok_or_else(truncated)?;
+        let header = Header::from_bytes(bytes.try_into().expect("The header has a fixed size"))?
+            .check(kind, T::SIZE)?;
+
+        if header.width != width as u64 || header.height != height as u64 {
+            return Err(SnapshotError::DimensionMismatch { width: header.width, height: header.height });
+        }
+
+        if header.endianness != Endianness::NATIVE {
+            return Err(SnapshotError::ForeignEndianness(header.endianness));
+        }
+
+        if map.len() < HEADER_SIZE + len * T::SIZE {
+            return Err(truncated().into());
+        }
+
+        Ok(Self::from_map(map, HEADER_SIZE, len))
+    }
+
+    /// Writes any changes to the file.
+    /// This does nothing for storage that is not backed by a file or that is mapped with
+    /// `MapMode::Private`.
+    pub fn flush(&self) -> io::Result<()> {
+        self.map.flush()
+    }
+}
+
+impl <T: Plain> Deref for MmapStorage<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The mapping holds `len` aligned values after `offset` and every pattern of bytes
+        // is a valid `T`.
+        unsafe { slice::from_raw_parts(self.map[self.offset..].as_ptr().cast(), self.len) }
+    }
+}
+
+impl <T: Plain> DerefMut for MmapStorage<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: As for `deref`, and the mapping is borrowed mutably.
+        unsafe { slice::from_raw_parts_mut(self.map[self.offset..].as_mut_ptr().cast(), self.len) }
+    }
+}
+
+impl <T: Plain> Storage<T> for MmapStorage<T> {
+    fn from_fn<F: FnMut(usize) -> T>(len: usize, mut f: F) -> Self {
+        let mut storage = Self::anonymous(len).expect("Failed to map memory for a grid");
+
+        for (i, value) in storage.iter_mut().enumerate() {
+            *value = f(i);
+        }
+
+        storage
+    }
+
+    fn from_fn_par<F: Fn(usize) -> T + Send + Sync>(len: usize, f: F) -> Self where T: Send + Sync {
+        let mut storage = Self::anonymous(len).expect("Failed to map memory for a grid");
+
+        storage.par_iter_mut().enumerate().for_each(|(i, value)| *value = f(i));
+
+        storage
+    }
+
+    fn from_vec(values: Vec<T>) -> Self {
+        let mut storage = Self::anonymous(values.len()).expect("Failed to map memory for a grid");
+
+        storage.copy_from_slice(&values);
+
+        storage
+    }
+
+    fn into_vec(self) -> Vec<T> {
+        self.to_vec()
+    }
+}
+
+impl <T: Plain + Debug> Debug for MmapStorage<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl <T: Plain + PartialEq> PartialEq for MmapStorage<T> {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
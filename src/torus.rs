@@ -0,0 +1,363 @@
+//! A module containing grids wrapped around tori.
+
+use std::{f64::consts::PI, ops::{Index, IndexMut}, vec};
+
+use itertools::Itertools;
+use rayon::prelude::*;
+use static_array::HeapArray2D;
+
+use crate::{GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+/// A grid wrapped around a torus.
+///
+/// Moving off any edge of the grid wraps around to the opposite edge making this the standard
+/// periodic domain for cellular automata.
+///
+/// # Type Parameters
+/// - `T` - The type of data that the grid holds.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid. This is the number of cells around the central axis of the torus.
+/// - `H` - The height of the grid. This is the number of cells around the tube of the torus.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TorusGrid<T, const W: usize, const H: usize> {
+    /// The data held in this grid.
+    data: HeapArray2D<T, W, H>,
+}
+
+impl <T, const W: usize, const H: usize> SurfaceGrid<T> for TorusGrid<T, W, H> {
+    type Point = TorusPoint<W, H>;
+
+    fn same_size_from_fn<F: FnMut(&Self::Point) -> T>(&self, f: F) -> Self {
+        Self::from_fn(f)
+    }
+
+    fn same_size_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&self, f: F) -> Self where T: Send + Sync {
+        Self::from_fn_par(f)
+    }
+
+    fn set_from_fn<F: FnMut(&Self::Point) -> T>(&mut self, mut f: F) {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| TorusPoint::new(x as u32, y as u32))
+            .for_each(|point| self[point] = f(&point))
+    }
+
+    fn set_from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(&mut self, f: F) where T: Send + Sync {
+        self.data.iter_mut().enumerate().par_bridge().for_each(|(y, subarray)| {
+            for (x, value) in subarray.iter_mut().enumerate() {
+                *value = f(&TorusPoint::new(x as u32, y as u32));
+            }
+        })
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (Self::Point, &'a T)> where T: 'a {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| (TorusPoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn par_iter<'a>(&'a self) -> impl ParallelIterator<Item = (Self::Point, &'a T)> where T: 'a + Send + Sync {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| (TorusPoint::new(x as u32, y as u32), &self.data[y][x]))
+    }
+
+    fn points(&self) -> impl Iterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .map(|(y, x)| TorusPoint::new(x as u32, y as u32))
+    }
+
+    fn par_points(&self) -> impl ParallelIterator<Item = Self::Point> {
+        (0..H).cartesian_product(0..W)
+            .par_bridge()
+            .map(|(y, x)| TorusPoint::new(x as u32, y as u32))
+    }
+}
+
+impl <T, const W: usize, const H: usize> StaticSurfaceGrid<T> for TorusGrid<T, W, H> {
+    fn from_fn<F: FnMut(&Self::Point) -> T>(mut f: F) -> Self {
+        Self {
+            data: HeapArray2D::from_fn(|y, x| f(&TorusPoint::new(x as u32, y as u32)))
+        }
+    }
+
+    fn from_fn_par<F: Fn(&Self::Point) -> T + Send + Sync>(f: F) -> Self where T: Send + Sync {
+        Self {
+            data: HeapArray2D::from_fn_par(|y, x| f(&TorusPoint::new(x as u32, y as u32)))
+        }
+    }
+}
+
+impl <T, const W: usize, const H: usize> Index<TorusPoint<W, H>> for TorusGrid<T, W, H> {
+    type Output = T;
+
+    fn index(&self, index: TorusPoint<W, H>) -> &Self::Output {
+        &self.data[index.y as usize][index.x as usize]
+    }
+}
+
+impl <T, const W: usize, const H: usize> IndexMut<TorusPoint<W, H>> for TorusGrid<T, W, H> {
+    fn index_mut(&mut self, index: TorusPoint<W, H>) -> &mut Self::Output {
+        &mut self.data[index.y as usize][index.x as usize]
+    }
+}
+
+impl <T, const W: usize, const H: usize> IntoIterator for TorusGrid<T, W, H> {
+    type Item = (TorusPoint<W, H>, T);
+
+    type IntoIter = vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let data: Vec<_> = self.data.into_iter()
+            .enumerate()
+            .flat_map(|(y, subarray)| subarray.into_iter()
+                      .enumerate()
+                      .map(move |(x, value)| (TorusPoint::new(x as u32, y as u32), value))
+                      )
+            .collect();
+
+        data.into_iter()
+    }
+}
+
+/// A point on a `TorusGrid`.
+///
+/// # Constant Parameters
+/// - `W` - The width of the grid.
+/// - `H` - The height of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TorusPoint<const W: usize, const H: usize> {
+    /// The X position in the grid.
+    x: u32,
+    /// The Y position in the grid.
+    y: u32,
+}
+
+impl <const W: usize, const H: usize> TorusPoint<W, H> {
+    fn new(x: u32, y: u32) -> Self {
+        Self {
+            x: x.rem_euclid(W as u32),
+            y: y.rem_euclid(H as u32),
+        }
+    }
+
+    /// Gets the position of the point in 3D space on a torus with the specified radii.
+    ///
+    /// The central axis of the torus is the Y axis.
+    ///
+    /// - `major_radius` - The distance from the central axis to the centre of the tube.
+    /// - `minor_radius` - The radius of the tube.
+    pub fn torus_position(&self, major_radius: f64, minor_radius: f64) -> (f64, f64, f64) {
+        let around_axis = self.x as f64 / W as f64 * PI * 2.0;
+        let around_tube = self.y as f64 / H as f64 * PI * 2.0;
+
+        let radius = major_radius + minor_radius * around_tube.cos();
+
+        let x = radius * around_axis.sin();
+        let y = minor_radius * around_tube.sin();
+        let z = radius * around_axis.cos();
+
+        (x, y, z)
+    }
+}
+
+impl <const W: usize, const H: usize> GridPoint for TorusPoint<W, H> {
+    fn up(&self) -> Self {
+        Self {
+            x: self.x,
+            y: (self.y as i64 - 1).rem_euclid(H as i64) as u32,
+        }
+    }
+
+    fn down(&self) -> Self {
+        Self {
+            x: self.x,
+            y: (self.y + 1).rem_euclid(H as u32),
+        }
+    }
+
+    fn left(&self) -> Self {
+        Self {
+            x: (self.x as i64 - 1).rem_euclid(W as i64) as u32,
+            y: self.y,
+        }
+    }
+
+    fn right(&self) -> Self {
+        Self {
+            x: (self.x + 1).rem_euclid(W as u32),
+            y: self.y,
+        }
+    }
+
+    /// Gets the position of the point in 3D space.
+    ///
+    /// The point is placed on a torus with a major radius of `scale` and a minor radius of half of
+    /// `scale`. Use `torus_position` to choose the radii separately.
+    ///
+    /// - `scale` - The scale of the 3D object.
+    fn position(&self, scale: f64) -> (f64, f64, f64) {
+        self.torus_position(scale, scale / 2.0)
+    }
+}
+
+impl <const W: usize, const H: usize> IndexedPoint for TorusPoint<W, H> {
+    const COUNT: usize = W * H;
+
+    fn to_index(&self) -> usize {
+        self.y as usize * W + self.x as usize
+    }
+
+    fn from_index(index: usize) -> Self {
+        assert!(index < Self::COUNT, "The index is outside the grid");
+
+        Self::new((index % W) as u32, (index / W) as u32)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use approx::assert_relative_eq;
+
+    use crate::{test::assert_indexed_points, GridPoint, IndexedPoint, SurfaceGrid, StaticSurfaceGrid};
+
+    use super::{TorusGrid, TorusPoint};
+
+    #[test]
+    fn test_torus_point_up_middle() {
+        let point: TorusPoint<10, 10> = TorusPoint::new(3, 4);
+
+        assert_eq!(TorusPoint::new(3, 3), point.up());
+    }
+
+    #[test]
+    fn test_torus_point_up_top() {
+        let point: TorusPoint<10, 10> = TorusPoint::new(3, 0);
+
+        assert_eq!(TorusPoint::new(3, 9), point.up());
+    }
+
+    #[test]
+    fn test_torus_point_down_middle() {
+        let point: TorusPoint<10, 10> = TorusPoint::new(3, 4);
+
+        assert_eq!(TorusPoint::new(3, 5), point.down());
+    }
+
+    #[test]
+    fn test_torus_point_down_bottom() {
+        let point: TorusPoint<10, 10> = TorusPoint::new(3, 9);
+
+        assert_eq!(TorusPoint::new(3, 0), point.down());
+    }
+
+    #[test]
+    fn test_torus_point_left_middle() {
+        let point: TorusPoint<10, 10> = TorusPoint::new(5, 5);
+
+        assert_eq!(TorusPoint::new(4, 5), point.left());
+    }
+
+    #[test]
+    fn test_torus_point_left_left() {
+        let point: TorusPoint<10, 10> = TorusPoint::new(0, 5);
+
+        assert_eq!(TorusPoint::new(9, 5), point.left());
+    }
+
+    #[test]
+    fn test_torus_point_right_middle() {
+        let point: TorusPoint<10, 10> = TorusPoint::new(5, 5);
+
+        assert_eq!(TorusPoint::new(6, 5), point.right());
+    }
+
+    #[test]
+    fn test_torus_point_right_right() {
+        let point: TorusPoint<10, 10> = TorusPoint::new(9, 5);
+
+        assert_eq!(TorusPoint::new(0, 5), point.right());
+    }
+
+    #[test]
+    fn test_torus_point_up_loop() {
+        let start: TorusPoint<4, 3> = TorusPoint::new(1, 2);
+
+        assert_eq!(start, start.up().up().up());
+    }
+
+    #[test]
+    fn test_torus_point_left_loop() {
+        let start: TorusPoint<4, 3> = TorusPoint::new(1, 2);
+
+        assert_eq!(start, start.left().left().left().left());
+    }
+
+    #[test]
+    fn test_torus_point_inverse_edge() {
+        let start: TorusPoint<4, 3> = TorusPoint::new(0, 0);
+
+        assert_eq!(start, start.up().down());
+        assert_eq!(start, start.down().up());
+        assert_eq!(start, start.left().right());
+        assert_eq!(start, start.right().left());
+    }
+
+    #[test]
+    fn test_torus_from_fn() {
+        let grid: TorusGrid<u32, 20, 10> = TorusGrid::from_fn(|point| point.x + point.y);
+
+        assert_eq!(12, grid[TorusPoint::new(5, 7)]);
+    }
+
+    #[test]
+    fn test_torus_point_new_wraps() {
+        let point: TorusPoint<20, 10> = TorusPoint::new(25, 13);
+
+        assert_eq!(TorusPoint::new(5, 3), point);
+    }
+
+    #[test]
+    fn test_torus_from_neighbours_wrap() {
+        let grid: TorusGrid<u32, 20, 10> = TorusGrid::from_fn(|point| point.x);
+
+        let grid2 = grid.map_neighbours(|current, up, down, left, right| current + up + down + left + right);
+
+        assert_eq!(20, grid2[TorusPoint::new(0, 0)]);
+    }
+
+    #[test]
+    fn test_torus_position_outer() {
+        let point: TorusPoint<8, 8> = TorusPoint::new(0, 0);
+
+        let (x, y, z) = point.torus_position(3.0, 1.0);
+
+        assert_relative_eq!(0.0, x);
+        assert_relative_eq!(0.0, y);
+        assert_relative_eq!(4.0, z);
+    }
+
+    #[test]
+    fn test_torus_position_inner() {
+        let point: TorusPoint<8, 8> = TorusPoint::new(2, 4);
+
+        let (x, y, z) = point.torus_position(3.0, 1.0);
+
+        assert_relative_eq!(2.0, x);
+        assert_relative_eq!(0.0, y, epsilon = 1e-12);
+        assert_relative_eq!(0.0, z, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_torus_position_scale() {
+        let point: TorusPoint<8, 8> = TorusPoint::new(0, 2);
+
+        assert_eq!(point.torus_position(2.0, 1.0), point.position(2.0));
+    }
+
+    #[test]
+    fn test_torus_indexed_points() {
+        assert_eq!(35, TorusPoint::<7, 5>::COUNT);
+
+        assert_indexed_points(&TorusGrid::<(), 7, 5>::default());
+    }
+}